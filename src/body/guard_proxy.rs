@@ -5,6 +5,45 @@
 
 use crate::body::topology::{HomologicalGuard, TopologicalSignature};
 use nalgebra::DVector;
+use serde::{Deserialize, Serialize};
+
+/// 惰性卫士配置 (Guard Configuration)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GuardConfig {
+    /// Vietoris-Rips 复形的连边尺度 epsilon
+    pub epsilon: f64,
+    /// 收敛阈值：几何误差低于此值时才值得审查
+    pub convergence_threshold: f64,
+    /// 曲率风险等级：局部 Ricci 曲率低于此值时才值得审查
+    pub curvature_risk_level: f64,
+}
+
+impl Default for GuardConfig {
+    fn default() -> Self {
+        Self {
+            // epsilon = 0.6 是一个经验值，用于定义连通性的尺度
+            epsilon: 0.6,
+            
+            // 几何误差 < 0.1 通常意味着 Sinkhorn Distance 已经非常小
+            convergence_threshold: 0.1, 
+            
+            // Ricci 曲率 < -0.8 意味着极强的发散性 (Tree-like structure)
+            curvature_risk_level: -0.8, 
+        }
+    }
+}
+
+/// 卫士的审查结论
+#[derive(Debug, Clone, PartialEq)]
+pub struct GuardVerdict {
+    /// 是否否决候选解
+    pub veto: bool,
+    /// 否决时的能量惩罚
+    pub penalty: f64,
+    /// 同调检查的拓扑签名；审查未被触发 (快速路径) 时为 None
+    pub signature: Option<TopologicalSignature>,
+}
 
 /// 惰性同调卫士 (Lazy Homology Guard)
 /// 
@@ -30,17 +69,16 @@ pub struct LazyGuard {
 }
 
 impl LazyGuard {
-    /// 初始化惰性卫士
+    /// 初始化惰性卫士 (默认配置)
     pub fn new() -> Self {
+        Self::with_config(GuardConfig::default())
+    }
+
+    pub fn with_config(config: GuardConfig) -> Self {
         Self {
-            // epsilon = 0.6 是一个经验值，用于定义连通性的尺度
-            inner: HomologicalGuard::new(0.6),
-            
-            // 几何误差 < 0.1 通常意味着 Sinkhorn Distance 已经非常小
-            convergence_threshold: 0.1, 
-            
-            // Ricci 曲率 < -0.8 意味着极强的发散性 (Tree-like structure)
-            curvature_risk_level: -0.8, 
+            inner: HomologicalGuard::new(config.epsilon),
+            convergence_threshold: config.convergence_threshold,
+            curvature_risk_level: config.curvature_risk_level,
         }
     }
 
@@ -49,7 +87,7 @@ impl LazyGuard {
     /// # 参数
     /// * `candidate_cloud`: 候选状态点云 (投影后的几何坐标)
     /// * `geometric_residual`: 当前状态与目标状态的几何距离 (Sinkhorn Divergence)
-    /// * `local_curvature`: 当前所在流形区域的离散 Ricci 曲率
+    /// * `local_curvature`: 当前所在流形区域的 Ollivier-Ricci 曲率 (与 `curvature_risk_level` 同一尺度)
     /// 
     /// # 返回
     /// * `(bool, f64)`: (是否否决, 惩罚值)
@@ -57,14 +95,30 @@ impl LazyGuard {
     ///   - (true, penalty): 否决，并返回高额惩罚值
     pub fn inspect(
         &self,
-        candidate_cloud: &Vec<DVector<f64>>,
+        candidate_cloud: &[DVector<f64>],
         geometric_residual: f64,
         local_curvature: f64
     ) -> (bool, f64) {
+        let verdict = self.review(candidate_cloud, geometric_residual, local_curvature);
+        (verdict.veto, verdict.penalty)
+    }
+
+    /// 几何残差是否低到值得审查 (诱惑条件)。只有此时 `local_curvature` 才会被读取
+    pub fn is_tempting(&self, geometric_residual: f64) -> bool {
+        geometric_residual < self.convergence_threshold
+    }
+
+    /// 与 `inspect` 相同，但同时返回同调检查的拓扑签名 (若检查被触发)
+    pub fn review(
+        &self,
+        candidate_cloud: &[DVector<f64>],
+        geometric_residual: f64,
+        local_curvature: f64
+    ) -> GuardVerdict {
         // Condition A: 诱惑 (Temptation)
         // 几何残差很低，优化器非常自信地认为它找到了真理。
         // 只有在 "看起来是对的" 时候，我们才担心它是 "伪真理"。
-        let is_tempting = geometric_residual < self.convergence_threshold;
+        let is_tempting = self.is_tempting(geometric_residual);
 
         // Condition B: 风险 (Risk)
         // 负曲率极高，说明此处地形极其复杂，可能有逻辑死循环、纽结或多义性分支。
@@ -73,8 +127,6 @@ impl LazyGuard {
 
         // 惰性触发：只有当 "诱惑" 与 "风险" 叠加时，才启动昂贵的同调检查
         if is_tempting && is_risky {
-            // println!("[Guard] SUSPICION DETECTED (Res={:.4}, Kappa={:.2}). Running Homology Check...", geometric_residual, local_curvature);
-            
             // [SLOW PATH] 启动真正的代数拓扑计算
             let signature = self.inner.compute_betti_numbers(candidate_cloud);
 
@@ -84,19 +136,28 @@ impl LazyGuard {
             // Betti-1 > 0 意味着逻辑流形中存在不可收缩的闭环 (Non-contractible Cycle)。
             // 在因果逻辑中，这通常对应于 "循环论证" (Circular Reasoning) 或 "自指悖论"。
             // 真正的真理应当是单连通的 (Simply Connected)，或者是平凡的拓扑结构。
-            if signature.betti_1 > 0 {
-                // println!("[Guard] VETO: Topological Hole Detected (Betti-1={}). Rejecting Pseudo-Solution.", signature.betti_1);
-                
-                // 强力否决：返回极高的能量惩罚，迫使 VAPO 优化器立刻逃离该区域
-                return (true, 100.0); 
-            } else {
-                 // 虽然很危险，但经查证拓扑是清白的
-                 // println!("[Guard] PASSED: Topology is clean.");
-            }
+            //
+            // 强力否决：返回极高的能量惩罚，迫使 VAPO 优化器立刻逃离该区域
+            let veto = signature.betti_1 > 0;
+            return GuardVerdict {
+                veto,
+                penalty: if veto { 100.0 } else { 0.0 },
+                signature: Some(signature),
+            };
         }
 
         // [FAST PATH] 默认放行
         // 大多数时候我们走这里，零开销
-        (false, 0.0)
+        GuardVerdict {
+            veto: false,
+            penalty: 0.0,
+            signature: None,
+        }
+    }
+}
+
+impl Default for LazyGuard {
+    fn default() -> Self {
+        Self::new()
     }
 }
@@ -9,10 +9,17 @@
 //! 
 //! 2. Adapter (适配):
 //!    - 将精确投影的熵转化为具体的逻辑门操作 (ProofAction)。
+//!
+//! 3. Modular (Theta 特征):
+//!    - 连接理想的 Theta 级数，供 StateLifter 进行跨宇宙比较。
 
 pub mod adapter;
+pub mod guard_proxy;
+pub mod modular;
 pub mod projection;
+pub mod topology;
 
 // Re-export for easier access
 pub use adapter::{Adapter, ProofAction, LogicOp};
+pub use modular::{FeatureProjector, ThetaNormalization};
 pub use projection::Projector;
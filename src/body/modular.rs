@@ -0,0 +1,232 @@
+// Copyright (c) 2025 M-Patek
+// Part of the Evolver Project
+//
+// "Coordinates are an accident of the universe; the theta series is its fingerprint."
+
+use nalgebra::DVector;
+
+use crate::soul::algebra::AlgebraicState;
+use crate::soul::hecke::HECKE_PRIMES;
+use crate::soul::lattice::QuaternionLattice;
+
+/// Theta 系数的归一化方式 (Normalization Variant)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThetaNormalization {
+    /// 原始计数 a_n = #{x ∈ I : N(x) = n·N(I)}。量级随 p 变化 (~ n/p)，不适合跨宇宙比较。
+    Raw,
+    /// 概率形状：a_n / Σ_{m>=1} a_m。只保留表示数的分布形状。
+    Probability,
+    /// Eisenstein 归一化：a_n / e_n，其中 e_n = 24/(p-1) · Σ_{d|n, p∤d} d。
+    /// e_n 只被借用为一个随 p 变化的经验尺度，用来消去表示数主项对 p 的依赖：
+    /// 工作代数与工作序都不是使 e_n 成为平均值的那一个 (见 `FeatureProjector`)，
+    /// 因此比值不能解读为尖点形式的贡献。
+    Eisenstein,
+}
+
+/// 连接理想的级的上限：更高的 M 部分被截断，保证 Gram 矩阵与枚举的规模有界
+const MAX_CONNECTING_LEVEL: i128 = 1 << 12;
+
+/// 理想范数形式的 Theta 级数
+/// θ_I(q) = Σ_n a_n q^n，  a_n = #{x ∈ I : N(x)/N(I) = n}
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThetaSeries {
+    /// 连接理想的级 M (I = qO + M·O)
+    pub level: i64,
+    /// 理想的范数 N(I)
+    pub ideal_norm: i128,
+    /// 系数 a_0, a_1, ..., a_N (a_0 = 1)
+    pub coefficients: Vec<u64>,
+}
+
+/// Theta 特征投影仪 (Theta Feature Projector)
+///
+/// 为 StateLifter 提供 "坐标无关" 的特征空间。
+///
+/// 对于状态 S 的连接理想 I = SO + M·O (M 取 N(S) 在给定素数处的部分)，取二次型 N(x)/N(I) 的 Theta 级数。
+/// 路径由右乘生成元构成，而 Hecke 字母表把 ℓ-邻居定义为右理想 αO + ℓO，所以 SO + M·O 正是路径在 M 处抵达的右理想；
+/// 主理想 SO 本身总在平凡类中，必须带上 M·O。右乘单位 S -> S·u 不改变 I，因此特征与 `canonical_hash` 的等价一致。
+///
+/// 这里不声称 θ_I 是某个已知级上的模形式：p ≡ 1 (mod 4) 时代数 (-1, -p) 在 p 处分裂、只在 {2, ∞} 处分歧，
+/// 工作序 Z<1, i, j, k> 在 2 处也不是极大的。特征应视为经验的理想类不变量：
+/// 它们只依赖于 I 的类而不依赖四元数坐标，因此两个宇宙中的状态可以在这里被比较。
+#[derive(Debug, Clone)]
+pub struct FeatureProjector {
+    /// 每个理想计算的 Theta 系数个数 N (a_1 .. a_N)
+    num_coefficients: usize,
+    /// 连接理想所在的素数集合。每个素数 ℓ 以 M = ℓ^{v_ℓ(N(S))} 贡献一组特征。
+    /// 默认与 Hecke 字母表相同 (ADR-006)：生成元的余因子 m 可以含 2，
+    /// 所以 2 处的连接级记录的是余因子而不是路径。
+    levels: Vec<i64>,
+    /// 输出的归一化变体，按顺序拼接
+    normalizations: Vec<ThetaNormalization>,
+}
+
+impl FeatureProjector {
+    pub fn new() -> Self {
+        Self {
+            num_coefficients: 16,
+            levels: HECKE_PRIMES.iter().map(|&ell| ell as i64).collect(),
+            normalizations: vec![ThetaNormalization::Eisenstein, ThetaNormalization::Probability],
+        }
+    }
+
+    pub fn coefficients(mut self, n: usize) -> Self {
+        self.num_coefficients = n.max(1);
+        self
+    }
+
+    pub fn levels(mut self, levels: Vec<i64>) -> Self {
+        self.levels = levels;
+        self
+    }
+
+    pub fn normalizations(mut self, normalizations: Vec<ThetaNormalization>) -> Self {
+        self.normalizations = normalizations;
+        self
+    }
+
+    /// 特征向量的维数
+    pub fn dimension(&self) -> usize {
+        self.levels.len() * self.normalizations.len() * self.num_coefficients
+    }
+
+    /// 状态在 `level` 处的连接级 M：N(S) 中 level 的最高次幂 (不超过 `MAX_CONNECTING_LEVEL`)。
+    /// N(S) 与 level 互素时 M = 1，对应的理想就是 O：状态没有在该素数处移动过。
+    pub fn connecting_level(&self, state: &AlgebraicState, level: i64) -> i64 {
+        let norm = state.algebra().norm(&state.value);
+        let level = level as i128;
+        let mut modulus = 1i128;
+        while level > 1 && modulus * level <= MAX_CONNECTING_LEVEL && norm % (modulus * level) == 0 {
+            modulus *= level;
+        }
+        modulus as i64
+    }
+
+    /// 计算状态在 `level` 处连接理想 I = SO + M·O 的 Theta 级数
+    pub fn theta_series(&self, state: &AlgebraicState, level: i64) -> ThetaSeries {
+        let algebra = state.algebra();
+        let level = self.connecting_level(state, level);
+        let ideal = QuaternionLattice::right_ideal(&state.value, level, &algebra);
+        let ideal_norm = ideal.norm_scale();
+        let bound = ideal_norm * self.num_coefficients as i128;
+
+        let mut coefficients = vec![0u64; self.num_coefficients + 1];
+        for (_, norm) in ideal.enumerate_short_vectors(bound) {
+            if norm % ideal_norm == 0 {
+                let n = (norm / ideal_norm) as usize;
+                if n < coefficients.len() {
+                    coefficients[n] += 1;
+                }
+            }
+        }
+
+        ThetaSeries {
+            level,
+            ideal_norm,
+            coefficients,
+        }
+    }
+
+    /// 投影：S -> R^d (坐标无关的灵魂指纹)
+    pub fn project(&self, state: &AlgebraicState) -> DVector<f64> {
+        let mut features = Vec::with_capacity(self.dimension());
+
+        for &level in &self.levels {
+            let theta = self.theta_series(state, level);
+            for norm in &self.normalizations {
+                features.extend(self.normalize(&theta, *norm, state.p));
+            }
+        }
+
+        DVector::from_vec(features)
+    }
+
+    fn normalize(&self, theta: &ThetaSeries, mode: ThetaNormalization, p: u64) -> Vec<f64> {
+        let coeffs = &theta.coefficients[1..];
+
+        match mode {
+            ThetaNormalization::Raw => coeffs.iter().map(|&a| a as f64).collect(),
+            ThetaNormalization::Probability => {
+                let total: u64 = coeffs.iter().sum();
+                if total == 0 {
+                    return vec![0.0; coeffs.len()];
+                }
+                coeffs.iter().map(|&a| a as f64 / total as f64).collect()
+            }
+            ThetaNormalization::Eisenstein => coeffs
+                .iter()
+                .enumerate()
+                .map(|(idx, &a)| a as f64 / eisenstein_coefficient(idx as u64 + 1, p))
+                .collect(),
+        }
+    }
+}
+
+impl Default for FeatureProjector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 极大序 Theta 级数的平均系数 e_n = 24/(p-1) · σ^{(p)}(n)
+/// σ^{(p)}(n) 只对与 p 互素的因子求和。
+fn eisenstein_coefficient(n: u64, p: u64) -> f64 {
+    let sigma: u64 = (1..=n).filter(|d| n.is_multiple_of(*d) && !d.is_multiple_of(p)).sum();
+    24.0 / (p.saturating_sub(1).max(1) as f64) * sigma as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::soul::algebra::Quaternion;
+    use crate::soul::hecke::HeckeMove;
+
+    #[test]
+    fn test_features_separate_states() {
+        // 根状态的所有连接理想都是 O；走一步 T_3 后 3 处的连接理想不含范数 3 的元素，a_1 消失
+        let projector = FeatureProjector::new();
+        let root = AlgebraicState::new_root(37);
+        let moved = root.apply_move(&HeckeMove::new(3, 0)).unwrap();
+
+        assert_eq!(projector.connecting_level(&root, 3), 1);
+        assert_eq!(projector.connecting_level(&moved, 3), 3);
+        assert_eq!(projector.theta_series(&root, 3).coefficients[1], 4);
+        assert_eq!(projector.theta_series(&moved, 3).coefficients[1], 0);
+        assert_ne!(projector.project(&root), projector.project(&moved));
+    }
+
+    #[test]
+    fn test_features_ignore_unit_multiples() {
+        let projector = FeatureProjector::new();
+        let state = AlgebraicState::new_root(37)
+            .apply_move(&HeckeMove::new(3, 1))
+            .and_then(|s| s.apply_move(&HeckeMove::new(5, 2)))
+            .unwrap();
+        let mut rotated = state.clone();
+        rotated.value = state.algebra().mul(&state.value, &Quaternion::new(0, 1, 0, 0));
+
+        assert_eq!(projector.project(&state), projector.project(&rotated));
+    }
+
+    #[test]
+    fn test_connecting_level_saturates_on_deep_paths() {
+        // 非回溯的 T_3 路径每一步为 N(S) 贡献一个因子 3，直到 3^7 = 2187 < MAX_CONNECTING_LEVEL < 3^8
+        let projector = FeatureProjector::new();
+        let mut state = AlgebraicState::new_root(37);
+        let mut levels = Vec::new();
+        for _ in 0..10 {
+            let alphabet = state.algebra().alphabet();
+            let backtrack = state.path_history.last().and_then(|mv| alphabet.inverse(mv));
+            let mv = (0..=3).map(|t| HeckeMove::new(3, t)).find(|mv| Some(*mv) != backtrack).unwrap();
+            state = state.apply_move(&mv).unwrap();
+            levels.push(projector.connecting_level(&state, 3));
+        }
+        assert_eq!(levels, vec![3, 9, 27, 81, 243, 729, 2187, 2187, 2187, 2187]);
+
+        // 截断后特征仍然有界且可计算
+        assert_eq!(projector.theta_series(&state, 3).level, 2187);
+        let features = projector.project(&state);
+        assert_eq!(features.len(), projector.dimension());
+        assert!(features.iter().all(|x| x.is_finite()));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::soul::algebra::IdealClass;
+use crate::soul::algebra::{IdealClass, Quaternion};
 use sha2::{Digest, Sha256};
 
 /// 投影仪 (Projector)
@@ -26,10 +26,14 @@ impl Projector {
     /// v = q / |q|
     /// 这样的映射满足 Lipschitz 连续性：状态的微小旋转导致特征的微小变化。
     pub fn project_continuous(&self, state: &IdealClass) -> Vec<f64> {
-        let q = state.value;
-        
+        self.project_quaternion(&state.value)
+    }
+
+    /// 连续投影的核心：任意四元数 q -> q / |q| ∈ S^3
+    /// 与具体的状态类型 (IdealClass / AlgebraicState) 无关。
+    pub fn project_quaternion(&self, q: &Quaternion) -> Vec<f64> {
         // 转换为浮点数
-        let vecRaw = vec![
+        let raw = [
             q.a as f64,
             q.b as f64,
             q.c as f64,
@@ -37,7 +41,7 @@ impl Projector {
         ];
 
         // 计算欧几里得范数 (L2 Norm)
-        let norm_sq: f64 = vecRaw.iter().map(|x| x * x).sum();
+        let norm_sq: f64 = raw.iter().map(|x| x * x).sum();
         let norm = norm_sq.sqrt();
 
         // 归一化投影 (避免除以零)
@@ -45,7 +49,24 @@ impl Projector {
             return vec![0.0; 4];
         }
 
-        vecRaw.iter().map(|x| x / norm).collect()
+        raw.iter().map(|x| x / norm).collect()
+    }
+
+    /// 意图投影 (Psi_intent): context -> S^3
+    /// 上下文所表达的几何意图，作为评估器的目标特征。
+    ///
+    /// 种子 `IdealClass::from_hash(context, p)` 直接哈希上下文；这里在哈希前加入域分隔前缀，
+    /// 因此意图方向与搜索起点无关 (否则起点能量为零，搜索原地收敛)。
+    pub fn project_intent(&self, context: &str) -> Vec<f64> {
+        let mut hasher = Sha256::new();
+        hasher.update(b"evolver/intent");
+        hasher.update(context.as_bytes());
+        let result = hasher.finalize();
+
+        let coord = |i: usize| {
+            i64::from_be_bytes(result[8 * i..8 * i + 8].try_into().unwrap_or([0; 8])) % 1000
+        };
+        self.project_quaternion(&Quaternion::new(coord(0), coord(1), coord(2), coord(3)))
     }
 
     /// 精确投影 (Psi_exact): S -> Z_p
@@ -56,6 +77,11 @@ impl Projector {
     /// 由于 state.value 是路径上所有算子的有序乘积，
     /// 这里的哈希值实际上是对整个因果链的数字签名。
     pub fn project_exact(&self, state: &IdealClass, time_step: u64) -> u64 {
+        self.project_exact_quaternion(&state.value, time_step)
+    }
+
+    /// 精确投影的核心：任意四元数 q 在时刻 t 的混沌哈希
+    pub fn project_exact_quaternion(&self, q: &Quaternion, time_step: u64) -> u64 {
         let mut hasher = Sha256::new();
         
         // 输入系统参数
@@ -66,10 +92,10 @@ impl Projector {
         
         // 输入四元数完整状态 (a, b, c, d)
         // 这里的微小差异会导致输出的雪崩效应
-        hasher.update(state.value.a.to_be_bytes());
-        hasher.update(state.value.b.to_be_bytes());
-        hasher.update(state.value.c.to_be_bytes());
-        hasher.update(state.value.d.to_be_bytes());
+        hasher.update(q.a.to_be_bytes());
+        hasher.update(q.b.to_be_bytes());
+        hasher.update(q.c.to_be_bytes());
+        hasher.update(q.d.to_be_bytes());
 
         let result = hasher.finalize();
 
@@ -44,7 +44,7 @@ impl HomologicalGuard {
     /// 2. 识别 2-Simplices (Triangles)
     /// 3. 构建边界算子矩阵
     /// 4. 在 GF(2) 域上进行矩阵秩的计算
-    pub fn compute_betti_numbers(&self, points: &Vec<DVector<f64>>) -> TopologicalSignature {
+    pub fn compute_betti_numbers(&self, points: &[DVector<f64>]) -> TopologicalSignature {
         let n = points.len();
         if n == 0 { 
             return TopologicalSignature { betti_0: 0, betti_1: 0 }; 
@@ -94,11 +94,7 @@ impl HomologicalGuard {
         // 4. 计算 Betti-1
         // 公式: b1 = dim(Ker d_1) - dim(Im d_2)
         // 其中 dim(Ker d_1) 即图的 Cycle Rank = |Edges| - |Vertices| + b0
-        let cycle_rank = if edge_count + b0 >= n {
-            edge_count - n + b0
-        } else {
-            0 // 防御性编程，理论上不会发生
-        };
+        let cycle_rank = (edge_count + b0).saturating_sub(n);
         
         if triangles.is_empty() {
              // 如果没有三角形来“填补”任何闭环，那么所有的图循环都是一维孔洞
@@ -129,7 +125,7 @@ impl HomologicalGuard {
         let boundary_rank = self.compute_rank_z2(&mut boundary_matrix);
         
         // 真正的同调维数 = 所有循环 - 边界循环
-        let b1 = if cycle_rank >= boundary_rank { cycle_rank - boundary_rank } else { 0 };
+        let b1 = cycle_rank.saturating_sub(boundary_rank);
 
         TopologicalSignature { betti_0: b0, betti_1: b1 }
     }
@@ -132,6 +132,12 @@ impl MathKernel {
     }
 }
 
+impl Default for MathKernel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 // Simple helper for the mock predicate
 fn is_prime_basic(n: i64) -> bool {
     if n <= 1 { return false; }
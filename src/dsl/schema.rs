@@ -1,10 +1,13 @@
 // src/dsl/schema.rs
 
-/// The schema definition for the Evolver Domain Specific Language (DSL).
-/// Defines the core structures for Logic Matrices and Constraints.
+//! The schema definition for the Evolver Domain Specific Language (DSL).
+//! Defines the core structures for Logic Matrices and Constraints.
 
+use nalgebra::{DMatrix, DVector};
 use serde::{Deserialize, Serialize};
 
+use crate::body::adapter::LogicOp;
+
 /// Represents the algebraic logic matrix operations.
 /// In Evolver, logic is treated as continuous energy functions.
 pub struct LogicMatrix;
@@ -24,6 +27,39 @@ impl LogicMatrix {
         // to detect violations more easily.
         prob_a * (1.0 - prob_b) * 10.0
     }
+
+    /// Vector form of a Boolean value: True = δ₂¹ = [1, 0]ᵀ, False = δ₂² = [0, 1]ᵀ.
+    pub fn truth_vector(value: bool) -> DVector<f64> {
+        if value {
+            DVector::from_vec(vec![1.0, 0.0])
+        } else {
+            DVector::from_vec(vec![0.0, 1.0])
+        }
+    }
+
+    /// Structure matrix M_op of a logical operator.
+    ///
+    /// Binary operators are 2×4 matrices with op(x, y) = M_op ⋉ x ⋉ y = M_op (x ⊗ y);
+    /// unary operators are 2×2 matrices with op(x) = M_op x.
+    /// - M_∧ = δ₂[1, 2, 2, 2]
+    /// - M_∨ = δ₂[1, 1, 1, 2]
+    /// - M_⊕ = δ₂[2, 1, 1, 2]
+    /// - M_¬ = δ₂[2, 1]
+    pub fn structure(op: LogicOp) -> DMatrix<f64> {
+        let columns: &[usize] = match op {
+            LogicOp::And => &[1, 2, 2, 2],
+            LogicOp::Or => &[1, 1, 1, 2],
+            LogicOp::Xor => &[2, 1, 1, 2],
+            LogicOp::Not => &[2, 1],
+            LogicOp::Identity => &[1, 2],
+        };
+        DMatrix::from_fn(2, columns.len(), |row, col| if columns[col] == row + 1 { 1.0 } else { 0.0 })
+    }
+
+    /// Whether the operator consumes two operands (x ⊗ y) or one.
+    pub fn is_binary(op: LogicOp) -> bool {
+        matches!(op, LogicOp::And | LogicOp::Or | LogicOp::Xor)
+    }
 }
 
 /// Basic predicates that can be asserted on a variable.
@@ -68,3 +104,74 @@ pub enum Constraint {
         strategy: AggregationStrategy,
     },
 }
+
+/// A single step of a proof emitted by the generator (LLM).
+/// Serialized as `{"action": "...", "params": {...}}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "RawProofAction", into = "RawProofAction")]
+#[allow(clippy::upper_case_acronyms)]
+pub enum ProofAction {
+    /// Introduce a symbol and its position in the type hierarchy.
+    Define {
+        symbol: String,
+        hierarchy_path: Vec<String>,
+    },
+    /// Apply a theorem to existing symbols, binding the result to a new symbol.
+    Apply {
+        theorem_id: String,
+        inputs: Vec<String>,
+        output_symbol: String,
+    },
+    /// Assert a relation between a subject and an object.
+    Assert {
+        subject: String,
+        relation: String,
+        object: String,
+    },
+    /// End of proof.
+    QED,
+}
+
+/// Wire form of `ProofAction`: every action carries a `params` object, even `QED` (`"params": {}`).
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "action", content = "params")]
+#[allow(clippy::upper_case_acronyms)]
+enum RawProofAction {
+    Define { symbol: String, hierarchy_path: Vec<String> },
+    Apply { theorem_id: String, inputs: Vec<String>, output_symbol: String },
+    Assert { subject: String, relation: String, object: String },
+    QED {},
+}
+
+impl From<RawProofAction> for ProofAction {
+    fn from(raw: RawProofAction) -> Self {
+        match raw {
+            RawProofAction::Define { symbol, hierarchy_path } => ProofAction::Define { symbol, hierarchy_path },
+            RawProofAction::Apply { theorem_id, inputs, output_symbol } => {
+                ProofAction::Apply { theorem_id, inputs, output_symbol }
+            }
+            RawProofAction::Assert { subject, relation, object } => ProofAction::Assert { subject, relation, object },
+            RawProofAction::QED {} => ProofAction::QED,
+        }
+    }
+}
+
+impl From<ProofAction> for RawProofAction {
+    fn from(action: ProofAction) -> Self {
+        match action {
+            ProofAction::Define { symbol, hierarchy_path } => RawProofAction::Define { symbol, hierarchy_path },
+            ProofAction::Apply { theorem_id, inputs, output_symbol } => {
+                RawProofAction::Apply { theorem_id, inputs, output_symbol }
+            }
+            ProofAction::Assert { subject, relation, object } => RawProofAction::Assert { subject, relation, object },
+            ProofAction::QED => RawProofAction::QED {},
+        }
+    }
+}
+
+/// A complete proof: the goal and its ordered steps.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProofSequence {
+    pub goal: String,
+    pub steps: Vec<ProofAction>,
+}
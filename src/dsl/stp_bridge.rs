@@ -1,7 +1,10 @@
-use crate::dsl::parser::{parse, Ast};
-use crate::dsl::math_kernel::{calculate_axiom_residual, check_stp_structure};
-use crate::soul::algebra::IdealClass;
-use crate::body::projection::{project, FeatureVector};
+use crate::dsl::math_kernel::MathKernel;
+use crate::dsl::schema::Constraint;
+use crate::soul::algebra::{AlgebraicState, IdealClass, Quaternion};
+use crate::body::projection::Projector;
+use crate::will::evaluator::Evaluator;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Represents the breakdown of the system's cognitive dissonance.
 #[derive(Debug, Clone)]
@@ -30,33 +33,45 @@ impl StpBridge {
     /// 
     /// # Arguments
     /// * `state` - The algebraic Soul state.
-    /// * `target` - The geometric target vector.
+    /// * `evaluator` - Supplies the geometric objective E_obj and the raw residuals C(S)
+    ///   in the order [Syntax, STP, Axiom1, Axiom2...].
     /// * `multipliers` - The dual variables (lambda) for constraints.
     /// * `slacks` - The logical relaxation variables (xi).
-    /// * `rho` - The penalty stiffness parameter.
-    /// * `mu` - The L1 sparsity coefficient.
+    /// * `rho` - The penalty stiffness of each constraint.
+    /// * `mu` - The L1 sparsity coefficient of each constraint (its tier's cost of compromise).
     pub fn calculate_hamiltonian(
         state: &IdealClass,
-        target: &FeatureVector,
+        evaluator: &dyn Evaluator,
         multipliers: &[f64],
         slacks: &[f64],
-        rho: f64,
-        mu: f64,
+        rho: &[f64],
+        mu: &[f64],
     ) -> HamiltonianState {
         // 1. Calculate Geometric Objective (E_obj)
-        // Using the topological projection (Lipshitz continuous)
-        let current_features = crate::body::projection::project_topo(state);
-        let geom_dist = feature_distance(&current_features, target);
-        let e_obj = geom_dist; // In theory, might be squared
+        let e_obj = evaluator.objective(state);
 
         // 2. Calculate Raw Residuals (C(S))
         // We evaluate how much the state violates "Truth".
-        let residuals = evaluate_residuals(state);
-        
+        let residuals = evaluator.residuals(state);
+
+        Self::lagrangian(e_obj, residuals, multipliers, slacks, rho, mu)
+    }
+
+    /// Assembles the Lagrangian from a precomputed objective E_obj and raw residuals C(S).
+    pub fn lagrangian(
+        e_obj: f64,
+        residuals: Vec<f64>,
+        multipliers: &[f64],
+        slacks: &[f64],
+        rho: &[f64],
+        mu: &[f64],
+    ) -> HamiltonianState {
         // Ensure multipliers and slacks match residuals length
         // (In production, handle mismatch gracefully)
         assert_eq!(residuals.len(), multipliers.len());
         assert_eq!(residuals.len(), slacks.len());
+        assert_eq!(residuals.len(), rho.len());
+        assert_eq!(residuals.len(), mu.len());
 
         // 3. Compute Lagrangian Terms
         let mut lagrangian_term = 0.0;
@@ -79,13 +94,12 @@ impl StpBridge {
             // Term: lambda * delta
             lagrangian_term += lambda_i * delta;
 
-            // Term: (rho / 2) * ||delta||^2
-            penalty_term += (rho / 2.0) * delta.powi(2);
+            // Term: (rho_i / 2) * ||delta||^2
+            penalty_term += (rho[i] / 2.0) * delta.powi(2);
         }
 
-        // 4. Compute L1 Regularization Term (mu * ||xi||_1)
-        let l1_norm: f64 = slacks.iter().map(|x| x.abs()).sum();
-        let sparsity_term = mu * l1_norm;
+        // 4. Compute L1 Regularization Term (sum_i mu_i * |xi_i|)
+        let sparsity_term: f64 = slacks.iter().zip(mu).map(|(x, m)| m * x.abs()).sum();
 
         // Total J
         let total_energy = e_obj + lagrangian_term + penalty_term + sparsity_term;
@@ -99,43 +113,127 @@ impl StpBridge {
     }
 }
 
-/// Helper to calculate raw residuals C(S)
-fn evaluate_residuals(state: &IdealClass) -> Vec<f64> {
-    let mut residuals = Vec::new();
-
-    // -- Constraint 0: Syntax --
-    // We use the exact projection for logic verification (Avalanche effect)
-    let code_projection = project(state); 
-    let ast_result = parse(code_projection);
-
-    match ast_result {
-        Ok(ast) => {
-            residuals.push(0.0); // Syntax OK
-
-            // -- Constraint 1: STP Structure --
-            let stp_error = if check_stp_structure(&ast) { 0.0 } else { 1.0 };
-            residuals.push(stp_error);
-
-            // -- Constraint 2+: Semantic Axioms --
-            // Get vector of violations for specific axioms
-            let axiom_errors = calculate_axiom_residual(&ast);
-            residuals.extend(axiom_errors);
-        },
-        Err(_) => {
-            residuals.push(10.0); // Syntax Error
-            // If syntax fails, we assume other constraints are heavily violated too
-            // to push the optimizer out of this garbage area.
-            residuals.push(10.0); 
-            residuals.push(10.0); 
+/// 逻辑目标 (Logic Target)
+/// 描述 Grand Loop 要寻找的 "真理"：一个几何意图加上一组必须满足的 DSL 约束。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LogicTarget {
+    /// 意图上下文。其意图投影 (`Projector::project_intent`) 作为几何目标 (与 `PyEvolver::align` 一致)。
+    pub context: Option<String>,
+    /// 显式的几何目标特征 (S^3 上的方向，恰好 4 维)，优先于 `context`
+    pub features: Option<Vec<f64>>,
+    /// 必须满足的 DSL 约束
+    pub constraints: Vec<Constraint>,
+    /// `AssertForAll` 引用的静态集合
+    pub collections: HashMap<String, Vec<f64>>,
+}
+
+impl LogicTarget {
+    /// 连续投影 q / |q| 的维数
+    pub const FEATURE_DIM: usize = 4;
+
+    /// 检查显式特征的维数：长度不符时 `geometric_loss` 的 zip 会悄悄截断
+    pub fn validate(&self) -> Result<(), String> {
+        match &self.features {
+            Some(features) if features.len() != Self::FEATURE_DIM => Err(format!(
+                "target.features needs {} components, got {}",
+                Self::FEATURE_DIM,
+                features.len()
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// 逻辑评估器 (Logic Evaluator)
+/// 
+/// Grand Loop 的目标函数：
+/// - `geometric_loss`: 连续投影与几何目标的欧几里得距离 (启发式引导)
+/// - `verify_exact`: 约束在状态的精确投影上是否全部成立 (最终裁决)
+pub struct LogicEvaluator {
+    target_features: Vec<f64>,
+    constraints: Vec<Constraint>,
+    collections: HashMap<String, Vec<f64>>,
+}
+
+impl LogicEvaluator {
+    /// 无约束、以原点方向为目标的评估器
+    pub fn new() -> Self {
+        Self::with_target(LogicTarget::default())
+    }
+
+    /// # Panics
+    /// `target.features` 的长度不是 4 时 panic (见 `LogicTarget::validate`；
+    /// 从文件加载的配置在 `EngineConfig::from_*` 中已校验)。
+    pub fn with_target(target: LogicTarget) -> Self {
+        if let Err(e) = target.validate() {
+            panic!("{}", e);
+        }
+        let projector = Projector::new(0);
+        let target_features = match (&target.features, &target.context) {
+            (Some(features), _) => features.clone(),
+            (None, Some(context)) => projector.project_intent(context),
+            (None, None) => projector.project_quaternion(&Quaternion::identity()),
+        };
+
+        Self {
+            target_features,
+            constraints: target.constraints,
+            collections: target.collections,
+        }
+    }
+
+    /// 几何损失：|Psi_topo(S) - target|
+    pub fn geometric_loss(&self, state: &AlgebraicState) -> f64 {
+        let features = Projector::new(state.p).project_quaternion(&state.value);
+        features
+            .iter()
+            .zip(self.target_features.iter())
+            .map(|(a, b)| (a - b).powi(2))
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    /// 严格验证：把约束中的每个变量绑定到状态精确投影的取值 (整数 0..1000)，
+    /// 然后要求 MathKernel 计算的每条约束能量都为零。
+    pub fn verify_exact(&self, state: &AlgebraicState) -> bool {
+        let projector = Projector::new(state.p);
+        let mut kernel = MathKernel::new();
+
+        for (name, values) in &self.collections {
+            kernel.set_collection(name, values.clone());
         }
+        for (t, name) in self.variable_names().iter().enumerate() {
+            let digit = projector.project_exact_quaternion(&state.value, t as u64);
+            kernel.set_variable(name, (digit % 1000) as f64);
+        }
+
+        self.constraints
+            .iter()
+            .all(|c| kernel.compute_energy(c).abs() < 1e-9)
     }
 
-    residuals
+    /// 约束中出现的变量名 (按首次出现顺序，去重)
+    fn variable_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for c in &self.constraints {
+            let vars: Vec<&String> = match c {
+                Constraint::Assert(_, v) => vec![v],
+                Constraint::AssertImplies(a, b) => vec![a, b],
+                Constraint::AssertForAll { .. } => vec![],
+            };
+            for v in vars {
+                if !names.contains(v) {
+                    names.push(v.clone());
+                }
+            }
+        }
+        names
+    }
 }
 
-fn feature_distance(a: &FeatureVector, b: &FeatureVector) -> f64 {
-    // Simple Euclidean distance for the demo
-    // FeatureVector is likely [u64] or similar, so we cast to f64
-    // This is a placeholder logic
-    1.0 
+impl Default for LogicEvaluator {
+    fn default() -> Self {
+        Self::new()
+    }
 }
@@ -0,0 +1,137 @@
+// Copyright (c) 2025 M-Patek
+// Part of the Evolver Project
+//
+// "Every search ends. The only question is whether it ends on purpose."
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::soul::algebra::AlgebraicState;
+use crate::will::tracer::Trace;
+
+/// 协作式取消令牌 (Cancellation Token)
+///
+/// 可以克隆并交给任意线程 (或 Python 绑定)；`cancel()` 之后，
+/// 持有同一令牌的引擎在下一个 epoch 边界停止，并返回迄今为止的最佳状态。
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// 引擎停止的原因
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum StopReason {
+    /// 找到并通过严格验证的真理状态
+    Converged,
+    /// 达到 `max_epochs` (熵耗尽)
+    EpochBudget,
+    /// 超过墙钟时限 `time_limit_secs`
+    Deadline,
+    /// 目标函数评估次数达到 `max_evaluations`
+    EvaluationBudget,
+    /// 宇宙迁移次数达到 `max_migrations`
+    MigrationBudget,
+    /// 取消令牌被触发
+    Cancelled,
+    /// 自动检查点写入失败 (继续运行可能丢失进度)
+    CheckpointFailed(String),
+}
+
+/// 蛇形命名 (`epoch_budget`)，供 Python 绑定与日志使用
+impl fmt::Display for StopReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StopReason::Converged => write!(f, "converged"),
+            StopReason::EpochBudget => write!(f, "epoch_budget"),
+            StopReason::Deadline => write!(f, "deadline"),
+            StopReason::EvaluationBudget => write!(f, "evaluation_budget"),
+            StopReason::MigrationBudget => write!(f, "migration_budget"),
+            StopReason::Cancelled => write!(f, "cancelled"),
+            StopReason::CheckpointFailed(error) => write!(f, "checkpoint_failed: {}", error),
+        }
+    }
+}
+
+/// 一次 `evolve` 的结果
+#[derive(Debug, Clone)]
+pub struct EngineOutcome {
+    pub stop_reason: StopReason,
+    /// `Converged` 时为验证通过的状态，否则为迄今为止几何能量最低的状态
+    pub state: AlgebraicState,
+    /// `state` 的几何能量
+    pub energy: f64,
+    /// 停止时完成的 epoch 数
+    pub epochs: usize,
+    /// 累计的目标函数评估次数
+    pub evaluations: usize,
+    /// 累计的宇宙迁移次数
+    pub migrations: usize,
+    /// 本次运行的种子 (用于复现)
+    pub seed: u64,
+    /// 行者的转移记录。重放得到停止时行者所在的状态：
+    /// `Converged` 时即为 `state`，其余原因下 `state` 是最佳状态，可能早于行者的当前位置。
+    pub trace: Trace,
+}
+
+impl EngineOutcome {
+    /// 结果是否是验证通过的真理状态
+    pub fn is_verified(&self) -> bool {
+        self.stop_reason == StopReason::Converged
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::config::EngineConfig;
+    use crate::engine::main_loop::EvolverEngine;
+    use crate::engine::observer::EngineEvent;
+
+    fn engine() -> EvolverEngine {
+        EvolverEngine::with_config(EngineConfig::fixture(3).max_epochs(1_000))
+    }
+
+    #[test]
+    fn test_cancelled_token_stops_evolve() {
+        let token = CancelToken::new();
+        let trigger = token.clone();
+        let mut engine = engine()
+            .cancel_token(token)
+            .observe(move |event: &EngineEvent| {
+                if let EngineEvent::EpochStarted { epoch: 3, .. } = event {
+                    trigger.cancel();
+                }
+            });
+
+        // 取消在第 3 个 epoch 内触发，于下一个 epoch 边界生效
+        let outcome = engine.evolve();
+        assert_eq!(outcome.stop_reason, StopReason::Cancelled);
+        assert_eq!(outcome.epochs, 3);
+        assert!(!outcome.is_verified());
+    }
+
+    #[test]
+    fn test_evaluation_budget_stops_evolve() {
+        let mut engine = EvolverEngine::with_config(
+            EngineConfig::fixture(3).max_epochs(1_000).max_evaluations(50),
+        );
+        let outcome = engine.evolve();
+        assert_eq!(outcome.stop_reason, StopReason::EvaluationBudget);
+        assert!(outcome.evaluations >= 50);
+    }
+}
@@ -0,0 +1,153 @@
+// Copyright (c) 2025 M-Patek
+// Part of the Evolver Project
+//
+// "A loop that can be paused was never really interrupted."
+
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::engine::config::EngineConfig;
+use crate::soul::algebra::AlgebraicState;
+use crate::soul::entropy::EvolverRng;
+use crate::soul::governor::SpectralGovernor;
+use crate::will::dynamics::DynamicOptimizer;
+use crate::will::frontier::{Frontier, FrontierEntry};
+use crate::will::restart::RestartStrategy;
+use crate::will::tracer::Trace;
+
+/// 检查点格式版本。格式发生不兼容变化时递增。
+pub const CHECKPOINT_VERSION: u32 = 1;
+
+/// 检查点读写错误
+#[derive(Debug, Error)]
+pub enum CheckpointError {
+    #[error("checkpoint I/O failed: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("malformed checkpoint: {0}")]
+    Format(#[from] serde_json::Error),
+    #[error("unsupported checkpoint version {found} (this build reads version {expected})")]
+    Version { found: u32, expected: u32 },
+}
+
+/// 引擎检查点 (Engine Checkpoint)
+///
+/// Grand Loop 在某个 epoch 结束时的完整可变状态。
+/// Lifter、Guard 与 Evaluator 是无状态的，恢复时由 `config` 重建；观察者不会被保存。
+/// 引擎的意志是 `DynamicOptimizer` (曲率模式 + 波前)，不持有 ALM `Optimizer`，
+/// 因此这里没有 ALM 乘子与松弛变量：它们只存在于 `will::optimizer` 的独立搜索中。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EngineCheckpoint {
+    /// 格式版本 (见 `CHECKPOINT_VERSION`)
+    pub version: u32,
+    /// 已完成的 epoch 数
+    pub epoch: usize,
+    /// 运行配置 (预算、子系统参数、目标)
+    pub config: EngineConfig,
+    /// 当前代数状态 (宇宙 p 包含在其中)
+    pub state: AlgebraicState,
+    /// 谱隙守护者 (当前宇宙与谱隙历史)
+    pub governor: SpectralGovernor,
+    /// 动态优化器 (当前战术模式)
+    pub optimizer: DynamicOptimizer,
+    /// 持久波前 (HyperbolicBeam 模式下的全部候选状态)
+    pub frontier: Frontier,
+    /// 迄今为止几何能量最低的状态
+    pub best: FrontierEntry,
+    /// 当前宇宙的种子状态 (重启游走的备选起点)
+    pub anchor: AlgebraicState,
+    /// 重启调度的时钟与平台计数
+    pub restarts: RestartStrategy,
+    /// 行者的转移记录 (恢复后继续追加)
+    pub trace: Trace,
+    /// 累计的目标函数评估次数
+    pub evaluations: usize,
+    /// 累计的宇宙迁移次数
+    pub migrations: usize,
+    /// 本次运行的种子 (恢复后保持不变)
+    pub seed: u64,
+    /// 熵源的完整内部状态：恢复后的随机序列与未中断的运行逐位一致
+    pub rng: EvolverRng,
+}
+
+impl EngineCheckpoint {
+    /// 写入文件。先写临时文件再原子重命名，进程在写入途中被抢占也不会损坏旧的检查点。
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), CheckpointError> {
+        let path = path.as_ref();
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+
+        fs::write(&tmp, serde_json::to_vec(self)?)?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// 读取并校验版本
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, CheckpointError> {
+        let bytes = fs::read(path)?;
+
+        // 先只解析版本号，避免把格式不兼容误报为字段错误
+        #[derive(Deserialize)]
+        struct Header {
+            version: u32,
+        }
+        let header: Header = serde_json::from_slice(&bytes)?;
+        if header.version != CHECKPOINT_VERSION {
+            return Err(CheckpointError::Version {
+                found: header.version,
+                expected: CHECKPOINT_VERSION,
+            });
+        }
+
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::main_loop::EvolverEngine;
+
+    fn json<T: Serialize>(value: &T) -> serde_json::Value {
+        serde_json::to_value(value).unwrap()
+    }
+
+    fn config(max_epochs: usize) -> EngineConfig {
+        EngineConfig::fixture(42).max_epochs(max_epochs)
+    }
+
+    #[test]
+    fn test_resume_matches_uninterrupted_run() {
+        // evolve(N) == evolve(k) + 检查点 + resume + evolve(N - k)
+        let (n, k) = (20, 8);
+        let mut reference = EvolverEngine::with_config(config(n));
+        let uninterrupted = reference.evolve();
+
+        let mut first_half = EvolverEngine::with_config(config(k));
+        first_half.evolve();
+        let path = std::env::temp_dir().join(format!("evolver-resume-{}.json", std::process::id()));
+        first_half.save_checkpoint(&path).unwrap();
+
+        let mut checkpoint = EngineCheckpoint::load(&path).unwrap();
+        let _ = fs::remove_file(&path);
+        assert_eq!(checkpoint.version, 1);
+        checkpoint.config.max_epochs = n;
+        let mut engine = EvolverEngine::from_checkpoint(checkpoint);
+        let resumed = engine.evolve();
+
+        assert_eq!(resumed.stop_reason, uninterrupted.stop_reason);
+        assert_eq!(resumed.epochs, uninterrupted.epochs);
+        assert_eq!(resumed.evaluations, uninterrupted.evaluations);
+        assert_eq!(resumed.state.value, uninterrupted.state.value);
+        assert_eq!(resumed.energy, uninterrupted.energy);
+        assert_eq!(json(&resumed.trace), json(&uninterrupted.trace));
+
+        // 逐位一致：轨迹、波前、熵源状态以及其余所有可变状态
+        let (resumed, reference) = (engine.checkpoint(), reference.checkpoint());
+        assert_eq!(json(&resumed.frontier), json(&reference.frontier));
+        assert_eq!(json(&resumed.rng), json(&reference.rng));
+        assert_eq!(json(&resumed), json(&reference));
+    }
+}
@@ -0,0 +1,370 @@
+// Copyright (c) 2025 M-Patek
+// Part of the Evolver Project
+//
+// "Same engine, different budgets."
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::body::guard_proxy::GuardConfig;
+use crate::dsl::stp_bridge::LogicTarget;
+use crate::soul::entropy::seed_from_context;
+use crate::soul::governor::GovernorConfig;
+use crate::soul::lifter::LiftConfig;
+use crate::will::dynamics::DynamicsConfig;
+use crate::will::restart::RestartConfig;
+
+/// 配置加载错误
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("failed to read config file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("invalid JSON config: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("invalid TOML config: {0}")]
+    Toml(#[from] toml::de::Error),
+    #[error("unsupported config format: {0} (expected .json or .toml)")]
+    UnsupportedFormat(String),
+    #[error("invalid target: {0}")]
+    InvalidTarget(String),
+}
+
+/// 引擎配置 (Engine Configuration)
+///
+/// Grand Loop 的全部预算与子系统参数。缺省字段取默认值，因此配置文件只需写出要覆盖的部分；
+/// 拼错的字段名会被拒绝，而不是被悄悄忽略：
+///
+/// ```toml
+/// initial_p = 101
+/// max_epochs = 5000
+///
+/// [lifter]
+/// parallel_universes = 8
+///
+/// [target]
+/// context = "prove the lemma"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EngineConfig {
+    /// 初始代数空间 B_{p,∞} 的素数参数
+    pub initial_p: u64,
+    /// 熵耗尽保护：最大 epoch 数
+    pub max_epochs: usize,
+    /// 墙钟时限 (秒)，从每次调用 `evolve` 开始计时。为 None 时不限时。
+    pub time_limit_secs: Option<f64>,
+    /// 目标函数评估次数上限 (跨检查点累计)
+    pub max_evaluations: Option<usize>,
+    /// 宇宙迁移次数上限：需要第 n+1 次迁移时停止
+    pub max_migrations: Option<usize>,
+    /// 每隔多少个 epoch 进行一次谱隙检查
+    pub governor_interval: usize,
+    /// 谱隙检查时局部图探索的半径 (展开的顶点数)
+    pub exploration_radius: usize,
+    /// 卫士审查使用的局部点云大小
+    pub embedding_cloud_size: usize,
+    /// 几何损失低于此值时进入严格验证
+    pub convergence_threshold: f64,
+    /// 自动检查点文件。为 None 时不写检查点。
+    pub checkpoint_path: Option<PathBuf>,
+    /// 每隔多少个 epoch 写一次检查点
+    pub checkpoint_interval: usize,
+    /// 熵种子。为 None 时由 `target.context` 的哈希派生，因此同一上下文的两次运行完全一致。
+    pub seed: Option<u64>,
+
+    pub governor: GovernorConfig,
+    pub lifter: LiftConfig,
+    pub guard: GuardConfig,
+    pub optimizer: DynamicsConfig,
+    pub restart: RestartConfig,
+
+    /// 要寻找的逻辑目标 (几何意图 + DSL 约束)
+    pub target: LogicTarget,
+}
+
+impl Default for EngineConfig {
+    fn default() -> Self {
+        Self {
+            initial_p: 37,
+            max_epochs: 100_000,
+            time_limit_secs: None,
+            max_evaluations: None,
+            max_migrations: None,
+            governor_interval: 50,
+            exploration_radius: 30,
+            embedding_cloud_size: 20,
+            convergence_threshold: 1e-6,
+            checkpoint_path: None,
+            checkpoint_interval: 1000,
+            seed: None,
+            governor: GovernorConfig::default(),
+            lifter: LiftConfig::default(),
+            guard: GuardConfig::default(),
+            optimizer: DynamicsConfig::default(),
+            restart: RestartConfig::default(),
+            target: LogicTarget::default(),
+        }
+    }
+}
+
+impl EngineConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn initial_p(mut self, p: u64) -> Self {
+        self.initial_p = p;
+        self
+    }
+
+    pub fn max_epochs(mut self, epochs: usize) -> Self {
+        self.max_epochs = epochs;
+        self
+    }
+
+    pub fn time_limit(mut self, limit: Duration) -> Self {
+        self.time_limit_secs = Some(limit.as_secs_f64());
+        self
+    }
+
+    pub fn max_evaluations(mut self, evaluations: usize) -> Self {
+        self.max_evaluations = Some(evaluations);
+        self
+    }
+
+    pub fn max_migrations(mut self, migrations: usize) -> Self {
+        self.max_migrations = Some(migrations);
+        self
+    }
+
+    pub fn governor_interval(mut self, interval: usize) -> Self {
+        self.governor_interval = interval.max(1);
+        self
+    }
+
+    pub fn exploration_radius(mut self, radius: usize) -> Self {
+        self.exploration_radius = radius;
+        self
+    }
+
+    pub fn embedding_cloud_size(mut self, size: usize) -> Self {
+        self.embedding_cloud_size = size;
+        self
+    }
+
+    pub fn convergence_threshold(mut self, threshold: f64) -> Self {
+        self.convergence_threshold = threshold;
+        self
+    }
+
+    /// 每 `interval` 个 epoch 把引擎状态写入 `path` (见 `EvolverEngine::resume`)
+    pub fn checkpoint(mut self, path: impl Into<PathBuf>, interval: usize) -> Self {
+        self.checkpoint_path = Some(path.into());
+        self.checkpoint_interval = interval.max(1);
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// 本次运行实际使用的种子
+    pub fn effective_seed(&self) -> u64 {
+        self.seed
+            .unwrap_or_else(|| seed_from_context(self.target.context.as_deref().unwrap_or("")))
+    }
+
+    pub fn governor(mut self, config: GovernorConfig) -> Self {
+        self.governor = config;
+        self
+    }
+
+    pub fn lifter(mut self, config: LiftConfig) -> Self {
+        self.lifter = config;
+        self
+    }
+
+    pub fn guard(mut self, config: GuardConfig) -> Self {
+        self.guard = config;
+        self
+    }
+
+    pub fn optimizer(mut self, config: DynamicsConfig) -> Self {
+        self.optimizer = config;
+        self
+    }
+
+    pub fn restart(mut self, config: RestartConfig) -> Self {
+        self.restart = config;
+        self
+    }
+
+    pub fn target(mut self, target: LogicTarget) -> Self {
+        self.target = target;
+        self
+    }
+
+    /// 检查 serde 无法表达的约束 (目前只有目标特征的维数)
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        self.target.validate().map_err(ConfigError::InvalidTarget)
+    }
+
+    pub fn from_json_str(s: &str) -> Result<Self, ConfigError> {
+        let config: Self = serde_json::from_str(s)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn from_toml_str(s: &str) -> Result<Self, ConfigError> {
+        let config: Self = toml::from_str(s)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// 从文件加载，按扩展名 (.json / .toml) 选择格式
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path)?;
+
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => Self::from_json_str(&contents),
+            Some("toml") => Self::from_toml_str(&contents),
+            other => Err(ConfigError::UnsupportedFormat(other.unwrap_or("").to_string())),
+        }
+    }
+}
+
+/// 测试夹具：p = 37 的宇宙与固定种子，其余取默认值
+#[cfg(test)]
+impl EngineConfig {
+    pub(crate) fn fixture(seed: u64) -> Self {
+        Self::default().initial_p(37).seed(seed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn custom() -> EngineConfig {
+        EngineConfig::default()
+            .initial_p(101)
+            .max_epochs(5000)
+            .max_evaluations(1234)
+            .time_limit(Duration::from_millis(1500))
+            .convergence_threshold(1e-4)
+            .seed(7)
+            .target(LogicTarget {
+                context: Some("prove the lemma".to_string()),
+                ..LogicTarget::default()
+            })
+    }
+
+    /// 比较序列化后的值 (配置类型本身不实现 PartialEq)
+    fn assert_same(a: &EngineConfig, b: &EngineConfig) {
+        assert_eq!(serde_json::to_value(a).unwrap(), serde_json::to_value(b).unwrap());
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let config = custom();
+        let loaded = EngineConfig::from_json_str(&serde_json::to_string(&config).unwrap()).unwrap();
+        assert_same(&loaded, &config);
+        assert_eq!(loaded.initial_p, 101);
+        assert_eq!(loaded.time_limit_secs, Some(1.5));
+    }
+
+    #[test]
+    fn test_toml_round_trip() {
+        let config = custom();
+        let loaded = EngineConfig::from_toml_str(&toml::to_string(&config).unwrap()).unwrap();
+        assert_same(&loaded, &config);
+        assert_eq!(loaded.target.context.as_deref(), Some("prove the lemma"));
+    }
+
+    #[test]
+    fn test_partial_config_keeps_defaults() {
+        let loaded = EngineConfig::from_toml_str("max_epochs = 10\n\n[lifter]\nparallel_universes = 2\n").unwrap();
+        assert_eq!(loaded.max_epochs, 10);
+        assert_eq!(loaded.lifter.parallel_universes, 2);
+        assert_same(&loaded, &EngineConfig::default().max_epochs(10).lifter(LiftConfig {
+            parallel_universes: 2,
+            ..LiftConfig::default()
+        }));
+    }
+
+    #[test]
+    fn test_rejects_unknown_and_invalid_fields() {
+        // 拼错的顶层字段与子配置字段
+        assert!(matches!(EngineConfig::from_toml_str("max_epoch = 10"), Err(ConfigError::Toml(_))));
+        assert!(matches!(
+            EngineConfig::from_toml_str("[lifter]\nparallel_universe = 2"),
+            Err(ConfigError::Toml(_))
+        ));
+        assert!(matches!(EngineConfig::from_json_str(r#"{"seeed": 1}"#), Err(ConfigError::Json(_))));
+        assert!(matches!(
+            EngineConfig::from_toml_str("[optimizer.curvature]\nambiguous_lo = -8.0"),
+            Err(ConfigError::Toml(_))
+        ));
+        assert!(matches!(
+            EngineConfig::from_toml_str("[optimizer.curvature.cheap]\naugment = true"),
+            Err(ConfigError::Toml(_))
+        ));
+        assert!(matches!(
+            EngineConfig::from_toml_str("[optimizer.curvature.ollivier]\nsensitivty = 1.0"),
+            Err(ConfigError::Toml(_))
+        ));
+        // 类型错误
+        assert!(matches!(
+            EngineConfig::from_json_str(r#"{"max_epochs": "many"}"#),
+            Err(ConfigError::Json(_))
+        ));
+        assert!(matches!(EngineConfig::from_toml_str("initial_p = -1"), Err(ConfigError::Toml(_))));
+    }
+
+    #[test]
+    fn test_rejects_target_features_of_the_wrong_length() {
+        let four = EngineConfig::from_toml_str("[target]\nfeatures = [1.0, 0.0, 0.0, 0.0]").unwrap();
+        assert_eq!(four.target.features.as_deref(), Some(&[1.0, 0.0, 0.0, 0.0][..]));
+        assert!(matches!(
+            EngineConfig::from_toml_str("[target]\nfeatures = [1.0, 0.0]"),
+            Err(ConfigError::InvalidTarget(_))
+        ));
+        assert!(matches!(
+            EngineConfig::from_json_str(r#"{"target": {"features": [0.5, 0.5, 0.5, 0.5, 0.0]}}"#),
+            Err(ConfigError::InvalidTarget(_))
+        ));
+    }
+
+    #[test]
+    fn test_from_file_dispatches_on_extension() {
+        let dir = std::env::temp_dir();
+        let stem = format!("evolver-config-{}", std::process::id());
+        let config = custom();
+
+        let json = dir.join(format!("{}.json", stem));
+        fs::write(&json, serde_json::to_string(&config).unwrap()).unwrap();
+        let toml_path = dir.join(format!("{}.toml", stem));
+        fs::write(&toml_path, toml::to_string(&config).unwrap()).unwrap();
+        let yaml = dir.join(format!("{}.yaml", stem));
+        fs::write(&yaml, "max_epochs: 10").unwrap();
+
+        let from_json = EngineConfig::from_file(&json);
+        let from_toml = EngineConfig::from_file(&toml_path);
+        let from_yaml = EngineConfig::from_file(&yaml);
+        let missing = EngineConfig::from_file(dir.join(format!("{}-missing.toml", stem)));
+        for path in [&json, &toml_path, &yaml] {
+            let _ = fs::remove_file(path);
+        }
+
+        assert_same(&from_json.unwrap(), &config);
+        assert_same(&from_toml.unwrap(), &config);
+        assert!(matches!(from_yaml, Err(ConfigError::UnsupportedFormat(ext)) if ext == "yaml"));
+        assert!(matches!(missing, Err(ConfigError::Io(_))));
+    }
+}
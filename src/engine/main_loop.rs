@@ -4,14 +4,28 @@
 // "The Grand Loop: Where Soul, Will, and Body dance in unison."
 
 use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
 use nalgebra::DVector;
+use rand::Rng;
 
 use crate::soul::governor::SpectralGovernor;
 use crate::soul::lifter::StateLifter;
 use crate::soul::algebra::AlgebraicState;
+use crate::soul::entropy::{rng_from_seed, EvolverRng};
 use crate::will::dynamics::{DynamicOptimizer, OptimizationMode};
+use crate::will::frontier::{Frontier, FrontierEntry};
+use crate::will::restart::{RestartOrigin, RestartReason, RestartStrategy};
+use crate::will::tabu::TabuKey;
+use crate::will::tracer::{Trace, Transition};
 use crate::body::guard_proxy::LazyGuard;
 use crate::dsl::stp_bridge::LogicEvaluator;
+use crate::engine::budget::{CancelToken, EngineOutcome, StopReason};
+use crate::engine::checkpoint::{CheckpointError, EngineCheckpoint, CHECKPOINT_VERSION};
+use crate::engine::config::EngineConfig;
+use crate::engine::observer::{EngineEvent, EngineObserver};
+use crate::engine::portfolio::{LagPolicy, SharedEnergy};
 
 /// Evolver 引擎 (Evolver Engine)
 /// 
@@ -31,75 +45,417 @@ pub struct EvolverEngine {
     /// 动态优化器：负责在流形上规划路径 (The Will)
     optimizer: DynamicOptimizer,
     
+    /// 持久波前：跨 epoch 保留的候选状态 (HyperbolicBeam 模式下整体扩展)
+    frontier: Frontier,
+    
     /// 惰性卫士：负责审查解的拓扑合法性 (The Body's Immune System)
     guard: LazyGuard,
     
     /// 逻辑评估器：计算几何损失和验证最终逻辑
     evaluator: LogicEvaluator,
     
+    /// 预算与阈值
+    config: EngineConfig,
+
+    /// 重启策略：决定何时重启，并记录每次重启的游走
+    restarts: RestartStrategy,
+
+    /// 唯一的熵源：谱隙估计、迁移探针与重启游走都从这里取随机数
+    rng: EvolverRng,
+
+    /// 本次运行的种子 (见 `EngineConfig::effective_seed`)
+    seed: u64,
+
+    /// 外部取消令牌 (例如 `Portfolio` 中另一个成员已经胜出，或服务端的请求超时)
+    cancel: Option<CancelToken>,
+
+    /// 组合搜索中所有成员共享的最低能量，以及落后时放弃当前区域的规则
+    shared: Option<(Arc<SharedEnergy>, LagPolicy)>,
+
+    /// 行者连续落后于共享最低能量的 epoch 数
+    lagging: usize,
+
+    /// 迄今为止几何能量最低的状态 (预算耗尽时返回它)
+    best: FrontierEntry,
+
+    /// 行者的全部转移：从初始宇宙的根重放即可复现当前状态
+    trace: Trace,
+
+    /// 当前宇宙的种子状态：初始根，或迁移后提升得到的状态
+    anchor: AlgebraicState,
+
+    /// 累计的目标函数评估次数
+    evaluations: usize,
+
+    /// 累计的宇宙迁移次数
+    migrations: usize,
+
+    /// 事件观察者 (日志 / 进度 / 指标)
+    observers: Vec<Box<dyn EngineObserver>>,
+
     /// 运行时计数器
     epoch: usize,
 }
 
 impl EvolverEngine {
-    /// 初始化引擎
+    /// 初始化引擎 (默认配置)
     /// initial_p: 初始代数空间的素数参数
     pub fn new(initial_p: u64) -> Self {
+        Self::with_config(EngineConfig::default().initial_p(initial_p))
+    }
+
+    /// 使用自定义配置初始化引擎
+    pub fn with_config(config: EngineConfig) -> Self {
+        let p = config.initial_p;
+        let seed = config.effective_seed();
+        let evaluator = LogicEvaluator::with_target(config.target.clone());
+        let state = AlgebraicState::new_root(p);
+        let best = FrontierEntry { state: state.clone(), energy: evaluator.geometric_loss(&state) };
+        let frontier = Frontier::single(state.clone(), best.energy, config.optimizer.beam_width);
         Self {
-            state: AlgebraicState::new_root(initial_p),
-            governor: SpectralGovernor::new(initial_p),
-            lifter: StateLifter::new(),
-            optimizer: DynamicOptimizer::new(),
-            guard: LazyGuard::new(),
-            evaluator: LogicEvaluator::new(),
+            state: state.clone(),
+            governor: SpectralGovernor::with_config(p, config.governor.clone()),
+            lifter: StateLifter::with_config(config.lifter.clone()),
+            optimizer: DynamicOptimizer::with_config(config.optimizer.clone()),
+            guard: LazyGuard::with_config(config.guard.clone()),
+            restarts: RestartStrategy::with_config(config.restart.clone()),
+            frontier,
+            evaluator,
+            rng: rng_from_seed(seed),
+            seed,
+            config,
+            cancel: None,
+            shared: None,
+            lagging: 0,
+            anchor: state.clone(),
+            trace: Trace::new(p, seed),
+            best,
+            evaluations: 0,
+            migrations: 0,
+            observers: Vec::new(),
             epoch: 0,
         }
     }
 
+    /// 从检查点恢复引擎。无状态的子系统由检查点中的配置重建；观察者需要重新注册。
+    pub fn from_checkpoint(checkpoint: EngineCheckpoint) -> Self {
+        let mut engine = Self::with_config(checkpoint.config);
+        engine.state = checkpoint.state;
+        engine.governor = checkpoint.governor;
+        engine.optimizer = checkpoint.optimizer;
+        engine.frontier = checkpoint.frontier;
+        engine.best = checkpoint.best;
+        engine.anchor = checkpoint.anchor;
+        engine.restarts = checkpoint.restarts;
+        engine.trace = checkpoint.trace;
+        engine.evaluations = checkpoint.evaluations;
+        engine.migrations = checkpoint.migrations;
+        engine.epoch = checkpoint.epoch;
+        engine.seed = checkpoint.seed;
+        engine.rng = checkpoint.rng;
+        engine
+    }
+
+    /// 读取检查点文件并恢复引擎，之后的 `evolve` 从中断处继续
+    pub fn resume(path: impl AsRef<Path>) -> Result<Self, CheckpointError> {
+        Ok(Self::from_checkpoint(EngineCheckpoint::load(path)?))
+    }
+
+    /// 当前 (epoch 结束时) 的完整引擎状态
+    pub fn checkpoint(&self) -> EngineCheckpoint {
+        EngineCheckpoint {
+            version: CHECKPOINT_VERSION,
+            epoch: self.epoch,
+            config: self.config.clone(),
+            state: self.state.clone(),
+            governor: self.governor.clone(),
+            optimizer: self.optimizer.clone(),
+            frontier: self.frontier.clone(),
+            best: self.best.clone(),
+            anchor: self.anchor.clone(),
+            restarts: self.restarts.clone(),
+            trace: self.trace.clone(),
+            evaluations: self.evaluations,
+            migrations: self.migrations,
+            seed: self.seed,
+            rng: self.rng.clone(),
+        }
+    }
+
+    pub fn save_checkpoint(&self, path: impl AsRef<Path>) -> Result<(), CheckpointError> {
+        self.checkpoint().save(path)
+    }
+
+    /// 本次运行的种子。以相同的配置和种子重新运行会得到相同的结果。
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn epoch(&self) -> usize {
+        self.epoch
+    }
+
+    pub fn state(&self) -> &AlgebraicState {
+        &self.state
+    }
+
+    /// 迄今为止几何能量最低的状态
+    pub fn best(&self) -> &FrontierEntry {
+        &self.best
+    }
+
+    /// 行者的转移记录。`trace().verify(state())` 在任何 epoch 边界都成立。
+    pub fn trace(&self) -> &Trace {
+        &self.trace
+    }
+
+    /// 取消令牌：被触发后，引擎在下一个 epoch 边界停止并返回最佳状态
+    pub fn cancel_token(mut self, token: CancelToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    /// 共享的最低能量：行者按 `policy` 落后于它时，引擎从本宇宙的种子重新出发
+    pub fn share_energy(mut self, shared: Arc<SharedEnergy>, policy: LagPolicy) -> Self {
+        self.shared = Some((shared, policy));
+        self
+    }
+
+    /// 注册一个事件观察者
+    pub fn observe(mut self, observer: impl EngineObserver + 'static) -> Self {
+        self.add_observer(observer);
+        self
+    }
+
+    pub fn add_observer(&mut self, observer: impl EngineObserver + 'static) {
+        self.observers.push(Box::new(observer));
+    }
+
+    fn emit(&mut self, event: EngineEvent) {
+        for observer in self.observers.iter_mut() {
+            observer.on_event(&event);
+        }
+    }
+
+    pub fn config(&self) -> &EngineConfig {
+        &self.config
+    }
+
+    /// 重启：从最佳状态 (若在当前宇宙中) 或种子状态出发做一次记录在案的随机游走，
+    /// 波前重置为游走终点
+    fn restart(&mut self, reason: RestartReason) {
+        let origin = match self.restarts.config().origin {
+            // 最佳状态只有与行者处于同一宇宙 (同一原点) 时才能作为起点
+            // 落后的行者说明自身的最佳状态已不具竞争力，此时从种子出发
+            RestartOrigin::Best
+                if reason != RestartReason::Lagging
+                    && self.best.state.p == self.state.p
+                    && self.best.state.origin == self.state.origin =>
+            {
+                self.best.state.clone()
+            }
+            _ => self.anchor.clone(),
+        };
+        let restart = self.restarts.restart(reason, &origin, &mut self.rng);
+        let energy = self.evaluator.geometric_loss(&restart.state);
+        self.trace.record(self.epoch, Transition::restart(reason, &self.state, &origin, restart.walk.clone()), energy);
+        self.state = restart.state;
+        self.lagging = 0;
+        self.reset_frontier();
+        self.emit(EngineEvent::Restart {
+            epoch: self.epoch,
+            reason,
+            origin: origin.hash(),
+            walk: restart.walk,
+        });
+    }
+
+    /// 行者在当前宇宙内移动到 `state`，并记录转移
+    fn move_to(&mut self, state: AlgebraicState, energy: f64) {
+        if let Some(transition) = Transition::between(&self.state, &state) {
+            self.trace.record(self.epoch, transition, energy);
+        }
+        self.state = state;
+    }
+
+    /// 若当前状态的能量低于历史最佳，则记为新的最佳状态
+    fn record_best(&mut self, energy: f64) {
+        if energy < self.best.energy {
+            self.best = FrontierEntry { state: self.state.clone(), energy };
+        }
+    }
+
+    /// 卫士否决了 `vetoed`：将其移出波前，波束模式下由波前中的下一个状态接替行者。
+    /// 没有接替者时返回 false (调用方负责重启)。
+    fn hand_off(&mut self, vetoed: u64, mode: OptimizationMode) -> bool {
+        self.frontier.remove(vetoed);
+        match self.frontier.best() {
+            Some(next) if mode == OptimizationMode::HyperbolicBeam => {
+                let next = next.clone();
+                self.move_to(next.state, next.energy);
+                self.record_best(next.energy);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 行者的能量是否已连续 `patience` 个 epoch 高于共享最低能量的 `factor` 倍
+    fn lagging_behind(&mut self, energy: f64) -> bool {
+        let Some((shared, policy)) = &self.shared else {
+            return false;
+        };
+        if energy > policy.factor * shared.get() {
+            self.lagging += 1;
+        } else {
+            self.lagging = 0;
+        }
+        self.lagging >= policy.patience.max(1)
+    }
+
+    /// 在 epoch 边界检查停止规则
+    fn exhausted(&self, started: Instant) -> Option<StopReason> {
+        if self.cancel.as_ref().is_some_and(|token| token.is_cancelled()) {
+            return Some(StopReason::Cancelled);
+        }
+        // 熵耗尽保护 (Entropy Exhaustion)
+        // 在推进计数器之前检查，使耗尽时的引擎停在最后一个完成的 epoch 上 (可以放宽预算后继续)
+        if self.epoch >= self.config.max_epochs {
+            return Some(StopReason::EpochBudget);
+        }
+        if self.config.time_limit_secs.is_some_and(|limit| started.elapsed().as_secs_f64() >= limit) {
+            return Some(StopReason::Deadline);
+        }
+        if self.config.max_evaluations.is_some_and(|limit| self.evaluations >= limit) {
+            return Some(StopReason::EvaluationBudget);
+        }
+        None
+    }
+
+    /// 结束循环：`Converged` 返回当前 (已验证) 状态，其余原因返回最佳状态
+    fn stop(&mut self, reason: StopReason) -> EngineOutcome {
+        let (state, energy) = if reason == StopReason::Converged {
+            (self.state.clone(), self.evaluator.geometric_loss(&self.state))
+        } else {
+            (self.best.state.clone(), self.best.energy)
+        };
+        self.emit(EngineEvent::Stopped { epoch: self.epoch, reason: reason.clone(), energy });
+
+        EngineOutcome {
+            stop_reason: reason,
+            state,
+            energy,
+            epochs: self.epoch,
+            evaluations: self.evaluations,
+            migrations: self.migrations,
+            seed: self.seed,
+            trace: self.trace.clone(),
+        }
+    }
+
+    fn reset_frontier(&mut self) {
+        let energy = self.evaluator.geometric_loss(&self.state);
+        self.frontier = Frontier::single(self.state.clone(), energy, self.config.optimizer.beam_width);
+    }
+
     /// THE GRAND LOOP: 意志的主循环
     /// 
-    /// 这是系统的主线程，永不停止，直到找到真理或熵耗尽。
+    /// 这是系统的主线程，直到找到真理或任一预算耗尽才停止。
     /// 
     /// # 返回
-    /// * `Ok(AlgebraicState)`: 找到并通过验证的真理状态
-    /// * `Err(String)`: 熵耗尽或发生不可恢复错误
-    pub fn evolve(&mut self) -> Result<AlgebraicState, String> {
-        println!("[System] EVOLVER ENGINE IGNITION. Target: Absolute Logic.");
+    /// * `EngineOutcome`: 停止原因，以及验证通过的真理状态 (`StopReason::Converged`)
+    ///   或迄今为止的最佳状态 (其余原因)
+    pub fn evolve(&mut self) -> EngineOutcome {
+        let started = Instant::now();
 
         loop {
-            self.epoch += 1;
-            
-            // 熵耗尽保护 (Entropy Exhaustion)
-            if self.epoch > 100_000 { 
-                return Err("Entropy Exhausted: Maximum epochs reached without convergence.".to_string()); 
+            // 自动检查点：在 epoch 边界保存，恢复后从下一个 epoch 继续
+            if self.epoch > 0 && self.epoch.is_multiple_of(self.config.checkpoint_interval.max(1)) {
+                if let Some(path) = self.config.checkpoint_path.clone() {
+                    if let Err(e) = self.save_checkpoint(&path) {
+                        return self.stop(StopReason::CheckpointFailed(e.to_string()));
+                    }
+                    self.emit(EngineEvent::CheckpointSaved { epoch: self.epoch });
+                }
             }
 
+            if let Some(reason) = self.exhausted(started) {
+                return self.stop(reason);
+            }
+
+            self.epoch += 1;
+            self.emit(EngineEvent::EpochStarted { epoch: self.epoch, p: self.state.p });
+
             // =============================================================
             // Phase 1: 宇宙常数检查 (Spectral Governance)
             // =============================================================
             // 这是一个昂贵的操作，我们仅在固定间隔检查，或者当系统明显停滞时检查。
-            if self.epoch % 50 == 0 {
+            if self.epoch.is_multiple_of(self.config.governor_interval.max(1)) {
                 // 探索局部图结构：获取节点集和邻接关系
-                let (nodes, adj) = self.state.explore_local_graph(30);
+                let (nodes, adj) = self.state.explore_local_graph(self.config.exploration_radius);
                 
-                if !self.governor.check_spectral_gap(&nodes, &adj) {
+                let report = self.governor.inspect_spectrum(&nodes, &adj, &mut self.rng);
+                let healthy = report.healthy;
+                self.emit(EngineEvent::SpectralCheck { epoch: self.epoch, report });
+
+                if !healthy {
                     // [CRITICAL] 谱隙关闭，代数空间已死 (Spectral Collapse)。
                     // 局部图结构变成了 "细管" 或 "哑铃"，随机游走效率极低。
-                    println!("[System] Spectral Collapse detected at Epoch {}. Initiating Migration.", self.epoch);
                     
-                    // 1. 寻找新的物理常数 p'
-                    let new_p = self.governor.migrate_algebra();
-                    
-                    // 2. [LIFTER] 灵魂转世：p -> p'
-                    // 携带旧记忆 (Feature Invariants)，在新宇宙重塑肉身
-                    self.state = self.lifter.lift_and_requantize(&self.state, new_p);
-                    
-                    // 3. 重置优化器动量
-                    // 新空间的曲率特性完全不同，旧的动量或模式已失效
-                    self.optimizer.mode = OptimizationMode::GradientFlow;
+                    if self.config.max_migrations.is_some_and(|limit| self.migrations >= limit) {
+                        return self.stop(StopReason::MigrationBudget);
+                    }
+
+                    // 1. 列出候选宇宙 p'_1 .. p'_k
+                    // 至少尝试一个宇宙：0 个候选会把每次迁移都变成 MigrationRejected
+                    let candidates = self.governor.candidate_primes(self.lifter.config().parallel_universes.max(1));
+
+                    // 2. [LIFTER] 灵魂转世：并行提升到每个候选宇宙，按特征损失 + 能量 + 谱健康打分。
+                    // 携带旧记忆 (Feature Invariants)，在最佳的新宇宙重塑肉身。
+                    let evaluator = &self.evaluator;
+                    let probe_seed = self.rng.gen::<u64>();
+                    let lifted = self.lifter.lift_parallel(
+                        &self.state,
+                        &candidates,
+                        &self.governor,
+                        probe_seed,
+                        |s| evaluator.geometric_loss(s),
+                    );
+                    self.evaluations += candidates.len();
+
+                    match lifted {
+                        // 没有宇宙保住特征：不提交迁移，留在当前宇宙继续本 epoch 的搜索
+                        None => {
+                            self.emit(EngineEvent::MigrationRejected { epoch: self.epoch, candidates });
+                        }
+                        Some(trial) => {
+                            let old_p = trial.report.p_old;
+                            let new_p = trial.report.p_new;
+                            self.governor.commit_migration(new_p);
+                            self.trace.record(
+                                self.epoch,
+                                Transition::Migration { p_old: old_p, p_new: new_p, lift_params: self.lifter.config().clone() },
+                                trial.probe_energy,
+                            );
+                            self.state = trial.state;
+                            self.anchor = self.state.clone();
+                            self.migrations += 1;
+                            self.reset_frontier();
+
+                            // 3. 重置优化器动量
+                            // 新空间的曲率特性完全不同，旧的动量或模式已失效
+                            self.optimizer.mode = OptimizationMode::GradientFlow;
+                            self.optimizer.forget();
                     
-                    println!("[System] Migration Complete. Resuming evolution in Cl(-{}).", new_p);
-                    continue;
+                            self.emit(EngineEvent::Migration {
+                                epoch: self.epoch,
+                                from: old_p,
+                                to: new_p,
+                                loss: trial.report.final_loss,
+                            });
+                            continue;
+                        }
+                    }
                 }
             }
 
@@ -107,83 +463,235 @@ impl EvolverEngine {
             // Phase 2: 动力学意图 (Dynamic Will)
             // =============================================================
             
-            // 1. 感知时空曲率 (Sense Curvature)
-            // 计算当前状态附近的离散 Ollivier-Ricci 曲率
-            let kappa = self.state.calculate_ricci_curvature();
-            
-            // 2. 切换战术模式 (Switch Tactics)
+            // 1. 感知时空曲率并切换战术模式 (Sense Curvature, Switch Tactics)
+            // 廉价的 Forman-Ricci 信号结论明确时直接使用，模糊时才升级为 Ollivier-Ricci
             // GradientFlow (平坦/正曲率) vs HyperbolicBeam (负曲率/混乱)
-            let _mode = self.optimizer.switch_mode(kappa);
+            let previous_mode = self.optimizer.mode;
+            let (curvature, mode) = self.optimizer.switch_mode_at(&self.state);
+            if mode != previous_mode {
+                self.emit(EngineEvent::ModeSwitched { epoch: self.epoch, curvature, from: previous_mode, to: mode });
+            }
             
-            // 3. 生成邻域候选者 (Generate Candidates)
-            let raw_candidates_states = self.state.generate_neighbors();
+            // 2. 生成邻域候选者 (Generate Candidates)
+            // GradientFlow: 波前坍缩为单一行者；HyperbolicBeam: 整个波前同时扩展
+            // 不提供立即回溯 (g 之后接 conj(g)) 的子代
+            if mode == OptimizationMode::GradientFlow {
+                self.frontier.collapse();
+            }
+            let parents: Vec<AlgebraicState> = self.frontier.entries().iter().map(|e| e.state.clone()).collect();
             
             // 建立 Hash -> State 的映射，因为 Optimizer 处理的是 u64 Hash
+            // 每个候选者只打分一次：几何能量缓存在 score_map 中 (多个父节点的公共子代只保留一份)
             let mut candidate_map = HashMap::new();
+            let mut score_map = HashMap::new();
             let mut raw_candidates_hashes = Vec::new();
             
-            for cand in raw_candidates_states {
+            for cand in parents.iter().flat_map(|parent| parent.forward_neighbors()) {
                 let h = cand.hash();
+                if candidate_map.contains_key(&h) {
+                    continue;
+                }
+                let loss = self.evaluator.geometric_loss(&cand);
+                self.evaluations += 1;
+                self.emit(EngineEvent::CandidateScored { epoch: self.epoch, hash: h, loss });
                 candidate_map.insert(h, cand);
+                score_map.insert(h, loss);
                 raw_candidates_hashes.push(h);
             }
             
-            // 4. 定义目标函数 (Objective Function)
-            // 闭包捕获 evaluator 和 map
+            // 3. 定义目标函数 (Objective Function)
             let objective_fn = |hash: u64| -> f64 {
-                if let Some(cand) = candidate_map.get(&hash) {
-                    // 计算基础几何能量 (Sinkhorn Distance / L2)
-                    self.evaluator.geometric_loss(cand)
-                } else {
-                    f64::MAX // Should not happen
-                }
+                score_map.get(&hash).copied().unwrap_or(f64::MAX)
             };
 
-            // 5. 执行优化步 (Step)
-            // 优化器根据当前模式筛选出最有希望的下一代状态
-            let best_hashes = self.optimizer.step(raw_candidates_hashes, objective_fn);
+            // 禁忌属性：规范状态 + 产生它的移动
+            let key_fn = |hash: u64| -> TabuKey {
+                let cand = &candidate_map[&hash];
+                TabuKey { state: cand.canonical_hash(), mv: cand.path_history.last().copied() }
+            };
+
+            // 4. 执行优化步 (Step)
+            // 优化器根据当前模式筛选出最有希望的下一代状态 (GradientFlow: 1 个；HyperbolicBeam: top-k)
+            // 禁忌记忆剔除最近访问过的状态与最近使用过的生成元 (除非刷新了历史最低能量)
+            let best_hashes = self.optimizer.step_with_memory(raw_candidates_hashes, objective_fn, key_fn);
+
+            if best_hashes.is_empty() {
+                // [STUCK] 死胡同：没有生成有效的候选者
+                // 可能是进入了孤立点或约束过强
+                self.restart(RestartReason::Stuck);
+                continue;
+            }
+
+            // 下一代波前
+            self.frontier.replace(
+                best_hashes
+                    .iter()
+                    .map(|h| FrontierEntry { state: candidate_map[h].clone(), energy: score_map[h] })
+                    .collect(),
+            );
             
             // =============================================================
             // Phase 3: 卫士审查 (The Lazy Guard)
             // =============================================================
             
-            if let Some(&best_hash) = best_hashes.first() {
-                // 既然 Optimizer 选择了它，我们先取出状态
-                let best_cand = candidate_map.get(&best_hash).unwrap();
-                let geom_loss = self.evaluator.geometric_loss(best_cand);
-                
-                // 为了惰性审查，我们需要生成局部点云 (Embedding Cloud)
-                // 这模拟了状态在特征空间中的微观分布
-                let cloud: Vec<DVector<f64>> = best_cand.generate_embedding_cloud(20);
+            // 审查波前的行者 (能量最低者)：只有它有资格宣称收敛
+            let walker = self.frontier.best().cloned().expect("frontier is non-empty after replace");
+            let best_hash = walker.state.hash();
+            let geom_loss = walker.energy;
+            
+            // 为了惰性审查，我们需要生成局部点云 (Embedding Cloud)
+            // 这模拟了状态在特征空间中的微观分布
+            let cloud: Vec<DVector<f64>> = walker.state.generate_embedding_cloud(self.config.embedding_cloud_size);
 
-                // [GUARD] 惰性审查
-                // 只有当 loss 很低 (诱惑) 且 kappa 很低 (风险) 时才触发同调检查
-                let (veto, _penalty) = self.guard.inspect(&cloud, geom_loss, kappa);
+            // [GUARD] 惰性审查
+            // 只有当 loss 很低 (诱惑) 且 kappa 很低 (风险) 时才触发同调检查。
+            // 风险阈值在 Ollivier 尺度上：卫士被咨询时，廉价的 Forman 读数总是升级
+            let kappa = if self.guard.is_tempting(geom_loss) {
+                self.optimizer.escalate(&self.state, curvature).kappa()
+            } else {
+                curvature.kappa()
+            };
+            let verdict = self.guard.review(&cloud, geom_loss, kappa);
 
-                if !veto {
-                    // [ACCEPT] 卫士放行
-                    self.state = best_cand.clone();
-                    
-                    // [FINAL CHECK] 检查是否完全收敛 (Logical Zero)
-                    // 这需要几何误差极小，且通过严格的 STP 代数验证 (verify_exact)
-                    if geom_loss < 1e-6 && self.evaluator.verify_exact(&self.state) {
-                        println!("[System] ✨ TRUTH DISCOVERED at Epoch {}. Energy ~ 0.", self.epoch);
-                        return Ok(self.state.clone());
-                    }
-                } else {
-                    // [REJECT] 卫士否决 (发现拓扑孔洞/逻辑死循环)
-                    // println!("[System] Guard Vetoed candidate at Epoch {}. Injecting Entropy.", self.epoch);
-                    
-                    // 施加惩罚并强制随机扰动 (Entropy Injection / Random Jump)
-                    // 这防止系统在拓扑陷阱周围震荡
-                    self.state = self.state.random_jump();
+            if !verdict.veto {
+                // [ACCEPT] 卫士放行
+                self.move_to(walker.state, geom_loss);
+                self.record_best(geom_loss);
+                
+                // [FINAL CHECK] 检查是否完全收敛 (Logical Zero)
+                // 这需要几何误差极小，且通过严格的 STP 代数验证 (verify_exact)
+                if geom_loss < self.config.convergence_threshold && self.evaluator.verify_exact(&self.state) {
+                    self.emit(EngineEvent::Converged {
+                        epoch: self.epoch,
+                        p: self.state.p,
+                        hash: best_hash,
+                        loss: geom_loss,
+                        seed: self.seed,
+                    });
+                    return self.stop(StopReason::Converged);
                 }
             } else {
-                // [STUCK] 死胡同：没有生成有效的候选者
-                // 可能是进入了孤立点或约束过强
-                // println!("[System] Stuck at Epoch {}. Random Jump.", self.epoch);
-                self.state = self.state.random_jump();
+                // [REJECT] 卫士否决 (发现拓扑孔洞/逻辑死循环)
+                if let Some(betti) = verdict.signature {
+                    self.emit(EngineEvent::GuardVeto {
+                        epoch: self.epoch,
+                        hash: best_hash,
+                        betti,
+                        penalty: verdict.penalty,
+                    });
+                }
+                if !self.hand_off(best_hash, mode) {
+                    // 施加惩罚并强制重启 (Entropy Injection)
+                    // 这防止系统在拓扑陷阱周围震荡
+                    self.restart(RestartReason::GuardVeto);
+                    continue;
+                }
+            }
+
+            // 重启调度：Luby / Geometric 间隔到期，或行者的能量陷入平台；
+            // 组合搜索中还包括远远落后于其他成员
+            let walker_energy = self.frontier.best().map_or(f64::INFINITY, |e| e.energy);
+            if let Some(reason) = self.restarts.tick(walker_energy) {
+                self.restart(reason);
+            } else if self.lagging_behind(walker_energy) {
+                self.restart(RestartReason::Lagging);
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use crate::dsl::stp_bridge::LogicTarget;
+    use crate::soul::governor::GovernorConfig;
+    use crate::soul::lifter::LiftConfig;
+
+    #[test]
+    fn test_veto_hand_off_records_best() {
+        let target = LogicTarget { context: Some("prove the lemma".to_string()), ..LogicTarget::default() };
+        let mut engine = EvolverEngine::with_config(EngineConfig::fixture(3).target(target));
+        let neighbors = engine.state.generate_neighbors();
+        let (vetoed, next) = (&neighbors[0], &neighbors[1]);
+        let energy = engine.best.energy / 2.0;
+        assert!(energy > 0.0);
+        engine.frontier.replace(vec![
+            FrontierEntry { state: vetoed.clone(), energy: energy / 2.0 },
+            FrontierEntry { state: next.clone(), energy },
+        ]);
+
+        // 否决波前的领头者：下一个状态接替行者，并且被记为最佳
+        assert!(engine.hand_off(vetoed.hash(), OptimizationMode::HyperbolicBeam));
+        assert_eq!(engine.state.hash(), next.hash());
+        assert_eq!(engine.best().state.hash(), next.hash());
+        assert_eq!(engine.best().energy, energy);
+        assert!(engine.trace().verify(engine.state()));
+
+        // 梯度流模式没有接替者
+        assert!(!engine.hand_off(next.hash(), OptimizationMode::GradientFlow));
+    }
+
+    #[test]
+    fn test_zero_parallel_universes_still_migrates() {
+        let lifter = LiftConfig { beam_width: 4, max_steps: 5, parallel_universes: 0, ..LiftConfig::default() };
+        let config = EngineConfig::default()
+            .initial_p(13)
+            .seed(3)
+            .max_epochs(1)
+            .governor_interval(1)
+            .exploration_radius(20)
+            .lifter(lifter);
+        let outcome = EvolverEngine::with_config(config).evolve();
+        assert_eq!(outcome.migrations, 1);
+    }
+
+    #[test]
+    fn test_evolve_with_the_curvature_probe_for_hundreds_of_epochs() {
+        let readings = Arc::new(Mutex::new(Vec::new()));
+        let sink = readings.clone();
+        // 谱隙检查 (与迁移的提升) 不在本测试的范围内
+        let config = EngineConfig::fixture(23).max_epochs(300).governor_interval(1_000);
+        let mut engine = EvolverEngine::with_config(config).observe(move |event: &EngineEvent| {
+            if let EngineEvent::ModeSwitched { curvature, .. } = event {
+                sink.lock().unwrap().push(*curvature);
+            }
+        });
+        let outcome = engine.evolve();
+
+        assert!(outcome.epochs > 0 && outcome.epochs <= 300);
+        assert!(outcome.trace.replay().is_some());
+
+        // 两种读数都驱动过模式切换；Ollivier 曲率总在 [-2, 1] 内
+        let readings = readings.lock().unwrap();
+        assert!(readings.iter().any(|r| !r.escalated()));
+        assert!(readings.iter().any(|r| r.escalated()));
+        for reading in readings.iter() {
+            if let Some(kappa) = reading.ollivier() {
+                assert!((-2.0..=1.0).contains(&kappa), "{}", kappa);
+            }
+        }
+    }
+
+    #[test]
+    fn test_replay_reproduces_walker_across_migration() {
+        // 谱隙阈值大于 1：每次检查都判定为坍缩，强制迁移
+        let config = EngineConfig::fixture(17)
+            .max_epochs(12)
+            .max_migrations(2)
+            .governor_interval(4)
+            .governor(GovernorConfig { min_spectral_gap: 2.0, ..GovernorConfig::default() })
+            .lifter(LiftConfig { beam_width: 4, max_steps: 5, probe_radius: 3, parallel_universes: 2, ..LiftConfig::default() });
+        let mut engine = EvolverEngine::with_config(config);
+        let outcome = engine.evolve();
+
+        assert!(outcome.migrations >= 1);
+        assert!(outcome
+            .trace
+            .entries
+            .iter()
+            .any(|e| matches!(e.transition, Transition::Migration { .. })));
+        assert_ne!(engine.state().p, 37);
+        assert!(outcome.trace.verify(engine.state()));
+    }
+}
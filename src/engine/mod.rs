@@ -0,0 +1,24 @@
+//! The Engine Module (引擎模块)
+//!
+//! Grand Loop 的控制器：协调 Soul (Governor / Lifter)、Will (Optimizer) 与 Body (Guard)。
+//!
+//! 所有预算与子系统参数都集中在 `EngineConfig` 中，可由代码构建，也可从 JSON / TOML 加载。
+//! 停止规则 (epoch / 时限 / 评估次数 / 迁移次数 / 取消令牌) 返回带 `StopReason` 的 `EngineOutcome`。
+//! 长时间运行的演化可以通过 `EngineCheckpoint` 暂停并由 `EvolverEngine::resume` 继续。
+//! `Portfolio` 在多个线程上并行运行不同宇宙与种子的引擎，先验证者胜出。
+//! 循环的进展通过 `EngineObserver` 以类型化事件 (`EngineEvent`) 的形式发出。
+
+pub mod budget;
+pub mod checkpoint;
+pub mod config;
+pub mod main_loop;
+pub mod observer;
+pub mod portfolio;
+
+// Re-export for easier access
+pub use budget::{CancelToken, EngineOutcome, StopReason};
+pub use checkpoint::{CheckpointError, EngineCheckpoint};
+pub use config::{ConfigError, EngineConfig};
+pub use main_loop::EvolverEngine;
+pub use observer::{ConsoleObserver, EngineEvent, EngineObserver};
+pub use portfolio::{MemberOutcome, Portfolio, PortfolioOutcome};
@@ -0,0 +1,207 @@
+// Copyright (c) 2025 M-Patek
+// Part of the Evolver Project
+//
+// "The loop does not speak; it is observed."
+
+use std::sync::mpsc::Sender;
+
+use crate::body::topology::TopologicalSignature;
+use crate::engine::budget::StopReason;
+use crate::soul::governor::SpectralReport;
+use crate::soul::hecke::HeckeMove;
+use crate::will::curvature::CurvatureReading;
+use crate::will::dynamics::OptimizationMode;
+use crate::will::restart::RestartReason;
+
+/// Grand Loop 发出的类型化事件 (Engine Event)
+#[derive(Debug, Clone, PartialEq)]
+pub enum EngineEvent {
+    /// 新的 epoch 开始
+    EpochStarted { epoch: usize, p: u64 },
+    /// 一个邻域候选者被目标函数打分
+    CandidateScored { epoch: usize, hash: u64, loss: f64 },
+    /// 优化器因曲率变化切换了战术模式
+    ModeSwitched { epoch: usize, curvature: CurvatureReading, from: OptimizationMode, to: OptimizationMode },
+    /// 卫士否决了候选者 (附带同调签名)
+    GuardVeto { epoch: usize, hash: u64, betti: TopologicalSignature, penalty: f64 },
+    /// 重启：行者从起点 (哈希 origin) 出发，沿记录的生成元游走到新位置
+    Restart { epoch: usize, reason: RestartReason, origin: u64, walk: Vec<HeckeMove> },
+    /// 谱隙检查完成
+    SpectralCheck { epoch: usize, report: SpectralReport },
+    /// 宇宙迁移完成 B(from, ∞) -> B(to, ∞)
+    Migration { epoch: usize, from: u64, to: u64, loss: f64 },
+    /// 没有候选宇宙满足特征损失上限，留在当前宇宙
+    MigrationRejected { epoch: usize, candidates: Vec<u64> },
+    /// 检查点已写入磁盘
+    CheckpointSaved { epoch: usize },
+    /// 找到并通过验证的真理状态
+    Converged { epoch: usize, p: u64, hash: u64, loss: f64, seed: u64 },
+    /// 循环结束 (包括收敛)
+    Stopped { epoch: usize, reason: StopReason, energy: f64 },
+}
+
+/// 引擎观察者 (Engine Observer)
+///
+/// 日志、进度条、指标采集等都通过实现该 trait 接入，而无需修改引擎。
+/// 闭包 `FnMut(&EngineEvent)` 与 `mpsc::Sender<EngineEvent>` (事件通道) 已自动实现。
+pub trait EngineObserver {
+    fn on_event(&mut self, event: &EngineEvent);
+}
+
+impl<F: FnMut(&EngineEvent)> EngineObserver for F {
+    fn on_event(&mut self, event: &EngineEvent) {
+        self(event)
+    }
+}
+
+impl EngineObserver for Sender<EngineEvent> {
+    fn on_event(&mut self, event: &EngineEvent) {
+        // 接收端已关闭时静默丢弃：观察者不应影响演化本身
+        let _ = self.send(event.clone());
+    }
+}
+
+/// 控制台观察者：打印迁移与收敛等里程碑事件 (引擎旧有的 stdout 输出)
+/// `verbose` 时还打印模式切换、否决与随机跳跃。
+#[derive(Debug, Clone, Default)]
+pub struct ConsoleObserver {
+    pub verbose: bool,
+}
+
+impl ConsoleObserver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+}
+
+impl EngineObserver for ConsoleObserver {
+    fn on_event(&mut self, event: &EngineEvent) {
+        match event {
+            EngineEvent::EpochStarted { epoch: 1, p } => {
+                println!("[System] EVOLVER ENGINE IGNITION in B({}, inf). Target: Absolute Logic.", p);
+            }
+            EngineEvent::SpectralCheck { epoch, report } if !report.healthy => {
+                println!("[System] Spectral Collapse detected at Epoch {}. Initiating Migration.", epoch);
+            }
+            EngineEvent::Migration { to, loss, .. } => {
+                println!("[System] Migration Complete. Resuming evolution in B({}, inf). Feature Loss: {:.6}", to, loss);
+            }
+            EngineEvent::MigrationRejected { epoch, candidates } => {
+                println!("[System] Migration rejected at Epoch {}: no universe in {:?} preserves the features.", epoch, candidates);
+            }
+            EngineEvent::Converged { epoch, seed, .. } => {
+                println!("[System] ✨ TRUTH DISCOVERED at Epoch {} (seed {}). Energy ~ 0.", epoch, seed);
+            }
+            EngineEvent::Stopped { epoch, reason, energy } if *reason != StopReason::Converged => {
+                println!("[System] Stopped at Epoch {}: {:?}. Best Energy: {:.6}", epoch, reason, energy);
+            }
+            EngineEvent::ModeSwitched { curvature, to, .. } if self.verbose => {
+                let source = if curvature.escalated() { "Ollivier" } else { "Forman" };
+                println!("[Dynamics] {} curvature {:.2}. Switching to {:?}.", source, curvature.kappa(), to);
+            }
+            EngineEvent::GuardVeto { epoch, betti, .. } if self.verbose => {
+                println!("[Guard] VETO at Epoch {}: Topological Hole Detected (Betti-1={}).", epoch, betti.betti_1);
+            }
+            EngineEvent::Restart { epoch, reason, walk, .. } if self.verbose => {
+                println!("[System] {:?} at Epoch {}. Restart ({} steps).", reason, epoch, walk.len());
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::channel;
+    use crate::engine::config::EngineConfig;
+    use crate::engine::main_loop::EvolverEngine;
+    use crate::soul::lifter::LiftConfig;
+    use crate::will::dynamics::DynamicsConfig;
+
+    fn position(events: &[EngineEvent], pred: impl Fn(&EngineEvent) -> bool) -> usize {
+        events.iter().position(pred).expect("event was emitted")
+    }
+
+    #[test]
+    fn test_channel_receives_events_in_order() {
+        // 任何被卫士放行的一步都算收敛：第一个 epoch 内结束
+        let config = EngineConfig::fixture(3).convergence_threshold(f64::INFINITY);
+        let (tx, rx) = channel();
+        let outcome = EvolverEngine::with_config(config).observe(tx).evolve();
+        let events: Vec<EngineEvent> = rx.try_iter().collect();
+
+        assert_eq!(events[0], EngineEvent::EpochStarted { epoch: 1, p: 37 });
+        let switched = position(&events, |e| matches!(e, EngineEvent::ModeSwitched { .. }));
+        let scored = position(&events, |e| matches!(e, EngineEvent::CandidateScored { .. }));
+        let converged = position(&events, |e| matches!(e, EngineEvent::Converged { .. }));
+        assert!(switched < scored && scored < converged);
+        assert_eq!(converged, events.len() - 2);
+
+        // 根处的 Forman 曲率为负，第一个 epoch 即切换到波束模式
+        match &events[switched] {
+            EngineEvent::ModeSwitched { epoch, curvature, from, to } => {
+                assert_eq!(*epoch, 1);
+                assert!(curvature.kappa() < DynamicsConfig::default().curvature_threshold);
+                assert_eq!((*from, *to), (OptimizationMode::GradientFlow, OptimizationMode::HyperbolicBeam));
+            }
+            _ => unreachable!(),
+        }
+        for event in &events[scored..converged] {
+            if let EngineEvent::CandidateScored { epoch, loss, .. } = event {
+                assert_eq!(*epoch, 1);
+                assert!(loss.is_finite() && *loss >= 0.0);
+            }
+        }
+        assert_eq!(
+            events[converged],
+            EngineEvent::Converged { epoch: 1, p: 37, hash: outcome.state.hash(), loss: outcome.energy, seed: 3 }
+        );
+        assert_eq!(
+            events[events.len() - 1],
+            EngineEvent::Stopped { epoch: 1, reason: StopReason::Converged, energy: outcome.energy }
+        );
+    }
+
+    #[test]
+    fn test_closed_channel_does_not_stop_the_engine() {
+        let (tx, rx) = channel();
+        drop(rx);
+        let outcome = EvolverEngine::with_config(EngineConfig::fixture(3).max_epochs(3))
+            .observe(tx)
+            .evolve();
+        assert_eq!(outcome.stop_reason, StopReason::EpochBudget);
+        assert_eq!(outcome.epochs, 3);
+    }
+
+    #[test]
+    fn test_migration_event_payload() {
+        let lifter = LiftConfig { beam_width: 4, max_steps: 5, parallel_universes: 1, ..LiftConfig::default() };
+        let config = EngineConfig::default()
+            .initial_p(13)
+            .seed(3)
+            .max_epochs(2)
+            .governor_interval(1)
+            .exploration_radius(20)
+            .lifter(lifter);
+        let (tx, rx) = channel();
+        EvolverEngine::with_config(config).observe(tx).evolve();
+        let events: Vec<EngineEvent> = rx.try_iter().collect();
+
+        // 谱隙坍缩 -> 迁移 -> 在新宇宙中开始下一个 epoch
+        let check = position(&events, |e| matches!(e, EngineEvent::SpectralCheck { .. }));
+        assert!(matches!(&events[check], EngineEvent::SpectralCheck { epoch: 1, report } if report.p == 13 && !report.healthy));
+        let (to, loss) = match &events[check + 1] {
+            EngineEvent::Migration { epoch: 1, from: 13, to, loss } => (*to, *loss),
+            other => panic!("expected a migration, got {:?}", other),
+        };
+        assert!(to > 13 && to % 4 == 1);
+        assert!(loss.is_finite() && loss >= 0.0);
+        assert_eq!(events[check + 2], EngineEvent::EpochStarted { epoch: 2, p: to });
+    }
+}
@@ -0,0 +1,345 @@
+// Copyright (c) 2025 M-Patek
+// Part of the Evolver Project
+//
+// "Many walkers, one truth: the first to arrive ends the search for all."
+
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use rayon::prelude::*;
+
+use crate::engine::budget::{CancelToken, EngineOutcome};
+use crate::engine::config::EngineConfig;
+use crate::engine::main_loop::EvolverEngine;
+use crate::engine::observer::{EngineEvent, EngineObserver};
+use crate::soul::algebra::AlgebraicState;
+use crate::soul::governor::is_prime;
+
+/// 跨线程共享的最低能量 (f64 以位模式存放在 AtomicU64 中)
+#[derive(Debug)]
+pub struct SharedEnergy(AtomicU64);
+
+impl SharedEnergy {
+    pub fn new() -> Self {
+        Self(AtomicU64::new(f64::INFINITY.to_bits()))
+    }
+
+    pub fn get(&self) -> f64 {
+        f64::from_bits(self.0.load(Ordering::Relaxed))
+    }
+
+    /// 若 `energy` 更低则替换，返回是否刷新了记录
+    pub fn record(&self, energy: f64) -> bool {
+        let mut current = self.0.load(Ordering::Relaxed);
+        while energy < f64::from_bits(current) {
+            match self.0.compare_exchange_weak(current, energy.to_bits(), Ordering::Relaxed, Ordering::Relaxed) {
+                Ok(_) => return true,
+                Err(actual) => current = actual,
+            }
+        }
+        false
+    }
+}
+
+impl Default for SharedEnergy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 落后成员的重启规则 (Lag Policy)
+///
+/// 成员的行者能量连续 `patience` 个 epoch 高于共享最低能量的 `factor` 倍时，
+/// 该成员放弃当前区域，从本宇宙的种子做一次重启游走 (`RestartReason::Lagging`)。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LagPolicy {
+    pub factor: f64,
+    pub patience: usize,
+}
+
+impl Default for LagPolicy {
+    fn default() -> Self {
+        Self {
+            factor: 4.0,
+            patience: 25,
+        }
+    }
+}
+
+/// 组合中单个引擎的结局
+#[derive(Debug, Clone)]
+pub struct MemberOutcome {
+    /// 成员在组合中的序号
+    pub index: usize,
+    pub initial_p: u64,
+    /// 该成员评估过的最低几何能量 (含起点与重启状态，即不高于 `outcome.energy`)
+    pub best_energy: f64,
+    /// 引擎的结局 (停止原因、返回状态、种子与计数)
+    pub outcome: EngineOutcome,
+}
+
+/// 组合搜索的结果
+#[derive(Debug, Clone)]
+pub struct PortfolioOutcome {
+    /// 第一个产生验证状态的成员
+    pub winner: Option<usize>,
+    /// 所有成员共享的最低能量
+    pub best_energy: f64,
+    pub members: Vec<MemberOutcome>,
+}
+
+impl PortfolioOutcome {
+    /// 获胜成员的验证状态
+    pub fn verified_state(&self) -> Option<&AlgebraicState> {
+        let winner = self.winner?;
+        let member = self.members.iter().find(|m| m.index == winner)?;
+        member.outcome.is_verified().then_some(&member.outcome.state)
+    }
+
+    /// 全局最低能量的状态：所有成员返回的状态中几何能量最低者
+    pub fn best_state(&self) -> Option<&AlgebraicState> {
+        self.members
+            .iter()
+            .min_by(|a, b| a.outcome.energy.total_cmp(&b.outcome.energy))
+            .map(|m| &m.outcome.state)
+    }
+}
+
+type ObserverFactory = dyn Fn(usize) -> Box<dyn EngineObserver> + Send + Sync;
+
+/// 组合搜索 (Portfolio Search)
+///
+/// 在 rayon 线程池上并行运行 N 个 `EvolverEngine`，每个成员有自己的起始宇宙、种子与优化器参数。
+/// 所有成员通过 `SharedEnergy` 共享本次运行中的最低能量，远远落后的成员按 `LagPolicy` 重启；
+/// 第一个产生验证状态的成员胜出，其余成员在下一个 epoch 边界被取消。
+///
+/// 落后重启取决于其他成员的进度，因此成员的运行不一定能用其配置单独复现；
+/// 但每次重启都作为转移记录在成员的 `Trace` 中，胜出者的状态总可以由它的轨迹重放验证。
+pub struct Portfolio {
+    members: Vec<EngineConfig>,
+    observers: Option<Arc<ObserverFactory>>,
+    lag: LagPolicy,
+}
+
+impl Portfolio {
+    pub fn new() -> Self {
+        Self {
+            members: Vec::new(),
+            observers: None,
+            lag: LagPolicy::default(),
+        }
+    }
+
+    /// 由基准配置派生 `n` 个多样化的成员：
+    /// - 起始宇宙：从 `base.initial_p` 起依次取 p ≡ 1 (mod 4) 的素数
+    /// - 种子：由基准种子与成员序号混合
+    /// - 优化器：轮换曲率阈值与波束宽度 (保守 / 默认 / 激进)
+    pub fn diversified(base: EngineConfig, n: usize) -> Self {
+        let base_seed = base.effective_seed();
+        let mut optimizer_profiles = vec![base.optimizer.clone(); 3];
+        optimizer_profiles[1].curvature_threshold *= 0.5;
+        optimizer_profiles[1].beam_width += 3;
+        optimizer_profiles[2].curvature_threshold *= 2.0;
+        optimizer_profiles[2].beam_width = (optimizer_profiles[2].beam_width / 2).max(1);
+
+        let mut portfolio = Self::new();
+        let mut p = base.initial_p;
+        for i in 0..n {
+            while !(p % 4 == 1 && is_prime(p)) {
+                p += 1;
+            }
+            let member = base
+                .clone()
+                .initial_p(p)
+                .seed(base_seed ^ (i as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15))
+                .optimizer(optimizer_profiles[i % optimizer_profiles.len()].clone());
+            portfolio = portfolio.member(member);
+            p += 1;
+        }
+        portfolio
+    }
+
+    pub fn member(mut self, config: EngineConfig) -> Self {
+        self.members.push(config);
+        self
+    }
+
+    /// 落后成员的重启规则 (`factor` 为无穷大时从不重启)
+    pub fn lag_policy(mut self, policy: LagPolicy) -> Self {
+        self.lag = policy;
+        self
+    }
+
+    /// 为每个成员 (按序号) 创建一个观察者，例如每个线程一条进度条
+    pub fn observe<F>(mut self, factory: F) -> Self
+    where
+        F: Fn(usize) -> Box<dyn EngineObserver> + Send + Sync + 'static,
+    {
+        self.observers = Some(Arc::new(factory));
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    pub fn run(&self) -> PortfolioOutcome {
+        const NO_WINNER: usize = usize::MAX;
+
+        // 取消令牌与共享能量都属于单次运行：同一个组合可以重复运行
+        let cancel = CancelToken::new();
+        let winner = Arc::new(AtomicUsize::new(NO_WINNER));
+        let best_energy = Arc::new(SharedEnergy::new());
+
+        let members: Vec<MemberOutcome> = self
+            .members
+            .par_iter()
+            .enumerate()
+            .map(|(index, config)| {
+                let own_best = Arc::new(SharedEnergy::new());
+                let shared_best = best_energy.clone();
+                let member_best = own_best.clone();
+
+                let mut engine = EvolverEngine::with_config(config.clone())
+                    .cancel_token(cancel.clone())
+                    .share_energy(best_energy.clone(), self.lag)
+                    .observe(move |event: &EngineEvent| {
+                        if let EngineEvent::CandidateScored { loss, .. } = event {
+                            member_best.record(*loss);
+                            shared_best.record(*loss);
+                        }
+                    });
+                if let Some(factory) = &self.observers {
+                    let mut observer = factory(index);
+                    engine.add_observer(move |event: &EngineEvent| observer.on_event(event));
+                }
+
+                let outcome = engine.evolve();
+                // 起点与重启状态不产生 CandidateScored 事件，引擎自己的最佳状态也要计入
+                own_best.record(outcome.energy);
+                best_energy.record(outcome.energy);
+                if outcome.is_verified()
+                    && winner
+                        .compare_exchange(NO_WINNER, index, Ordering::SeqCst, Ordering::SeqCst)
+                        .is_ok()
+                {
+                    cancel.cancel();
+                }
+
+                MemberOutcome {
+                    index,
+                    initial_p: config.initial_p,
+                    best_energy: own_best.get(),
+                    outcome,
+                }
+            })
+            .collect();
+
+        let winner = winner.load(Ordering::SeqCst);
+        PortfolioOutcome {
+            winner: (winner != NO_WINNER).then_some(winner),
+            best_energy: best_energy.get(),
+            members,
+        }
+    }
+}
+
+impl Default for Portfolio {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::budget::StopReason;
+    use crate::will::restart::RestartReason;
+
+    #[test]
+    fn test_shared_energy_keeps_the_minimum_across_threads() {
+        let shared = Arc::new(SharedEnergy::new());
+        assert_eq!(shared.get(), f64::INFINITY);
+
+        (0..64u32).into_par_iter().for_each(|i| {
+            shared.record(100.0 - i as f64);
+        });
+        assert_eq!(shared.get(), 37.0);
+        assert!(!shared.record(40.0));
+        assert!(shared.record(1.5));
+        assert_eq!(shared.get(), 1.5);
+    }
+
+    #[test]
+    fn test_portfolio_reports_the_global_minimum() {
+        let base = EngineConfig::fixture(9).max_epochs(6);
+        let portfolio = Portfolio::diversified(base, 3);
+        let outcome = portfolio.run();
+
+        assert_eq!(outcome.members.len(), 3);
+        let global = outcome
+            .members
+            .iter()
+            .map(|m| m.best_energy)
+            .fold(f64::INFINITY, f64::min);
+        assert!(global.is_finite());
+        assert_eq!(outcome.best_energy, global);
+        for member in &outcome.members {
+            assert!(member.best_energy <= member.outcome.energy);
+        }
+        let best = outcome.best_state().unwrap();
+        let best_member = outcome.members.iter().find(|m| &m.outcome.state == best).unwrap();
+        assert_eq!(best_member.outcome.energy, global);
+    }
+
+    #[test]
+    fn test_shared_energy_is_reset_per_run() {
+        let member = EngineConfig::fixture(9).max_epochs(3);
+        let portfolio = Portfolio::new().member(member);
+        let first = portfolio.run();
+        let second = portfolio.run();
+        assert_eq!(first.best_energy, first.members[0].best_energy);
+        assert_eq!(second.best_energy, first.best_energy);
+    }
+
+    #[test]
+    fn test_lagging_member_restarts_from_its_seed() {
+        let shared = Arc::new(SharedEnergy::new());
+        shared.record(0.0);
+        let restarts = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = restarts.clone();
+        let mut engine = EvolverEngine::with_config(EngineConfig::fixture(3).max_epochs(6))
+            .share_energy(shared, LagPolicy { factor: 4.0, patience: 2 })
+            .observe(move |event: &EngineEvent| {
+                if let EngineEvent::Restart { epoch, reason, origin, .. } = event {
+                    sink.lock().unwrap().push((*epoch, *reason, *origin));
+                }
+            });
+        let outcome = engine.evolve();
+
+        let restarts = restarts.lock().unwrap();
+        let root = AlgebraicState::new_root(37).hash();
+        let lagging: Vec<_> = restarts.iter().filter(|r| r.1 == RestartReason::Lagging).collect();
+        assert!(!lagging.is_empty(), "{:?}", restarts);
+        assert!(lagging.iter().all(|r| r.2 == root));
+        assert!(outcome.trace.verify(engine.state()));
+    }
+
+    #[test]
+    fn test_verified_member_cancels_the_others() {
+        // 成员 0 的任何一步都算收敛；成员 1 永远无法收敛，只能被取消
+        let easy = EngineConfig::fixture(1).convergence_threshold(f64::INFINITY);
+        let hard = EngineConfig::default().initial_p(41).seed(2).convergence_threshold(0.0).max_epochs(100_000);
+        let outcome = Portfolio::new().member(easy).member(hard).run();
+
+        assert_eq!(outcome.winner, Some(0));
+        assert!(outcome.verified_state().is_some());
+        let loser = &outcome.members[1];
+        assert_eq!(loser.outcome.stop_reason, StopReason::Cancelled);
+        assert!(loser.outcome.epochs < 100_000);
+    }
+}
@@ -1,18 +1,97 @@
+// pyo3 0.19 的 #[pymethods] 在宏展开中生成非局部 impl
+#![allow(non_local_definitions)]
+
+use std::time::Duration;
+
 use pyo3::prelude::*;
 use crate::soul::algebra::IdealClass;
 use crate::soul::dynamics::{TimeEvolution, IdentityDynamics, VDFDynamics};
 use crate::will::optimizer::VapoOptimizer;
-use crate::will::evaluator::{Evaluator, GeometricEvaluator, StpEvaluator};
+use crate::will::evaluator::{Evaluator, FeatureMetric, GeometricEvaluator, StpEvaluator, TruthTable};
 use crate::body::projection::Projector;
+use crate::dsl::stp_bridge::LogicTarget;
+use crate::engine::budget::{CancelToken, EngineOutcome};
+use crate::engine::config::EngineConfig;
+use crate::engine::main_loop::EvolverEngine;
 
 pub mod soul;
 pub mod will;
 pub mod body;
 pub mod dsl;
+pub mod engine;
+
+/// Python 端的取消令牌：可以从任意 Python 线程调用 `cancel()`，
+/// 正在运行的 `PyEvolver.evolve` 在下一个 epoch 边界返回最佳状态。
+#[pyclass(name = "CancelToken")]
+#[derive(Clone, Default)]
+pub struct PyCancelToken(CancelToken);
+
+#[pymethods]
+impl PyCancelToken {
+    #[new]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.cancel();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.is_cancelled()
+    }
+}
+
+/// `PyEvolver.evolve` 的结果
+#[pyclass(name = "EngineOutcome")]
+pub struct PyEngineOutcome {
+    /// 停止原因 ('converged', 'deadline', 'cancelled', ...)
+    #[pyo3(get)]
+    stop_reason: String,
+    /// 返回的状态是否通过了严格验证
+    #[pyo3(get)]
+    verified: bool,
+    /// 返回状态的几何能量
+    #[pyo3(get)]
+    energy: f64,
+    /// 返回状态所在的宇宙
+    #[pyo3(get)]
+    p: u64,
+    /// 返回状态的哈希
+    #[pyo3(get)]
+    hash: u64,
+    #[pyo3(get)]
+    epochs: usize,
+    #[pyo3(get)]
+    evaluations: usize,
+    #[pyo3(get)]
+    migrations: usize,
+    /// 本次运行的种子 (以相同参数重新运行即可复现)
+    #[pyo3(get)]
+    seed: u64,
+}
+
+impl From<EngineOutcome> for PyEngineOutcome {
+    fn from(outcome: EngineOutcome) -> Self {
+        Self {
+            stop_reason: outcome.stop_reason.to_string(),
+            verified: outcome.is_verified(),
+            energy: outcome.energy,
+            p: outcome.state.p,
+            hash: outcome.state.hash(),
+            epochs: outcome.epochs,
+            evaluations: outcome.evaluations,
+            migrations: outcome.migrations,
+            seed: outcome.seed,
+        }
+    }
+}
 
 #[pyclass]
 pub struct PyEvolver {
+    #[pyo3(get)]
     p: u64, 
+    #[pyo3(get)]
     k: u64, 
     vdf_difficulty: usize,
     search_steps: usize,
@@ -33,8 +112,29 @@ impl PyEvolver {
 
     /// 对齐函数
     /// Context -> Algebraic Seed -> Search -> Valid Logic Path
-    #[pyo3(signature = (context, mode="prove", depth=16))]
-    pub fn align(&self, context: String, mode: &str, depth: usize) -> PyResult<Vec<u64>> {
+    ///
+    /// `metric` 选择 "fast" / "hybrid" 模式下几何评估器的特征度量：
+    /// 'euclidean', 'geodesic', 'cosine', 'sinkhorn'
+    ///
+    /// `truth_table` 是 "prove" 模式要求电路实现的真值表 (2^n 行，第 r 行输入 x_i = (r >> i) & 1)，
+    /// 默认为两输入异或。
+    #[pyo3(signature = (context, mode="prove", depth=16, metric="euclidean", truth_table=None))]
+    pub fn align(
+        &self,
+        context: String,
+        mode: &str,
+        depth: usize,
+        metric: &str,
+        truth_table: Option<Vec<bool>>,
+    ) -> PyResult<Vec<u64>> {
+        let metric: FeatureMetric = metric
+            .parse()
+            .map_err(pyo3::exceptions::PyValueError::new_err)?;
+        let truth_table = match truth_table {
+            Some(rows) => TruthTable::from_rows(rows).map_err(pyo3::exceptions::PyValueError::new_err)?,
+            None => TruthTable::default(),
+        };
+
         // 1. 生成初始种子
         let seed = IdealClass::from_hash(&context, self.p); 
         
@@ -43,24 +143,23 @@ impl PyEvolver {
         let eval_projector = Projector::new(self.p);
 
         // 3. 构造 Evaluator 和 Dynamics
-        // [Fix] 显式构建 target_features。
-        // 这里我们使用 Context Hash 生成的种子的连续特征作为“目标意图”。
-        // 这意味着 VAPO 会倾向于寻找几何上接近初始 Context，但逻辑上合法的状态。
-        let target_features = eval_projector.project_continuous(&seed);
+        // 目标意图取自 Context 的意图投影，与种子的哈希相互独立：
+        // VAPO 从种子出发，寻找几何上接近意图、且逻辑上合法的状态。
+        let target_features = eval_projector.project_intent(&context);
 
         let (dynamics, evaluator): (Box<dyn TimeEvolution>, Box<dyn Evaluator>) = match mode {
             "fast" | "native" => (
                 Box::new(IdentityDynamics),   
-                Box::new(GeometricEvaluator), 
+                Box::new(GeometricEvaluator::new(eval_projector, target_features).metric(metric)), 
             ),
             "prove" | "vdf" => (
                 Box::new(VDFDynamics::new(self.vdf_difficulty)), 
                 // [Fix] 参数对齐：传入 projector, depth, target_features
-                Box::new(StpEvaluator::new(eval_projector, depth, target_features)), 
+                Box::new(StpEvaluator::new(eval_projector, depth, target_features).truth_table(truth_table)), 
             ),
             "hybrid" => (
                 Box::new(VDFDynamics::new(self.vdf_difficulty)), 
-                Box::new(GeometricEvaluator),                    
+                Box::new(GeometricEvaluator::new(eval_projector, target_features).metric(metric)),
             ),
             _ => return Err(pyo3::exceptions::PyValueError::new_err(
                 "Unknown mode. Available modes: 'fast', 'prove', 'hybrid'",
@@ -92,10 +191,132 @@ impl PyEvolver {
 
         Ok(logic_path)
     }
+
+    /// 运行 Grand Loop，直到收敛或任一预算耗尽
+    ///
+    /// `time_limit` 为墙钟时限 (秒)；`cancel` 可以在另一个 Python 线程中触发。
+    /// 运行期间释放 GIL，因此其他 Python 线程可以继续执行。
+    // Python 的关键字参数：每个预算都是可选的
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (context=None, max_epochs=None, time_limit=None, max_evaluations=None, seed=None, cancel=None))]
+    pub fn evolve(
+        &self,
+        py: Python<'_>,
+        context: Option<String>,
+        max_epochs: Option<usize>,
+        time_limit: Option<f64>,
+        max_evaluations: Option<usize>,
+        seed: Option<u64>,
+        cancel: Option<PyCancelToken>,
+    ) -> PyResult<PyEngineOutcome> {
+        let config = self.engine_config(context, max_epochs, time_limit, max_evaluations, seed)?;
+        let outcome = py.allow_threads(move || {
+            let mut engine = EvolverEngine::with_config(config);
+            if let Some(token) = cancel {
+                engine = engine.cancel_token(token.0);
+            }
+            engine.evolve()
+        });
+        Ok(outcome.into())
+    }
+}
+
+impl PyEvolver {
+    fn engine_config(
+        &self,
+        context: Option<String>,
+        max_epochs: Option<usize>,
+        time_limit: Option<f64>,
+        max_evaluations: Option<usize>,
+        seed: Option<u64>,
+    ) -> PyResult<EngineConfig> {
+        let mut config = EngineConfig::default()
+            .initial_p(self.p)
+            .target(LogicTarget { context, ..LogicTarget::default() });
+        if let Some(epochs) = max_epochs {
+            config = config.max_epochs(epochs);
+        }
+        if let Some(secs) = time_limit {
+            let limit = Duration::try_from_secs_f64(secs)
+                .map_err(|_| pyo3::exceptions::PyValueError::new_err("time_limit must be a non-negative number of seconds"))?;
+            config = config.time_limit(limit);
+        }
+        if let Some(evaluations) = max_evaluations {
+            config = config.max_evaluations(evaluations);
+        }
+        if let Some(seed) = seed {
+            config = config.seed(seed);
+        }
+        Ok(config)
+    }
 }
 
 #[pymodule]
 fn new_evolver(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<PyEvolver>()?;
+    m.add_class::<PyCancelToken>()?;
+    m.add_class::<PyEngineOutcome>()?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTEXT: &str = "the sum of two odd integers is even";
+
+    #[test]
+    fn test_intent_is_not_the_seed() {
+        let projector = Projector::new(37);
+        let seed = IdealClass::from_hash(CONTEXT, 37);
+        let evaluator = GeometricEvaluator::new(projector.clone(), projector.project_intent(CONTEXT));
+
+        // 目标与起点重合时搜索原地收敛；意图投影必须给出一个非零的起点能量
+        assert!(evaluator.evaluate(&seed) > 1e-3);
+
+        let (found, _trace) = VapoOptimizer::new(Box::new(evaluator), 50).search(&seed);
+        let evaluator = GeometricEvaluator::new(projector.clone(), projector.project_intent(CONTEXT));
+        assert!(evaluator.evaluate(&found) < evaluator.evaluate(&seed));
+    }
+
+    /// `evolve` 去掉 GIL 释放后的部分 (测试中没有 Python 解释器)
+    fn run(evolver: &PyEvolver, time_limit: Option<f64>, cancel: Option<&PyCancelToken>) -> PyEngineOutcome {
+        let config = evolver
+            .engine_config(Some(CONTEXT.to_string()), Some(1_000), time_limit, None, Some(3))
+            .unwrap();
+        let mut engine = EvolverEngine::with_config(config);
+        if let Some(token) = cancel {
+            engine = engine.cancel_token(token.0.clone());
+        }
+        engine.evolve().into()
+    }
+
+    #[test]
+    fn test_evolve_reports_the_stop_reason() {
+        let evolver = PyEvolver::new(37, 2, None, None);
+
+        let token = PyCancelToken::new();
+        token.cancel();
+        assert!(token.is_cancelled());
+        let outcome = run(&evolver, None, Some(&token));
+        assert_eq!(outcome.stop_reason, "cancelled");
+        assert!(!outcome.verified);
+        assert_eq!((outcome.epochs, outcome.seed, outcome.p), (0, 3, 37));
+
+        assert_eq!(run(&evolver, Some(0.0), None).stop_reason, "deadline");
+        assert!(evolver.engine_config(None, None, Some(-1.0), None, None).is_err());
+        assert!(evolver.engine_config(None, None, Some(f64::NAN), None, None).is_err());
+    }
+
+    #[test]
+    fn test_fast_mode_moves_off_the_seed() {
+        let evolver = PyEvolver::new(37, 2, None, Some(50));
+        let path = evolver.align(CONTEXT.to_string(), "fast", 4, "euclidean", None).unwrap();
+
+        // fast 模式的动力学是恒等映射：路径是搜索结果在各时刻的精确投影
+        let projector = Projector::new(37);
+        let seed = IdealClass::from_hash(CONTEXT, 37);
+        let seed_path: Vec<u64> = (0..4).map(|t| projector.project_exact(&seed, t)).collect();
+        assert_ne!(path, seed_path);
+    }
+}
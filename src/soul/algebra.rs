@@ -1,6 +1,20 @@
 use sha2::{Digest, Sha256};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt;
-use std::ops::{Add, Mul, Neg, Sub};
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use std::ops::{Add, Mul, Sub};
+
+use nalgebra::DVector;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::body::projection::Projector;
+use crate::soul::hecke::{HeckeAlphabet, HeckeMove};
+use crate::soul::lattice::gcd;
 
 // ============================================================================
 // Constants defining the Definite Quaternion Algebra B_{p, \infty}
@@ -15,7 +29,7 @@ const PARAM_B: i64 = -ALGEBRA_P;
 
 /// A Quaternion q = a + bi + cj + dk in the algebra B_{p, \infty}.
 /// This is the atomic "word" of our causal language.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Quaternion {
     pub a: i64, // Scalar part
     pub b: i64, // i coeff
@@ -56,6 +70,31 @@ impl Quaternion {
     pub fn conjugate(&self) -> Self {
         Self::new(self.a, -self.b, -self.c, -self.d)
     }
+
+    /// The content: gcd of the four coordinates (1 for the zero quaternion).
+    pub fn content(&self) -> i64 {
+        [self.b, self.c, self.d]
+            .iter()
+            .fold(self.a as i128, |g, &x| gcd(g, x as i128))
+            .max(1) as i64
+    }
+
+    /// q / content(q): the primitive quaternion on the same line through the origin.
+    pub fn primitive(&self) -> Self {
+        let content = self.content();
+        Self::new(self.a / content, self.b / content, self.c / content, self.d / content)
+    }
+}
+
+/// Arithmetic failures of the lattice walk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum AlgebraError {
+    /// The move has no generator in this universe (l = p, or l has no splitting).
+    #[error("move T_{}[{}] has no generator in B_{{{}, inf}}", .0.ell, .0.label, .1)]
+    NoGenerator(HeckeMove, u64),
+    /// A coefficient of the product does not fit in an i64, or its reduced norm in an i128.
+    #[error("quaternion product overflows the i64 coefficients or the i128 norm")]
+    Overflow,
 }
 
 // ----------------------------------------------------------------------------
@@ -100,20 +139,20 @@ impl Mul for Quaternion {
         let a1 = self.a; let b1 = self.b; let c1 = self.c; let d1 = self.d;
         let a2 = rhs.a; let b2 = rhs.b; let c2 = rhs.c; let d2 = rhs.d;
 
-        let A = PARAM_A;
-        let B = PARAM_B;
+        let pa = PARAM_A;
+        let pb = PARAM_B;
 
         // Real part
-        let ra = a1*a2 + A*b1*b2 + B*c1*c2 - A*B*d1*d2;
+        let ra = a1*a2 + pa*b1*b2 + pb*c1*c2 - pa*pb*d1*d2;
         
         // i part
-        let rb = a1*b2 + b1*a2 - B*c1*d2 + B*d1*c2;
+        let rb = a1*b2 + b1*a2 - pb*c1*d2 + pb*d1*c2;
 
         // j part
-        let rc = a1*c2 + A*b1*d2 + c1*a2 - A*d1*b2;
+        let rc = a1*c2 + pa*b1*d2 + c1*a2 - pa*d1*b2;
 
         // k part
-        let rd = a1*d2 - b1*c2 + c1*b2 + d1*a2;
+        let rd = a1*d2 + b1*c2 - c1*b2 + d1*a2;
 
         Self::new(ra, rb, rc, rd)
     }
@@ -207,24 +246,432 @@ impl IdealClass {
         // Since i^2 = -1, j^2 = -37, k^2 = -37.
         // Norm = a^2 + b^2 + 37c^2 + 37d^2
         
-        let mut moves = Vec::new();
-        
-        // 1. Trivial generators (if they exist in this algebra structure)
         // For demonstration, we use a deterministic set of small perturbations
         // that represent the 'directions' in the Cayley graph.
-        
-        // Generator 1: 6^2 + 1^2 = 37. (a=6, b=1, c=0, d=0) -> Norm = 36 + 1 = 37.
-        moves.push(self.apply_hecke(&Quaternion::new(6, 1, 0, 0)));
-        moves.push(self.apply_hecke(&Quaternion::new(6, -1, 0, 0)));
-        
-        // Generator 2: 1^2 + 6^2 = 37.
-        moves.push(self.apply_hecke(&Quaternion::new(1, 6, 0, 0)));
-        moves.push(self.apply_hecke(&Quaternion::new(1, -6, 0, 0)));
-
         // In a real Pizer graph, there are p+1 neighbors for T_p.
-        // We include "Identity-like" perturbations for fine-tuning.
-        moves.push(self.apply_hecke(&Quaternion::new(1, 0, 0, 0))); // Self-loop (Stay)
+        // No identity self-loop: "staying" is not a move, and it lets a walker stall forever.
+        vec![
+            // Generator 1: 6^2 + 1^2 = 37. (a=6, b=1, c=0, d=0) -> Norm = 36 + 1 = 37.
+            self.apply_hecke(&Quaternion::new(6, 1, 0, 0)),
+            self.apply_hecke(&Quaternion::new(6, -1, 0, 0)),
+            // Generator 2: 1^2 + 6^2 = 37.
+            self.apply_hecke(&Quaternion::new(1, 6, 0, 0)),
+            self.apply_hecke(&Quaternion::new(1, -6, 0, 0)),
+        ]
+    }
+}
+
+// ============================================================================
+// The Multiverse: p-Parametric Algebra B_{p, \infty}
+// The constants above pin the legacy IdealClass to p = 37. Migration between
+// universes (SpectralGovernor / StateLifter) needs the algebra as a value.
+// ============================================================================
+
+/// The Definite Quaternion Algebra B_{p, \infty} for an arbitrary prime p.
+/// Same presentation as the constants above: i^2 = -1, j^2 = -p, ij = k.
+/// The working order is O = Z<1, i, j, k>.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuaternionAlgebra {
+    pub p: u64,
+    param_a: i64,
+    param_b: i64,
+}
+
+impl QuaternionAlgebra {
+    pub fn new(p: u64) -> Self {
+        Self {
+            p,
+            param_a: -1,
+            param_b: -(p as i64),
+        }
+    }
+
+    /// Non-commutative multiplication in this universe.
+    /// Same multiplication table as `impl Mul for Quaternion`, with B = -p.
+    ///
+    /// Panics if a coefficient of the product overflows; products along an unbounded
+    /// walk must go through `checked_mul`.
+    pub fn mul(&self, x: &Quaternion, y: &Quaternion) -> Quaternion {
+        self.checked_mul(x, y)
+            .expect("quaternion product overflows i64; use checked_mul")
+    }
+
+    /// Multiplication with the sums accumulated in checked i128 arithmetic.
+    /// Fails with `AlgebraError::Overflow` instead of wrapping when a coefficient leaves i64.
+    pub fn checked_mul(&self, x: &Quaternion, y: &Quaternion) -> Result<Quaternion, AlgebraError> {
+        let a = self.param_a as i128;
+        let b = self.param_b as i128;
+
+        // Each coefficient is a sum of four weighted products w * u * v.
+        let coefficient = |terms: [(i128, i64, i64); 4]| -> Result<i64, AlgebraError> {
+            let sum = terms.iter().try_fold(0i128, |acc, &(w, u, v)| {
+                (u as i128)
+                    .checked_mul(v as i128)
+                    .and_then(|uv| uv.checked_mul(w))
+                    .and_then(|t| acc.checked_add(t))
+            });
+            sum.and_then(|s| i64::try_from(s).ok()).ok_or(AlgebraError::Overflow)
+        };
+
+        Ok(Quaternion::new(
+            coefficient([(1, x.a, y.a), (a, x.b, y.b), (b, x.c, y.c), (-a * b, x.d, y.d)])?,
+            coefficient([(1, x.a, y.b), (1, x.b, y.a), (-b, x.c, y.d), (b, x.d, y.c)])?,
+            coefficient([(1, x.a, y.c), (a, x.b, y.d), (1, x.c, y.a), (-a, x.d, y.b)])?,
+            coefficient([(1, x.a, y.d), (1, x.b, y.c), (-1, x.c, y.b), (1, x.d, y.a)])?,
+        ))
+    }
+
+    /// The diagonal of the reduced norm form on the basis {1, i, j, k}:
+    /// N(q) = a^2 + b^2 + p c^2 + p d^2
+    pub fn norm_form(&self) -> [i128; 4] {
+        let a = self.param_a as i128;
+        let b = self.param_b as i128;
+        [1, -a, -b, a * b]
+    }
+
+    /// The reduced norm in this universe.
+    pub fn norm(&self, q: &Quaternion) -> i128 {
+        let form = self.norm_form();
+        let coords = [q.a as i128, q.b as i128, q.c as i128, q.d as i128];
+        coords.iter().zip(form.iter()).map(|(x, w)| w * x * x).sum()
+    }
+
+    /// The reduced norm, or `None` if it does not fit in an i128.
+    pub fn checked_norm(&self, q: &Quaternion) -> Option<i128> {
+        let form = self.norm_form();
+        let coords = [q.a as i128, q.b as i128, q.c as i128, q.d as i128];
+        coords.iter().zip(form.iter()).try_fold(0i128, |acc, (x, w)| {
+            x.checked_mul(*x)
+                .and_then(|xx| xx.checked_mul(*w))
+                .and_then(|t| acc.checked_add(t))
+        })
+    }
+
+    /// The basis {1, i, j, k} of the working order O.
+    pub fn order_basis(&self) -> [Quaternion; 4] {
+        [
+            Quaternion::new(1, 0, 0, 0),
+            Quaternion::new(0, 1, 0, 0),
+            Quaternion::new(0, 0, 1, 0),
+            Quaternion::new(0, 0, 0, 1),
+        ]
+    }
+
+    /// The migration-invariant move alphabet of this universe:
+    /// each abstract T_l move (l, t in P^1(F_l)) with its concrete generator for this p.
+    pub fn alphabet(&self) -> Arc<HeckeAlphabet> {
+        HeckeAlphabet::for_prime(self.p)
+    }
+
+    /// Concrete Hecke generators of this universe, in canonical move order.
+    pub fn generators(&self) -> Vec<Quaternion> {
+        let alphabet = self.alphabet();
+        alphabet
+            .moves()
+            .iter()
+            .filter_map(|mv| alphabet.generator(mv))
+            .collect()
+    }
+
+    /// All one-step Hecke neighbors of a state in this universe.
+    pub fn generate_neighbors(&self, state: &AlgebraicState) -> Vec<AlgebraicState> {
+        self.alphabet()
+            .moves()
+            .iter()
+            .filter_map(|mv| state.apply_move(mv))
+            .collect()
+    }
+
+    /// Replays an abstract move sequence from the origin of this universe.
+    /// Each step is mapped to this universe's generator for the same (l, t) label,
+    /// so the syntax of the path survives a migration even though the coordinates do not.
+    /// Moves unavailable here (l = p) are skipped.
+    pub fn replay_path(&self, path: &[HeckeMove]) -> AlgebraicState {
+        self.replay_from(Quaternion::identity(), path)
+    }
+
+    /// Replays an abstract move sequence from an anchored origin.
+    pub fn replay_from(&self, origin: Quaternion, path: &[HeckeMove]) -> AlgebraicState {
+        path.iter().fold(AlgebraicState::anchored(self.p, origin), |state, mv| {
+            state.apply_move(mv).unwrap_or(state)
+        })
+    }
+}
+
+/// A state of the Grand Loop: a position in the lattice of B_{p, \infty}
+/// together with the generator path that produced it.
+///
+/// Unlike `IdealClass`, the universe p is part of the state, so a state can be
+/// lifted from one algebra into another.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AlgebraicState {
+    /// The accumulator quaternion S = origin * g_1 * g_2 * ... * g_n, up to a positive
+    /// integer: the content is divided out after every move (see `try_apply_move`).
+    pub value: Quaternion,
+    /// The prime of the universe this state lives in.
+    pub p: u64,
+    /// The lattice point the path starts from: the identity for `new_root`,
+    /// or a requantized point for `anchored` states (see `StateLifter`).
+    pub origin: Quaternion,
+    /// The abstract moves applied from the origin, in causal order.
+    pub path_history: Vec<HeckeMove>,
+}
+
+impl AlgebraicState {
+    /// The origin of the universe B_{p, \infty}.
+    pub fn new_root(p: u64) -> Self {
+        Self::anchored(p, Quaternion::identity())
+    }
+
+    /// A path-free state sitting at an arbitrary lattice point of B_{p, \infty}.
+    /// Replaying `path_history` from `origin` reproduces `value`.
+    pub fn anchored(p: u64, origin: Quaternion) -> Self {
+        Self {
+            value: origin,
+            p,
+            origin,
+            path_history: Vec::new(),
+        }
+    }
+
+    pub fn algebra(&self) -> QuaternionAlgebra {
+        QuaternionAlgebra::new(self.p)
+    }
+
+    /// S_next = S_current * G(mv), recording the abstract move in the path.
+    /// Returns `None` if the move has no generator in this universe or the product
+    /// leaves the i64 coefficient range (see `try_apply_move`).
+    pub fn apply_move(&self, mv: &HeckeMove) -> Option<Self> {
+        self.try_apply_move(mv).ok()
+    }
+
+    /// S_next = S_current * G(mv) / content, recording the abstract move in the path.
+    ///
+    /// Dividing out the content does not change the ideal class (`canonical_hash`), and it
+    /// undoes backtracking exactly: S * g * conj(g) = l * S reduces back to S. The norm of a
+    /// non-backtracking walk still grows like l^n, so a long enough walk reaches the end of the
+    /// i64 range (or its norm the i128 range); that move fails with `AlgebraError::Overflow`
+    /// instead of wrapping around.
+    pub fn try_apply_move(&self, mv: &HeckeMove) -> Result<Self, AlgebraError> {
+        let algebra = self.algebra();
+        let generator = algebra
+            .alphabet()
+            .generator(mv)
+            .ok_or(AlgebraError::NoGenerator(*mv, self.p))?;
+        let value = algebra.checked_mul(&self.value, &generator)?.primitive();
+        // Norms are read all over the engine; a state whose norm cannot be represented is not reachable
+        algebra.checked_norm(&value).ok_or(AlgebraError::Overflow)?;
+
+        let mut path_history = self.path_history.clone();
+        path_history.push(*mv);
+
+        Ok(Self {
+            value,
+            p: self.p,
+            origin: self.origin,
+            path_history,
+        })
+    }
+
+    /// Breadth-first exploration of the local Cayley subgraph around this state.
+    /// `radius` bounds the number of expanded vertices (the generator count makes a
+    /// depth bound explode exponentially).
+    ///
+    /// Returns the vertex hashes and the adjacency of every expanded vertex,
+    /// in the format consumed by `SpectralGovernor::check_spectral_gap`.
+    pub fn explore_local_graph(&self, radius: usize) -> (HashSet<u64>, HashMap<u64, Vec<u64>>) {
+        let algebra = self.algebra();
+        let mut nodes = HashSet::new();
+        let mut adjacency = HashMap::new();
+        let mut queue = VecDeque::new();
+
+        nodes.insert(self.hash());
+        queue.push_back(self.clone());
+
+        while let Some(state) = queue.pop_front() {
+            if adjacency.len() >= radius {
+                break;
+            }
+
+            let mut edges = Vec::new();
+            for neighbor in algebra.generate_neighbors(&state) {
+                let h = neighbor.hash();
+                edges.push(h);
+                if nodes.insert(h) {
+                    queue.push_back(neighbor);
+                }
+            }
+            adjacency.insert(state.hash(), edges);
+        }
+
+        (nodes, adjacency)
+    }
+
+    /// All one-step Hecke neighbors of this state.
+    pub fn generate_neighbors(&self) -> Vec<AlgebraicState> {
+        self.algebra().generate_neighbors(self)
+    }
+
+    /// The local point cloud of this state in feature space: the continuous projections
+    /// of up to `size` states of its Cayley neighborhood (the state itself first).
+    pub fn generate_embedding_cloud(&self, size: usize) -> Vec<DVector<f64>> {
+        let projector = Projector::new(self.p);
+        let mut seen = HashSet::new();
+        let mut queue = VecDeque::new();
+        let mut cloud = Vec::with_capacity(size);
+
+        seen.insert(self.hash());
+        queue.push_back(self.clone());
+
+        while let Some(state) = queue.pop_front() {
+            if cloud.len() >= size {
+                break;
+            }
+            cloud.push(DVector::from_vec(projector.project_quaternion(&state.value)));
+
+            for neighbor in state.generate_neighbors() {
+                if seen.insert(neighbor.hash()) {
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        cloud
+    }
+
+    /// One-step neighbors excluding immediate backtracking: the move labelled by the
+    /// conjugate of the last generator walks the same l-neighbor edge back, so it is not offered.
+    pub fn forward_neighbors(&self) -> Vec<AlgebraicState> {
+        let alphabet = self.algebra().alphabet();
+        let backtrack = self.path_history.last().and_then(|mv| alphabet.inverse(mv));
+
+        alphabet
+            .moves()
+            .iter()
+            .filter(|mv| Some(**mv) != backtrack)
+            .filter_map(|mv| self.apply_move(mv))
+            .collect()
+    }
+
+    /// A non-backtracking random walk of `length` real generators.
+    /// Every step is appended to `path_history`, so the walk can be read back from the
+    /// path tail and replayed; it is a pure function of the RNG state.
+    pub fn random_walk<R: Rng + ?Sized>(&self, length: usize, rng: &mut R) -> Self {
+        let alphabet = self.algebra().alphabet();
+        let moves = alphabet.moves();
+        let mut state = self.clone();
+
+        for _ in 0..length {
+            let backtrack = state.path_history.last().and_then(|mv| alphabet.inverse(mv));
+            let forward: Vec<&HeckeMove> = moves.iter().filter(|mv| Some(**mv) != backtrack).collect();
+            if let Some(next) = forward.choose(rng).and_then(|mv| state.apply_move(mv)) {
+                state = next;
+            }
+        }
+        state
+    }
+
+    /// Identity of the right ideal class of the lattice point: the value is divided by its
+    /// content (gcd of the coordinates) and the representative is chosen canonically among
+    /// the right-unit multiples S * u, u in O^x = {+-1, +-i}. So S, n * S and S * i collide.
+    pub fn canonical_hash(&self) -> u64 {
+        let primitive = self.value.primitive();
+
+        let algebra = self.algebra();
+        let units = [
+            Quaternion::identity(),
+            Quaternion::new(0, 1, 0, 0),
+            Quaternion::new(-1, 0, 0, 0),
+            Quaternion::new(0, -1, 0, 0),
+        ];
+        let representative = units
+            .iter()
+            .map(|u| algebra.mul(&primitive, u))
+            .max_by_key(|r| (r.a, r.b, r.c, r.d))
+            .unwrap_or(primitive);
+
+        let mut hasher = DefaultHasher::new();
+        self.p.hash(&mut hasher);
+        representative.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Identity of the lattice point (universe + position), independent of the path taken.
+    pub fn hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.p.hash(&mut hasher);
+        self.value.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::soul::entropy::rng_from_seed;
+
+    #[test]
+    fn test_thousand_moves_never_wrap() {
+        // 1000 次随机移动 (含回溯)：要么成功且范数为正，要么报告溢出，绝不静默回绕
+        let mut rng = rng_from_seed(7);
+        let mut state = AlgebraicState::new_root(37);
+        let moves = state.algebra().alphabet().moves();
+        let mut overflowed = 0;
+
+        for _ in 0..1000 {
+            let mv = *moves.choose(&mut rng).unwrap();
+            match state.try_apply_move(&mv) {
+                Ok(next) => {
+                    assert!(next.algebra().norm(&next.value) > 0);
+                    assert_eq!(next.value.content(), 1);
+                    state = next;
+                }
+                Err(AlgebraError::Overflow) => overflowed += 1,
+                Err(e) => panic!("unexpected error: {e}"),
+            }
+        }
+        assert_eq!(state.path_history.len() + overflowed, 1000);
+    }
+
+    #[test]
+    fn test_backtracking_is_reduced_by_content() {
+        // S * g * conj(g) = N(g) * S，除以 content 后回到 S：500 对往返不增长
+        let algebra = QuaternionAlgebra::new(37);
+        let generators = algebra.generators();
+        let start = AlgebraicState::new_root(37).value;
+        let mut value = start;
+
+        for step in 0..500 {
+            let g = generators[step % generators.len()];
+            let forward = algebra.checked_mul(&value, &g).unwrap().primitive();
+            value = algebra.checked_mul(&forward, &g.conjugate()).unwrap().primitive();
+        }
+        assert_eq!(value, start);
+    }
+
+    #[test]
+    fn test_product_is_associative_and_multiplicative() {
+        let algebra = QuaternionAlgebra::new(37);
+        let qs = [
+            Quaternion::new(1, 3, 2, 1),
+            Quaternion::new(-2, 0, 1, 4),
+            Quaternion::new(0, 1, -1, 2),
+        ];
+        let (x, y, z) = (qs[0], qs[1], qs[2]);
+
+        let left = algebra.mul(&algebra.mul(&x, &y), &z);
+        let right = algebra.mul(&x, &algebra.mul(&y, &z));
+        assert_eq!(left, right);
+        assert_eq!(x * y, algebra.mul(&x, &y));
+        assert_eq!(algebra.norm(&algebra.mul(&x, &y)), algebra.norm(&x) * algebra.norm(&y));
+        assert_eq!(algebra.mul(&x, &x.conjugate()), Quaternion::new(algebra.norm(&x) as i64, 0, 0, 0));
+    }
 
-        moves
+    #[test]
+    fn test_checked_mul_reports_overflow() {
+        let algebra = QuaternionAlgebra::new(37);
+        let huge = Quaternion::new(i64::MAX / 2, 0, i64::MAX / 2, 0);
+        let g = algebra.generators()[0];
+        assert_eq!(algebra.checked_mul(&huge, &g), Err(AlgebraError::Overflow));
     }
 }
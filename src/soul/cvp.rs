@@ -0,0 +1,399 @@
+// Copyright (c) 2025 M-Patek
+// Part of the Evolver Project
+//
+// "Every real intention has a nearest integer truth."
+
+use crate::soul::algebra::{Quaternion, QuaternionAlgebra};
+use crate::soul::lattice::QuaternionLattice;
+
+/// CVP 求解方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CvpMethod {
+    /// Babai 最近平面算法 (在 LLL 约化基上)
+    Babai,
+    /// Kannan 嵌入：把 CVP 转化为 n+1 维格上的 SVP
+    Embedding,
+}
+
+/// 最近向量问题的解
+#[derive(Debug, Clone)]
+pub struct CvpSolution {
+    /// 解在原格基下的整数坐标
+    pub coeffs: [i64; 4],
+    /// 解对应的四元数 (格点)
+    pub point: Quaternion,
+    /// 范数形式下的平方距离 N(point - target)
+    pub distance_sq: f64,
+    /// 产生该解的算法
+    pub method: CvpMethod,
+}
+
+/// 最近向量求解器 (Closest Vector Solver)
+///
+/// 对 B_{p,∞} 中的任意格 (理想或序) 与实数目标 t ∈ R^4 (基 {1, i, j, k} 下的坐标)，
+/// 求使 N(x - t) 最小的格点 x。度量是范数形式本身：坐标按 sqrt(w_t) 缩放后即为欧氏度量。
+///
+/// 流程：
+/// 1. LLL 约化格基 (保留幺模变换，使结果可以精确地映射回整数坐标)。
+/// 2. Babai 最近平面。若目标落在 Gram-Schmidt 盒的内切球内，Babai 的解就是精确最近点。
+/// 3. 否则使用 Kannan 嵌入作为后备，取两者中更近的一个。
+#[derive(Debug, Clone)]
+pub struct ClosestVectorSolver {
+    /// LLL 的 Lovász 参数 (1/4 < delta < 1)
+    delta: f64,
+}
+
+impl ClosestVectorSolver {
+    pub fn new() -> Self {
+        Self { delta: 0.99 }
+    }
+
+    pub fn delta(mut self, delta: f64) -> Self {
+        self.delta = delta.clamp(0.26, 0.999);
+        self
+    }
+
+    /// 求格 `lattice` 中最接近 `target` 的格点
+    pub fn solve(&self, lattice: &QuaternionLattice, target: &[f64; 4]) -> CvpSolution {
+        let scale: Vec<f64> = QuaternionAlgebra::new(lattice.p)
+            .norm_form()
+            .iter()
+            .map(|&w| (w as f64).sqrt())
+            .collect();
+
+        // 嵌入到欧氏空间 R^4
+        let basis: Vec<Vec<f64>> = lattice
+            .basis
+            .iter()
+            .map(|row| (0..4).map(|t| row[t] as f64 * scale[t]).collect())
+            .collect();
+        let t: Vec<f64> = (0..4).map(|i| target[i] * scale[i]).collect();
+
+        let lll = lll_reduce(&basis, self.delta);
+
+        // --- Babai ---
+        let babai_local = babai_nearest_plane(&lll.basis, &t);
+        let babai_coeffs = compose(&babai_local, &lll.transform);
+        let babai = self.solution(lattice, &babai_coeffs, target, CvpMethod::Babai);
+
+        // Babai 的精确性判据：距离 < min ||b*_i|| / 2。
+        // LLL 未收敛时基未必约化，判据仍成立但很少触发，直接交给嵌入。
+        let gs = gram_schmidt(&lll.basis);
+        let min_gs_sq = gs.iter().map(|v| dot(v, v)).fold(f64::INFINITY, f64::min);
+        if lll.converged && babai.distance_sq < min_gs_sq / 4.0 {
+            return babai;
+        }
+
+        // --- Kannan Embedding ---
+        match kannan_embedding(&basis, &t, babai.distance_sq.sqrt(), self.delta) {
+            Some(coeffs) => {
+                let embedded = self.solution(lattice, &coeffs, target, CvpMethod::Embedding);
+                if embedded.distance_sq < babai.distance_sq {
+                    embedded
+                } else {
+                    babai
+                }
+            }
+            None => babai,
+        }
+    }
+
+    fn solution(
+        &self,
+        lattice: &QuaternionLattice,
+        coeffs: &[i64],
+        target: &[f64; 4],
+        method: CvpMethod,
+    ) -> CvpSolution {
+        let coeffs = [coeffs[0], coeffs[1], coeffs[2], coeffs[3]];
+        let point = lattice.element(&coeffs);
+        let weights = QuaternionAlgebra::new(lattice.p).norm_form();
+
+        let coords = [point.a, point.b, point.c, point.d];
+        let distance_sq = (0..4)
+            .map(|t| weights[t] as f64 * (coords[t] as f64 - target[t]).powi(2))
+            .sum();
+
+        CvpSolution {
+            coeffs,
+            point,
+            distance_sq,
+            method,
+        }
+    }
+}
+
+impl Default for ClosestVectorSolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Generic real-lattice kernels (any dimension)
+// ----------------------------------------------------------------------------
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b.iter()).map(|(x, y)| x * y).sum()
+}
+
+fn gram_schmidt(basis: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let mut ortho: Vec<Vec<f64>> = Vec::with_capacity(basis.len());
+    for b in basis {
+        let mut v = b.clone();
+        for u in &ortho {
+            let uu = dot(u, u);
+            if uu > 1e-12 {
+                let mu = dot(b, u) / uu;
+                for (x, y) in v.iter_mut().zip(u.iter()) {
+                    *x -= mu * y;
+                }
+            }
+        }
+        ortho.push(v);
+    }
+    ortho
+}
+
+/// LLL 主循环的迭代上限 (浮点误差可能使交换无限往复)
+const MAX_LLL_ITERATIONS: usize = 10_000;
+
+/// LLL 约化的结果
+#[derive(Debug, Clone)]
+pub struct LllReduction {
+    /// 约化后的基：basis = transform · 原基
+    pub basis: Vec<Vec<f64>>,
+    /// 整数幺模变换矩阵
+    pub transform: Vec<Vec<i64>>,
+    /// 是否在迭代上限内满足 Lovász 条件。为 false 时基仍是同一个格的基，只是未必约化。
+    pub converged: bool,
+}
+
+/// Gram-Schmidt 系数：(mu[i][j] = <b_i, b*_j> / |b*_j|^2, |b*_i|^2)
+fn gram_schmidt_coefficients(basis: &[Vec<f64>]) -> (Vec<Vec<f64>>, Vec<f64>) {
+    let n = basis.len();
+    let gs = gram_schmidt(basis);
+    let norms: Vec<f64> = gs.iter().map(|v| dot(v, v)).collect();
+    let mu = (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| if j < i && norms[j] > 1e-12 { dot(&basis[i], &gs[j]) / norms[j] } else { 0.0 })
+                .collect()
+        })
+        .collect();
+    (mu, norms)
+}
+
+/// LLL 约化 (Lenstra–Lenstra–Lovász)
+///
+/// Gram-Schmidt 系数 μ 与 |b*_i|^2 只在开始时计算一次，之后随尺寸约化与交换增量更新
+/// (Cohen, Algorithm 2.6.3)。只有交换遇到退化的 b* 时才重新计算。
+pub fn lll_reduce(basis: &[Vec<f64>], delta: f64) -> LllReduction {
+    lll_reduce_within(basis, delta, MAX_LLL_ITERATIONS)
+}
+
+fn lll_reduce_within(basis: &[Vec<f64>], delta: f64, max_iterations: usize) -> LllReduction {
+    let n = basis.len();
+    let mut b: Vec<Vec<f64>> = basis.to_vec();
+    let mut u: Vec<Vec<i64>> = (0..n)
+        .map(|i| (0..n).map(|j| if i == j { 1 } else { 0 }).collect())
+        .collect();
+    let (mut mu, mut norms) = gram_schmidt_coefficients(&b);
+
+    let mut k = 1;
+    let mut iterations = 0;
+    while k < n && iterations < max_iterations {
+        iterations += 1;
+
+        // Size reduction：b_k -= r·b_j 只改变第 k 行的 μ，b* 不变
+        for j in (0..k).rev() {
+            let r = mu[k][j].round();
+            if r != 0.0 {
+                let (bj, uj) = (b[j].clone(), u[j].clone());
+                for (x, y) in b[k].iter_mut().zip(bj.iter()) {
+                    *x -= r * y;
+                }
+                for (x, y) in u[k].iter_mut().zip(uj.iter()) {
+                    *x -= r as i64 * y;
+                }
+                let mu_j = mu[j].clone();
+                for (x, y) in mu[k][..j].iter_mut().zip(mu_j.iter()) {
+                    *x -= r * y;
+                }
+                mu[k][j] -= r;
+            }
+        }
+
+        // Lovász condition
+        let m = mu[k][k - 1];
+        if norms[k] >= (delta - m * m) * norms[k - 1] {
+            k += 1;
+            continue;
+        }
+
+        b.swap(k, k - 1);
+        u.swap(k, k - 1);
+        let merged = norms[k] + m * m * norms[k - 1];
+        if merged > 1e-12 {
+            // 交换 b_{k-1}, b_k 后的增量更新
+            let (upper, lower) = mu.split_at_mut(k);
+            upper[k - 1][..k - 1].swap_with_slice(&mut lower[0][..k - 1]);
+            mu[k][k - 1] = m * norms[k - 1] / merged;
+            norms[k] = norms[k - 1] * norms[k] / merged;
+            norms[k - 1] = merged;
+            for i in k + 1..n {
+                let t = mu[i][k];
+                mu[i][k] = mu[i][k - 1] - m * t;
+                mu[i][k - 1] = t + mu[k][k - 1] * mu[i][k];
+            }
+        } else {
+            (mu, norms) = gram_schmidt_coefficients(&b);
+        }
+        k = (k - 1).max(1);
+    }
+
+    LllReduction { basis: b, transform: u, converged: k >= n }
+}
+
+/// Babai 最近平面算法。返回相对于 `basis` 的整数坐标。
+pub fn babai_nearest_plane(basis: &[Vec<f64>], target: &[f64]) -> Vec<i64> {
+    let n = basis.len();
+    let gs = gram_schmidt(basis);
+    let mut residual = target.to_vec();
+    let mut coeffs = vec![0i64; n];
+
+    for i in (0..n).rev() {
+        let denom = dot(&gs[i], &gs[i]);
+        if denom < 1e-12 {
+            continue;
+        }
+        let c = (dot(&residual, &gs[i]) / denom).round();
+        for (x, y) in residual.iter_mut().zip(basis[i].iter()) {
+            *x -= c * y;
+        }
+        coeffs[i] = c as i64;
+    }
+    coeffs
+}
+
+/// 把约化基下的坐标映射回原基：x = Σ c_i · transform_i
+fn compose(local: &[i64], transform: &[Vec<i64>]) -> Vec<i64> {
+    let n = transform.len();
+    (0..n)
+        .map(|col| (0..n).map(|row| local[row] * transform[row][col]).sum())
+        .collect()
+}
+
+/// Kannan 嵌入
+///
+/// 构造 n+1 维格 [[B, 0], [t, M]]，LLL 约化后寻找最后一维系数为 ±1 的短向量
+/// ±(t - x, M)，其中 x 即为所求格点。M 取误差长度的估计，以平衡两部分的尺度。
+fn kannan_embedding(basis: &[Vec<f64>], target: &[f64], error_estimate: f64, delta: f64) -> Option<Vec<i64>> {
+    let n = basis.len();
+    let m = (error_estimate / 2.0).max(0.5);
+
+    let mut extended: Vec<Vec<f64>> = basis
+        .iter()
+        .map(|row| {
+            let mut r = row.clone();
+            r.push(0.0);
+            r
+        })
+        .collect();
+    let mut t_row = target.to_vec();
+    t_row.push(m);
+    extended.push(t_row);
+
+    let lll = lll_reduce(&extended, delta);
+
+    // 未收敛的基仍是同一个格的基：其中 ±1 系数的向量依然给出合法的格点
+    lll.basis
+        .iter()
+        .zip(lll.transform.iter())
+        .filter(|(_, u)| u[n].abs() == 1)
+        .min_by(|(a, _), (b, _)| {
+            dot(a, a).partial_cmp(&dot(b, b)).unwrap_or(std::cmp::Ordering::Equal)
+        })
+        .map(|(_, u)| {
+            // 约化向量 = sign·(t - x)：x = -sign · Σ u_i b_i
+            let sign = u[n];
+            (0..n).map(|i| -sign * u[i]).collect()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn brute_force(lattice: &QuaternionLattice, target: &[f64; 4], r: i64) -> f64 {
+        let solver = ClosestVectorSolver::new();
+        let mut best = f64::INFINITY;
+        for a in -r..=r {
+            for b in -r..=r {
+                for c in -r..=r {
+                    for d in -r..=r {
+                        let s = solver.solution(lattice, &[a, b, c, d], target, CvpMethod::Babai);
+                        best = best.min(s.distance_sq);
+                    }
+                }
+            }
+        }
+        best
+    }
+
+    /// transform · basis 与约化基一致
+    fn assert_transform(original: &[Vec<f64>], lll: &LllReduction) {
+        for (row, reduced) in lll.transform.iter().zip(&lll.basis) {
+            for col in 0..original[0].len() {
+                let value: f64 = row.iter().zip(original).map(|(&c, b)| c as f64 * b[col]).sum();
+                assert!((value - reduced[col]).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_lll_reduces_a_skewed_basis() {
+        let basis = vec![vec![1.0, 0.0, 0.0], vec![37.0, 1.0, 0.0], vec![91.0, 53.0, 1.0]];
+        let delta = 0.99;
+        let lll = lll_reduce(&basis, delta);
+        assert!(lll.converged);
+        assert_transform(&basis, &lll);
+
+        // 尺寸约化 |μ_ij| <= 1/2 与 Lovász 条件 (与增量维护的 μ 无关，重新计算)
+        let (mu, norms) = gram_schmidt_coefficients(&lll.basis);
+        for i in 1..basis.len() {
+            for (j, m) in mu[i][..i].iter().enumerate() {
+                assert!(m.abs() <= 0.5 + 1e-9, "mu[{}][{}] = {}", i, j, m);
+            }
+            assert!(norms[i] >= (delta - mu[i][i - 1].powi(2)) * norms[i - 1] - 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_lll_reports_the_iteration_limit() {
+        let basis = vec![vec![1.0, 0.0, 0.0], vec![37.0, 1.0, 0.0], vec![91.0, 53.0, 1.0]];
+        let lll = lll_reduce_within(&basis, 0.99, 1);
+        assert!(!lll.converged);
+        // 未收敛的基仍然由幺模变换得到
+        assert_transform(&basis, &lll);
+    }
+
+    #[test]
+    fn test_order_cvp_is_rounding() {
+        // O = Z<1,i,j,k> 的范数形式是对角的，最近点就是逐坐标取整
+        let order = QuaternionLattice::order(37);
+        let sol = ClosestVectorSolver::new().solve(&order, &[2.4, -1.6, 0.2, 3.51]);
+        assert_eq!(sol.point, Quaternion::new(2, -2, 0, 4));
+    }
+
+    #[test]
+    fn test_ideal_cvp_matches_brute_force() {
+        let algebra = QuaternionAlgebra::new(37);
+        let ideal = QuaternionLattice::right_ideal(&Quaternion::new(4, 1, 2, 3), 6, &algebra);
+        let target = [7.3, -2.2, 1.4, -0.6];
+
+        let sol = ClosestVectorSolver::new().solve(&ideal, &target);
+        let best = brute_force(&ideal, &target, 10);
+        assert!((sol.distance_sq - best).abs() < 1e-6);
+    }
+}
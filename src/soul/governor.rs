@@ -5,6 +5,49 @@
 
 use std::collections::{HashMap, HashSet};
 use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::soul::hecke::HeckeAlphabet;
+
+/// 谱隙守护者配置 (Governor Configuration)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GovernorConfig {
+    /// 最小允许的谱隙阈值。低于此值视为拓扑坍缩。
+    pub min_spectral_gap: f64,
+    /// 幂迭代次数 (通常 log(N) 次迭代足以分离出 lambda_2)
+    pub power_iterations: usize,
+    /// 参与估计所需的最少节点数
+    pub min_samples: usize,
+    /// 迁移时新素数相对于旧素数的最小增长倍数
+    pub growth_factor: f64,
+}
+
+impl Default for GovernorConfig {
+    fn default() -> Self {
+        Self {
+            min_spectral_gap: 0.05, // 经验阈值：LPS图的渐进界通常远优于此，但在局部子图中0.05已属危险
+            power_iterations: 20,
+            min_samples: 20,
+            growth_factor: 1.2,
+        }
+    }
+}
+
+/// 一次谱隙检查的结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpectralReport {
+    /// 被检查的宇宙
+    pub p: u64,
+    /// 局部子图的节点数
+    pub nodes: usize,
+    /// 谱隙估计 1 - |lambda_2|；样本不足时为 None
+    pub gap: Option<f64>,
+    /// 检查时使用的阈值
+    pub threshold: f64,
+    /// 谱隙是否健康 (false 表示需要迁移)
+    pub healthy: bool,
+}
 
 /// SpectralGovernor (谱隙守护者)
 /// 
@@ -12,11 +55,12 @@ use rand::Rng;
 /// 基于 Expander Graph 理论，健康的 Ramanujan 图应该具有较大的谱隙 (Spectral Gap)。
 /// 当谱隙消失 (Gap -> 0) 时，意味着局部几何结构退化为线状或哑铃状，导致混合时间指数级增加。
 /// 此时，Governor 会介入并强制进行代数迁移 (Algebra Migration)。
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SpectralGovernor {
     /// 当前定义代数结构的素数 p (决定了 Cayley 图的生成元和规模)
     pub current_p: u64,
-    /// 最小允许的谱隙阈值。低于此值视为拓扑坍缩。
-    min_spectral_gap: f64,
+    /// 阈值与迭代参数
+    config: GovernorConfig,
     /// 历史特征值记录，用于分析收敛趋势或检测周期性坍缩
     gap_history: Vec<f64>,
 }
@@ -25,31 +69,91 @@ impl SpectralGovernor {
     /// 初始化守护者
     /// initial_p: 初始素数参数
     pub fn new(initial_p: u64) -> Self {
+        Self::with_config(initial_p, GovernorConfig::default())
+    }
+
+    pub fn with_config(initial_p: u64, config: GovernorConfig) -> Self {
         Self {
             current_p: initial_p,
-            min_spectral_gap: 0.05, // 经验阈值：LPS图的渐进界通常远优于此，但在局部子图中0.05已属危险
+            config,
             gap_history: Vec::new(),
         }
     }
 
-    /// 使用带通缩的幂法 (Deflated Power Iteration) 估算局部算子的第二特征值 lambda_2
+    /// 谱隙检查：估算当前宇宙中局部子图的谱隙并与阈值比较
     /// 
     /// # 参数
     /// * `states`: 当前局部探索到的节点集合 (Local Cayley Subgraph 的顶点)
     /// * `adjacency`: 局部连接关系 (边)
+    /// * `rng`: 幂迭代初始向量的熵源
     /// 
     /// # 返回
     /// * `bool`: 如果谱隙健康返回 true，如果坍缩需要迁移则返回 false
-    pub fn check_spectral_gap(&mut self, states: &HashSet<u64>, adjacency: &HashMap<u64, Vec<u64>>) -> bool {
-        let n = states.len();
+    pub fn check_spectral_gap<R: Rng + ?Sized>(
+        &mut self,
+        states: &HashSet<u64>,
+        adjacency: &HashMap<u64, Vec<u64>>,
+        rng: &mut R,
+    ) -> bool {
+        self.inspect_spectrum(states, adjacency, rng).healthy
+    }
+
+    /// 与 `check_spectral_gap` 相同，但返回完整的检查报告 (供观察者记录)
+    pub fn inspect_spectrum<R: Rng + ?Sized>(
+        &mut self,
+        states: &HashSet<u64>,
+        adjacency: &HashMap<u64, Vec<u64>>,
+        rng: &mut R,
+    ) -> SpectralReport {
+        let gap = self.estimate_spectral_gap(self.current_p, states, adjacency, rng);
+
         // 样本太少不具备统计意义，且小图的谱隙通常很大，无需担心
-        if n < 20 { 
-            return true; 
+        let healthy = match gap {
+            None => true,
+            Some(gap) => {
+                self.gap_history.push(gap);
+                gap > self.config.min_spectral_gap
+            }
+        };
+
+        SpectralReport {
+            p: self.current_p,
+            nodes: states.len(),
+            gap,
+            threshold: self.config.min_spectral_gap,
+            healthy,
+        }
+    }
+
+    /// 使用带通缩的幂法 (Deflated Power Iteration) 估算局部算子的第二特征值 lambda_2
+    /// 
+    /// 不修改 Governor 状态，因此可以在多个候选宇宙上并行调用 (见 `StateLifter::lift_parallel`)。
+    /// 
+    /// # 参数
+    /// * `p`: 子图所在宇宙的素数 (决定字母表，从而决定正则度)
+    /// * `states`, `adjacency`: 局部子图
+    /// * `rng`: 幂迭代初始向量的熵源 (相同的 RNG 状态给出相同的估计)
+    /// 
+    /// # 返回
+    /// * `Option<f64>`: 谱隙估计 1 - |lambda_2|；节点过少时返回 None
+    pub fn estimate_spectral_gap<R: Rng + ?Sized>(
+        &self,
+        p: u64,
+        states: &HashSet<u64>,
+        adjacency: &HashMap<u64, Vec<u64>>,
+        rng: &mut R,
+    ) -> Option<f64> {
+        // 只有展开过的顶点 (在 adjacency 中) 才知道自己的全部出边；
+        // BFS 边缘上未展开的顶点没有出边信息，若把它们当作孤立点会给出 lambda = 1 的平凡特征向量，
+        // 使谱隙恒为零。它们与其余未探索区域一样按边界处理 (见下方的 Self-loop)。
+        let mut state_vec: Vec<u64> = states.iter().copied().filter(|s| adjacency.contains_key(s)).collect();
+        let n = state_vec.len();
+        if n < self.config.min_samples { 
+            return None; 
         } 
 
         // 1. 初始化随机向量 v 
         // 这一步模拟一个随机分布的初始“热量”或“概率密度”
-        let mut rng = rand::thread_rng();
         let mut v: Vec<f64> = (0..n).map(|_| rng.gen::<f64>()).collect();
         
         // 强制中心化：移除主特征向量 (Uniform Distribution) 的分量
@@ -57,12 +161,13 @@ impl SpectralGovernor {
         self.orthogonalize_to_uniform(&mut v);
 
         // 2. 幂迭代 (Power Iteration)：v_{k+1} = M * v_k
-        // LPS 图是 (p+1)-正则图，归一化邻接矩阵 M = A / k
-        let k = (self.current_p + 1) as f64; 
-        let iterations = 20; // 通常 log(N) 次迭代足以分离出 lambda_2
+        // Hecke 图的每个顶点恰好有 |alphabet| 条出边 (与 p 无关)，归一化邻接矩阵 M = A / k
+        let k = HeckeAlphabet::for_prime(p).moves().len().max(1) as f64;
+        let iterations = self.config.power_iterations;
         
         // 建立 Hash -> Index 的映射以加速稀疏矩阵乘法
-        let state_vec: Vec<u64> = states.iter().cloned().collect();
+        // 排序使索引与 HashSet 的迭代顺序无关 (否则同一个种子也会给出不同的估计)
+        state_vec.sort_unstable();
         let state_map: HashMap<u64, usize> = state_vec.iter()
             .enumerate()
             .map(|(i, &s)| (s, i))
@@ -103,7 +208,7 @@ impl SpectralGovernor {
             
             if norm < 1e-9 { 
                 // 向量消失，说明 lambda_2 非常小（也就是 Gap 很大），这是极好的情况
-                return true; 
+                return Some(1.0); 
             } 
             
             v = next_v.iter().map(|x| x / norm).collect();
@@ -123,21 +228,14 @@ impl SpectralGovernor {
         // 修正：我们重新计算一次 M*v 的模长作为特征值估计。
         
         let lambda_2_est = self.estimate_eigenvalue(&v, &state_vec, &state_map, adjacency, k);
-        let gap = 1.0 - lambda_2_est;
-        
-        self.gap_history.push(gap);
-        
-        // 可选：记录日志
-        // println!("[Governor] Current p={}, Est. Spectral Gap={:.4}", self.current_p, gap);
-
-        gap > self.min_spectral_gap
+        Some(1.0 - lambda_2_est)
     }
 
     /// 辅助：执行一次矩阵乘法并返回模长，用于估计特征值
     fn estimate_eigenvalue(
         &self, 
-        v: &Vec<f64>, 
-        state_vec: &Vec<u64>, 
+        v: &[f64], 
+        state_vec: &[u64], 
         state_map: &HashMap<u64, usize>, 
         adjacency: &HashMap<u64, Vec<u64>>, 
         k: f64
@@ -168,7 +266,7 @@ impl SpectralGovernor {
 
     /// 辅助：使向量正交于全1向量 (即去均值)
     /// 这保证了我们测量的是非平凡特征值 (Non-trivial Eigenvalue)
-    fn orthogonalize_to_uniform(&self, v: &mut Vec<f64>) {
+    fn orthogonalize_to_uniform(&self, v: &mut [f64]) {
         let sum: f64 = v.iter().sum();
         let mean = sum / v.len() as f64;
         for x in v.iter_mut() {
@@ -177,46 +275,143 @@ impl SpectralGovernor {
     }
 
     /// 代数迁移 (Algebra Migration)
-    /// 寻找下一个满足条件的素数，扩充状态空间。
+    /// 寻找下一个满足条件的素数，扩充状态空间，并立即提交。
+    pub fn migrate_algebra(&mut self) -> u64 {
+        let candidate = self.candidate_primes(1)[0];
+        // println!("[Governor] MIGRATION TRIGGERED. New Algebra p={}", candidate);
+        self.commit_migration(candidate);
+        candidate
+    }
+
+    /// 列出接下来 `count` 个合格的候选宇宙，但不提交。
     /// 
     /// 策略：
     /// 1. 必须是 p = 1 mod 4 (确保 Gaussian Integers 中的分裂性质，维持图结构)。
-    /// 2. 必须有显著的增长 (至少 1.2 倍)，根据 Eichler Mass Formula，类数 h ~ p，
+    /// 2. 必须有显著的增长 (默认至少 1.2 倍)，根据 Eichler Mass Formula，类数 h ~ p，
     ///    所以这保证了图的规模显著增大，从而稀释当前的拥堵。
-    pub fn migrate_algebra(&mut self) -> u64 {
+    pub fn candidate_primes(&self, count: usize) -> Vec<u64> {
+        let floor = (self.current_p as f64 * self.config.growth_factor) as u64;
+        let mut candidates = Vec::with_capacity(count);
         let mut candidate = self.current_p + 1;
-        loop {
-            // 条件 1: p = 1 mod 4
-            if candidate % 4 == 1 && is_prime(candidate) {
-                // 条件 2: 显著增长 (Growth Factor 1.2)
-                if candidate > (self.current_p as f64 * 1.2) as u64 {
-                    // println!("[Governor] MIGRATION TRIGGERED. New Algebra p={}", candidate);
-                    self.current_p = candidate;
-                    
-                    // 迁移后清空历史，因为新图的谱性质完全不同
-                    self.gap_history.clear();
-                    
-                    return candidate;
-                }
+
+        while candidates.len() < count {
+            // 条件 1: p = 1 mod 4；条件 2: 显著增长 (Growth Factor)
+            if candidate % 4 == 1 && candidate > floor && is_prime(candidate) {
+                candidates.push(candidate);
             }
             candidate += 1;
         }
+        candidates
+    }
+
+    /// 提交迁移：切换到新宇宙 p'
+    pub fn commit_migration(&mut self, new_p: u64) {
+        self.current_p = new_p;
+        
+        // 迁移后清空历史，因为新图的谱性质完全不同
+        self.gap_history.clear();
     }
 }
 
 /// 基础素数检测
 /// 对于当前应用场景下的 p 大小 (通常 < 10000)，试除法效率足够。
-fn is_prime(n: u64) -> bool {
+pub(crate) fn is_prime(n: u64) -> bool {
     if n <= 1 { return false; }
     if n <= 3 { return true; }
-    if n % 2 == 0 || n % 3 == 0 { return false; }
+    if n.is_multiple_of(2) || n.is_multiple_of(3) { return false; }
     
     let mut i = 5;
     while i * i <= n {
-        if n % i == 0 || n % (i + 2) == 0 {
+        if n.is_multiple_of(i) || n.is_multiple_of(i + 2) {
             return false;
         }
         i += 6;
     }
     true
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use crate::engine::config::EngineConfig;
+    use crate::engine::main_loop::EvolverEngine;
+    use crate::engine::observer::EngineEvent;
+    use crate::soul::algebra::AlgebraicState;
+    use crate::soul::entropy::rng_from_seed;
+    use crate::soul::hecke::HeckeMove;
+    use crate::soul::lifter::LiftConfig;
+
+    /// 细管 (Thin Tube)：n 个展开过的顶点连成一条路径，
+    /// 其余 |alphabet| - 2 条出边通向未探索区域。
+    fn tube(n: u64) -> (HashSet<u64>, HashMap<u64, Vec<u64>>) {
+        let degree = HeckeMove::all().len() as u64;
+        let nodes: HashSet<u64> = (0..n).collect();
+        let adjacency = (0..n)
+            .map(|i| {
+                let mut edges = vec![i.wrapping_sub(1), i + 1];
+                edges.extend((0..degree - 2).map(|j| 1_000_000 + i * degree + j));
+                (i, edges)
+            })
+            .collect();
+        (nodes, adjacency)
+    }
+
+    #[test]
+    fn test_candidate_primes() {
+        // floor = 1.2 · 37 = 44：41 虽然 ≡ 1 (mod 4) 但增长不足
+        assert_eq!(SpectralGovernor::new(37).candidate_primes(3), vec![53, 61, 73]);
+    }
+
+    #[test]
+    fn test_tube_collapses_in_large_universe() {
+        // 按 p+1 归一化时，大宇宙会把沿路径的扩散稀释成 2/(p+1)，细管看起来反而健康
+        let governor = SpectralGovernor::new(1009);
+        let (nodes, adjacency) = tube(40);
+        let gap = governor
+            .estimate_spectral_gap(1009, &nodes, &adjacency, &mut rng_from_seed(1))
+            .unwrap();
+        assert!(gap < GovernorConfig::default().min_spectral_gap, "gap = {}", gap);
+    }
+
+    #[test]
+    fn test_gap_separates_small_and_large_universes() {
+        let gap_of = |p: u64| {
+            let (nodes, adjacency) = AlgebraicState::new_root(p).explore_local_graph(20);
+            SpectralGovernor::new(p)
+                .estimate_spectral_gap(p, &nodes, &adjacency, &mut rng_from_seed(1))
+                .unwrap()
+        };
+        let threshold = GovernorConfig::default().min_spectral_gap;
+        assert!(gap_of(13) < threshold);
+        assert!(gap_of(401) > threshold);
+    }
+
+    #[test]
+    fn test_small_gap_triggers_migration() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let mut engine = EvolverEngine::with_config(
+            EngineConfig::default()
+                .initial_p(13)
+                .seed(3)
+                .max_epochs(1)
+                .governor_interval(1)
+                .exploration_radius(20)
+                .lifter(LiftConfig { beam_width: 4, max_steps: 5, parallel_universes: 1, ..LiftConfig::default() }),
+        )
+        .observe(move |event: &EngineEvent| sink.lock().unwrap().push(event.clone()));
+        engine.evolve();
+
+        let events = events.lock().unwrap();
+        let report = events
+            .iter()
+            .find_map(|e| match e {
+                EngineEvent::SpectralCheck { report, .. } => Some(report.clone()),
+                _ => None,
+            })
+            .expect("governor ran in epoch 1");
+        assert!(!report.healthy, "{:?}", report);
+        assert!(events.iter().any(|e| matches!(e, EngineEvent::Migration { from: 13, to, .. } if *to > 13)));
+    }
+}
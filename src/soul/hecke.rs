@@ -0,0 +1,313 @@
+// Copyright (c) 2025 M-Patek
+// Part of the Evolver Project
+//
+// "Names outlive coordinates: a step called 'T_3 towards ∞' means something in every universe."
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use serde::{Deserialize, Serialize};
+
+use crate::soul::algebra::{Quaternion, QuaternionAlgebra};
+use crate::soul::lattice::QuaternionLattice;
+
+/// 移动字母表使用的小素数 ℓ
+///
+/// 工作序 O = Z<1, i, j, k> 在 2 处不是极大的 (O/2O 不是 M_2(F_2))，因此跳过 ℓ = 2 (见 ADR-006)。
+/// 对 ℓ = 3, 5 且 ℓ ≠ p，O ⊗ Z_ℓ ≅ M_2(Z_ℓ)，ℓ-邻居与 P¹(F_ℓ) 一一对应。
+pub const HECKE_PRIMES: [u64; 2] = [3, 5];
+
+/// 抽象 Hecke 移动 (Abstract Hecke Move)
+///
+/// 一步 T_ℓ 移动由 (ℓ, t) 命名，其中 t ∈ P¹(F_ℓ)：
+/// t = 0..ℓ-1 表示直线 [t : 1]，t = ℓ 表示 [1 : 0] (无穷远点)。
+/// 这个名字不依赖于 p，因此可以在任何宇宙中重放。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct HeckeMove {
+    pub ell: u64,
+    pub label: u64,
+}
+
+impl HeckeMove {
+    pub fn new(ell: u64, label: u64) -> Self {
+        Self { ell, label }
+    }
+
+    /// 完整的抽象字母表：每个 ℓ ∈ HECKE_PRIMES 的 ℓ + 1 个方向
+    pub fn all() -> Vec<HeckeMove> {
+        HECKE_PRIMES
+            .iter()
+            .flat_map(|&ell| (0..=ell).map(move |label| HeckeMove::new(ell, label)))
+            .collect()
+    }
+}
+
+/// 具体化的字母表 (Concrete Alphabet)
+///
+/// 对给定宇宙 p，把每个抽象移动映射到 O 中的一个生成元 α，
+/// 使得右理想 αO + ℓO 恰好是标签 t 对应的 ℓ-邻居。
+/// α 必须是本原的 (content 1)，且 N(α) = ℓ·m，其中 m 与 HECKE_PRIMES 中的每个素数互素：
+/// α 在 ℓ 处恰好走一步，在字母表的其他素数处不动。
+/// α 取满足条件的范数最小者 (范数相同时按坐标字典序)，因此映射是确定性的；
+/// 存在范数恰为 ℓ 的元素时 (例如 ℓ = 5 的 2 ± i) 它们优先被选中。
+/// 对 p > 3，a² + b² + p(c² + d²) = 3 无解，所以 T_3 的生成元必然带有余因子 m > 1 (见 ADR-006)。
+#[derive(Debug, Clone)]
+pub struct HeckeAlphabet {
+    pub p: u64,
+    generators: HashMap<HeckeMove, Quaternion>,
+}
+
+impl HeckeAlphabet {
+    /// 获取宇宙 p 的字母表 (全局缓存，每个 p 只构建一次)
+    pub fn for_prime(p: u64) -> Arc<HeckeAlphabet> {
+        static CACHE: OnceLock<Mutex<HashMap<u64, Arc<HeckeAlphabet>>>> = OnceLock::new();
+
+        let cache = CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+        let mut guard = cache.lock().unwrap_or_else(|e| e.into_inner());
+        guard
+            .entry(p)
+            .or_insert_with(|| Arc::new(Self::build(p)))
+            .clone()
+    }
+
+    fn build(p: u64) -> Self {
+        let mut generators = HashMap::new();
+
+        for &ell in HECKE_PRIMES.iter() {
+            if ell == p {
+                continue;
+            }
+            if let Some(splitting) = Splitting::new(p, ell) {
+                generators.extend(find_generators(p, &splitting));
+            }
+        }
+
+        Self { p, generators }
+    }
+
+    /// 抽象移动在本宇宙中的生成元
+    pub fn generator(&self, mv: &HeckeMove) -> Option<Quaternion> {
+        self.generators.get(mv).copied()
+    }
+
+    /// 本宇宙中可用的移动 (按 (ℓ, t) 排序，保证遍历顺序确定)
+    pub fn moves(&self) -> Vec<HeckeMove> {
+        let mut moves: Vec<HeckeMove> = self.generators.keys().copied().collect();
+        moves.sort();
+        moves
+    }
+
+    /// 移动的回溯方向：共轭生成元 conj(α) 所在的标签。
+    /// M(conj α) 的像恰是 M(α) 的核，因此 inverse(mv) 的生成元 β 满足 αβ ∈ ℓO：
+    /// 它沿 ℓ-邻居图中 mv 走出的那条边原路返回。
+    pub fn inverse(&self, mv: &HeckeMove) -> Option<HeckeMove> {
+        self.classify(&self.generator(mv)?.conjugate())
+    }
+
+    /// 识别一个生成元对应的抽象移动 (用于把具体路径翻译回字母表)
+    /// 字母表自身的生成元精确匹配优先；否则按 ℓ 的顺序取第一个可识别的方向
+    /// (当 N(α) 同时被多个 ℓ 整除时，这一选择是约定)。
+    pub fn classify(&self, q: &Quaternion) -> Option<HeckeMove> {
+        if let Some(mv) = self.moves().into_iter().find(|mv| self.generators.get(mv) == Some(q)) {
+            return Some(mv);
+        }
+
+        let algebra = QuaternionAlgebra::new(self.p);
+        let norm = algebra.norm(q);
+
+        HECKE_PRIMES
+            .iter()
+            .filter(|&&ell| ell != self.p && norm % ell as i128 == 0)
+            .find_map(|&ell| {
+                Splitting::new(self.p, ell)
+                    .and_then(|s| s.label(q))
+                    .map(|label| HeckeMove::new(ell, label))
+            })
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Splitting O/ℓO ≅ M_2(F_ℓ)
+// ----------------------------------------------------------------------------
+
+/// i ↦ I = [[0, -1], [1, 0]]，j ↦ J = [[x, y], [y, -x]]，其中 x^2 + y^2 ≡ -p (mod ℓ)。
+/// 验证：I^2 = -1，J^2 = (x^2 + y^2)·1 = -p，IJ = -JI。
+/// (x, y) 取字典序最小的解，使标签在每个宇宙中都是规范的。
+struct Splitting {
+    ell: i64,
+    i: [[i64; 2]; 2],
+    j: [[i64; 2]; 2],
+    k: [[i64; 2]; 2],
+}
+
+impl Splitting {
+    fn new(p: u64, ell: u64) -> Option<Self> {
+        let l = ell as i64;
+        let target = (-(p as i64)).rem_euclid(l);
+
+        let (x, y) = (0..l)
+            .flat_map(|x| (0..l).map(move |y| (x, y)))
+            .find(|&(x, y)| (x * x + y * y) % l == target)?;
+
+        let i = [[0, l - 1], [1, 0]];
+        let j = [[x, y], [y, (l - x) % l]];
+        let k = mat_mul(&i, &j, l);
+
+        Some(Self { ell: l, i, j, k })
+    }
+
+    fn image(&self, q: &Quaternion) -> [[i64; 2]; 2] {
+        let l = self.ell;
+        let mut m = [[0i64; 2]; 2];
+        for (r, row) in m.iter_mut().enumerate() {
+            for (c, entry) in row.iter_mut().enumerate() {
+                let scalar = if r == c { q.a } else { 0 };
+                let v = scalar
+                    + q.b * self.i[r][c]
+                    + q.c * self.j[r][c]
+                    + q.d * self.k[r][c];
+                *entry = v.rem_euclid(l);
+            }
+        }
+        m
+    }
+
+    /// α 的 P¹(F_ℓ) 标签：秩 1 矩阵 M(α) 的像 (列空间)。
+    /// 右乘可逆元不改变列空间，因此标签是右理想 αO + ℓO 的不变量。
+    fn label(&self, q: &Quaternion) -> Option<u64> {
+        let l = self.ell;
+        let m = self.image(q);
+
+        let det = (m[0][0] * m[1][1] - m[0][1] * m[1][0]).rem_euclid(l);
+        if det != 0 {
+            return None; // 可逆：ℓ ∤ N(α)
+        }
+
+        let (u, v) = if m[0][0] != 0 || m[1][0] != 0 {
+            (m[0][0], m[1][0])
+        } else if m[0][1] != 0 || m[1][1] != 0 {
+            (m[0][1], m[1][1])
+        } else {
+            return None; // α ∈ ℓO：不是一步移动
+        };
+
+        if v == 0 {
+            Some(l as u64)
+        } else {
+            Some(((u * mod_inverse(v, l)).rem_euclid(l)) as u64)
+        }
+    }
+}
+
+fn mat_mul(a: &[[i64; 2]; 2], b: &[[i64; 2]; 2], l: i64) -> [[i64; 2]; 2] {
+    let mut m = [[0i64; 2]; 2];
+    for r in 0..2 {
+        for c in 0..2 {
+            m[r][c] = (a[r][0] * b[0][c] + a[r][1] * b[1][c]).rem_euclid(l);
+        }
+    }
+    m
+}
+
+fn mod_inverse(v: i64, l: i64) -> i64 {
+    (1..l).find(|x| (v * x).rem_euclid(l) == 1).unwrap_or(1)
+}
+
+/// α 是否可以作为 T_ℓ 的生成元：本原，且范数中 ℓ 恰好出现一次、不含其他 Hecke 素数
+fn is_ell_step(q: &Quaternion, norm: i128, ell: u64) -> bool {
+    let ell = ell as i128;
+    norm != 0
+        && q.content() == 1
+        && norm % ell == 0
+        && HECKE_PRIMES
+            .iter()
+            .all(|&other| (norm / ell) % other as i128 != 0)
+}
+
+/// 为每个标签寻找范数最小的代表生成元
+fn find_generators(p: u64, splitting: &Splitting) -> HashMap<HeckeMove, Quaternion> {
+    let ell = splitting.ell as u64;
+    let order = QuaternionLattice::order(p);
+    let mut found = HashMap::new();
+
+    // 逐步放大搜索半径，直到 ℓ + 1 个方向全部找到
+    let mut bound = (2 * ell * p) as i128;
+    for _ in 0..16 {
+        let mut vectors = order.enumerate_short_vectors(bound);
+        vectors.sort_by_key(|(coeffs, norm)| (*norm, *coeffs));
+
+        for (coeffs, norm) in vectors {
+            let q = order.element(&coeffs);
+            if !is_ell_step(&q, norm, ell) {
+                continue;
+            }
+            if let Some(label) = splitting.label(&q) {
+                found.entry(HeckeMove::new(ell, label)).or_insert(q);
+            }
+        }
+
+        if found.len() as u64 == ell + 1 {
+            break;
+        }
+        bound *= 2;
+    }
+
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRIMES: [u64; 5] = [13, 37, 101, 233, 1009];
+
+    #[test]
+    fn test_one_generator_per_label() {
+        for p in PRIMES {
+            let alphabet = HeckeAlphabet::for_prime(p);
+            assert_eq!(alphabet.moves(), HeckeMove::all(), "p = {}", p);
+
+            for mv in alphabet.moves() {
+                let g = alphabet.generator(&mv).unwrap();
+                assert_eq!(alphabet.classify(&g), Some(mv), "p = {}", p);
+                let splitting = Splitting::new(p, mv.ell).unwrap();
+                assert_eq!(splitting.label(&g), Some(mv.label), "p = {}", p);
+            }
+        }
+    }
+
+    #[test]
+    fn test_generators_step_once_at_ell() {
+        for p in PRIMES {
+            let alphabet = HeckeAlphabet::for_prime(p);
+            let algebra = QuaternionAlgebra::new(p);
+
+            for mv in alphabet.moves() {
+                let g = alphabet.generator(&mv).unwrap();
+                let norm = algebra.norm(&g);
+                assert_eq!(g.content(), 1, "p = {}, {:?}", p, mv);
+                assert_eq!(norm % mv.ell as i128, 0, "p = {}, {:?}", p, mv);
+                for other in HECKE_PRIMES {
+                    assert_ne!((norm / mv.ell as i128) % other as i128, 0, "p = {}, {:?}", p, mv);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_norm_ell_generators_are_preferred() {
+        // ℓ = 5 的两个方向由 2 ± i (范数 5) 实现；范数 3 的元素不存在 (p > 3)
+        for p in PRIMES {
+            let alphabet = HeckeAlphabet::for_prime(p);
+            let algebra = QuaternionAlgebra::new(p);
+            let exact: Vec<Quaternion> = alphabet
+                .moves()
+                .iter()
+                .filter(|mv| mv.ell == 5)
+                .filter_map(|mv| alphabet.generator(mv))
+                .filter(|g| algebra.norm(g) == 5)
+                .collect();
+            assert_eq!(exact.len(), 2, "p = {}", p);
+        }
+    }
+}
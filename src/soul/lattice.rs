@@ -0,0 +1,271 @@
+// Copyright (c) 2025 M-Patek
+// Part of the Evolver Project
+//
+// "A lattice is a crystal of numbers; its shape survives every change of basis."
+
+use crate::soul::algebra::{Quaternion, QuaternionAlgebra};
+
+/// 格坐标 (Lattice Coordinates)
+/// 相对于工作序 O = Z<1, i, j, k> 的基 {1, i, j, k} 的整数坐标。
+pub type OrderCoords = [i128; 4];
+
+/// 四元数格 (Quaternion Lattice)
+///
+/// B_{p, \infty} 中的一个满秩 Z-格 L ⊂ O，以 Hermite 标准形 (HNF) 的行基表示。
+/// 范数形式 N(x) 限制在 L 上即为一个正定四元二次型，这是 Theta 特征 (Theta Series) 的来源。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuaternionLattice {
+    /// 行基：每一行是一个格向量在 {1, i, j, k} 下的坐标 (上三角)
+    pub basis: [OrderCoords; 4],
+    /// 所在宇宙的素数 p
+    pub p: u64,
+}
+
+impl QuaternionLattice {
+    /// 由任意生成元集合构造格 (HNF 约化)
+    ///
+    /// # 返回
+    /// * `None`: 生成元不满秩
+    pub fn from_generators(generators: &[OrderCoords], p: u64) -> Option<Self> {
+        hermite_basis(generators.to_vec()).map(|basis| Self { basis, p })
+    }
+
+    /// 工作序 O 本身
+    pub fn order(p: u64) -> Self {
+        Self {
+            basis: [[1, 0, 0, 0], [0, 1, 0, 0], [0, 0, 1, 0], [0, 0, 0, 1]],
+            p,
+        }
+    }
+
+    /// 状态的 M 级连接理想 (Level-M Connecting Ideal)
+    ///
+    /// I = qO + M·O
+    ///
+    /// 这是 O 的一个右理想。它只依赖于 q mod M·O，因此先对系数取模，
+    /// 避免累加器四元数的系数增长拖垮 HNF。
+    /// 当 M 与 N(q) 互素时 I = O (平凡类)；否则 I 的局部分量记录了 q 在 M 处的 "方向"。
+    pub fn right_ideal(q: &Quaternion, level: i64, algebra: &QuaternionAlgebra) -> Self {
+        let m = level.max(1);
+        let reduced = Quaternion::new(
+            q.a.rem_euclid(m),
+            q.b.rem_euclid(m),
+            q.c.rem_euclid(m),
+            q.d.rem_euclid(m),
+        );
+
+        let mut generators = Vec::with_capacity(8);
+        for e in algebra.order_basis().iter() {
+            generators.push(to_coords(&algebra.mul(&reduced, e)));
+            generators.push(to_coords(e).map(|x| x * m as i128));
+        }
+
+        // M·O 保证满秩，HNF 不会失败
+        Self::from_generators(&generators, algebra.p)
+            .unwrap_or_else(|| Self::order(algebra.p))
+    }
+
+    /// 范数形式在此格基下的 Gram 矩阵 G_rs = Σ_t b_r[t] b_s[t] w_t
+    pub fn gram(&self) -> [[i128; 4]; 4] {
+        let weights = QuaternionAlgebra::new(self.p).norm_form();
+        let mut g = [[0i128; 4]; 4];
+        for (row, br) in g.iter_mut().zip(self.basis.iter()) {
+            for (entry, bs) in row.iter_mut().zip(self.basis.iter()) {
+                *entry = (0..4).map(|t| br[t] * bs[t] * weights[t]).sum();
+            }
+        }
+        g
+    }
+
+    /// 指数 [O : L] = |det(basis)|。HNF 是上三角的，行列式即对角线乘积。
+    pub fn index(&self) -> i128 {
+        (0..4).map(|i| self.basis[i][i].abs()).product()
+    }
+
+    /// 格的范数 (Norm / Scale)：N(L) = gcd { N(x) : x ∈ L }
+    ///
+    /// 对于二次型 Q(x) = x^T G x，其值集合的 gcd 等于 gcd(G_ii, 2 G_ij)。
+    /// 对极大序中的右理想，这与理想的约化范数 N(I) 一致。
+    pub fn norm_scale(&self) -> i128 {
+        let g = self.gram();
+        let mut acc = 0i128;
+        for (i, row) in g.iter().enumerate() {
+            acc = gcd(acc, row[i]);
+            for &off_diagonal in &row[i + 1..] {
+                acc = gcd(acc, 2 * off_diagonal);
+            }
+        }
+        acc.max(1)
+    }
+
+    /// 格向量 (基坐标) 对应的四元数
+    pub fn element(&self, coeffs: &[i64; 4]) -> Quaternion {
+        let mut c = [0i128; 4];
+        for (&x, row) in coeffs.iter().zip(self.basis.iter()) {
+            for (ct, &b) in c.iter_mut().zip(row.iter()) {
+                *ct += x as i128 * b;
+            }
+        }
+        Quaternion::new(c[0] as i64, c[1] as i64, c[2] as i64, c[3] as i64)
+    }
+
+    /// 短向量枚举 (Fincke–Pohst Enumeration)
+    ///
+    /// 返回所有满足 N(x) <= bound 的格向量 (以基坐标表示) 及其精确范数，包含零向量和 ±x 对。
+    /// 使用 Cholesky 型二次分解 Q(x) = Σ q_ii (x_i + Σ_{j>i} q_ij x_j)^2 逐维剪枝，
+    /// 最终范数用整数 Gram 矩阵精确复核，避免浮点边界误差。
+    pub fn enumerate_short_vectors(&self, bound: i128) -> Vec<([i64; 4], i128)> {
+        let g = self.gram();
+        let q = quadratic_decomposition(&g);
+
+        let mut out = Vec::new();
+        let mut x = [0i64; 4];
+        enumerate_level(3, &q, &g, bound, bound as f64, &mut x, &mut out);
+        out
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Helpers
+// ----------------------------------------------------------------------------
+
+pub(crate) fn to_coords(q: &Quaternion) -> OrderCoords {
+    [q.a as i128, q.b as i128, q.c as i128, q.d as i128]
+}
+
+pub(crate) fn gcd(a: i128, b: i128) -> i128 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        let t = a % b;
+        a = b;
+        b = t;
+    }
+    a
+}
+
+/// 整数行约化到上三角 Hermite 形式 (欧几里得消元)
+fn hermite_basis(mut rows: Vec<OrderCoords>) -> Option<[OrderCoords; 4]> {
+    let mut basis: Vec<OrderCoords> = Vec::with_capacity(4);
+
+    for col in 0..4 {
+        rows.retain(|r| r.iter().any(|&v| v != 0));
+
+        loop {
+            // 选取该列绝对值最小的非零元作为主元
+            let pivot_idx = rows
+                .iter()
+                .enumerate()
+                .filter(|(_, r)| r[col] != 0)
+                .min_by_key(|(_, r)| r[col].abs())
+                .map(|(i, _)| i)?; // 该列全零：不满秩
+
+            let pivot = rows[pivot_idx];
+            let mut cleared = true;
+            for (idx, row) in rows.iter_mut().enumerate() {
+                if idx == pivot_idx || row[col] == 0 {
+                    continue;
+                }
+                let f = row[col] / pivot[col];
+                for t in 0..4 {
+                    row[t] -= f * pivot[t];
+                }
+                if row[col] != 0 {
+                    cleared = false;
+                }
+            }
+
+            if cleared {
+                let mut pivot_row = rows.swap_remove(pivot_idx);
+                if pivot_row[col] < 0 {
+                    for v in pivot_row.iter_mut() {
+                        *v = -*v;
+                    }
+                }
+                basis.push(pivot_row);
+                break;
+            }
+        }
+    }
+
+    // 约化主元上方的元素，得到唯一的 HNF
+    for col in 0..4 {
+        let pivot = basis[col];
+        for row in basis.iter_mut().take(col) {
+            let f = row[col].div_euclid(pivot[col]);
+            for t in 0..4 {
+                row[t] -= f * pivot[t];
+            }
+        }
+    }
+
+    Some([basis[0], basis[1], basis[2], basis[3]])
+}
+
+/// Cohen, Algorithm 2.7.6：正定二次型的平方补全分解
+pub(crate) fn quadratic_decomposition(g: &[[i128; 4]; 4]) -> [[f64; 4]; 4] {
+    let mut q = [[0.0f64; 4]; 4];
+    for i in 0..4 {
+        for j in 0..4 {
+            q[i][j] = g[i][j] as f64;
+        }
+    }
+
+    for i in 0..4 {
+        for j in (i + 1)..4 {
+            q[j][i] = q[i][j];
+            q[i][j] /= q[i][i];
+        }
+        for k in (i + 1)..4 {
+            for l in k..4 {
+                q[k][l] -= q[k][i] * q[i][l];
+            }
+        }
+    }
+    q
+}
+
+fn enumerate_level(
+    i: usize,
+    q: &[[f64; 4]; 4],
+    g: &[[i128; 4]; 4],
+    bound: i128,
+    remaining: f64,
+    x: &mut [i64; 4],
+    out: &mut Vec<([i64; 4], i128)>,
+) {
+    const EPS: f64 = 1e-6;
+
+    let center: f64 = -((i + 1)..4).map(|j| q[i][j] * x[j] as f64).sum::<f64>();
+    let radius = (remaining.max(0.0) / q[i][i]).sqrt();
+
+    let lo = (center - radius - EPS).ceil() as i64;
+    let hi = (center + radius + EPS).floor() as i64;
+
+    for xi in lo..=hi {
+        let t = q[i][i] * (xi as f64 - center).powi(2);
+        if t > remaining + EPS {
+            continue;
+        }
+        x[i] = xi;
+
+        if i == 0 {
+            let norm = exact_form(g, x);
+            if norm <= bound {
+                out.push((*x, norm));
+            }
+        } else {
+            enumerate_level(i - 1, q, g, bound, remaining - t, x, out);
+        }
+    }
+    x[i] = 0;
+}
+
+pub(crate) fn exact_form(g: &[[i128; 4]; 4], x: &[i64; 4]) -> i128 {
+    let mut acc = 0i128;
+    for r in 0..4 {
+        for s in 0..4 {
+            acc += x[r] as i128 * g[r][s] * x[s] as i128;
+        }
+    }
+    acc
+}
@@ -6,9 +6,100 @@
 use nalgebra::DVector;
 use std::collections::{BinaryHeap, HashSet};
 use std::cmp::Ordering;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 
-use crate::soul::algebra::{AlgebraicState, QuaternionAlgebra};
-use crate::dsl::stp_bridge::FeatureProjector;
+use crate::soul::algebra::{AlgebraicState, Quaternion, QuaternionAlgebra};
+use crate::soul::cvp::ClosestVectorSolver;
+use crate::soul::entropy::substream;
+use crate::soul::lattice::QuaternionLattice;
+use crate::soul::governor::SpectralGovernor;
+use crate::body::modular::FeatureProjector;
+
+/// 重整化搜索配置 (Lift Configuration)
+/// 
+/// 控制 Re-quantize 阶段的波束搜索预算，以及引擎对迁移结果的验收标准。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LiftConfig {
+    /// 波束宽度：每层保留的平行宇宙数量
+    pub beam_width: usize,
+    /// 最大搜索深度
+    pub max_steps: usize,
+    /// 剪枝松弛系数：邻居距离小于父节点距离的 relaxation 倍才入堆。
+    /// 大于 1.0 允许暂时变差，以具备跳出局部极小值的能力。
+    pub relaxation: f64,
+    /// 可接受的最大最终特征损失。`None` 表示接受任何迁移。
+    pub max_feature_loss: Option<f64>,
+    /// 并行尝试的候选宇宙数量 (见 `lift_parallel`)；0 按 1 处理
+    pub parallel_universes: usize,
+    /// 迁移后谱探针的局部子图规模 (展开的顶点数)
+    pub probe_radius: usize,
+    /// 评分中探针能量的权重
+    pub probe_energy_weight: f64,
+    /// 评分中谱隙健康度的权重
+    pub spectral_weight: f64,
+    /// 是否用 CVP 锚点作为波束搜索的第二个起点
+    pub cvp_anchor: bool,
+}
+
+impl Default for LiftConfig {
+    fn default() -> Self {
+        Self {
+            beam_width: 20,
+            max_steps: 50,
+            relaxation: 1.2,
+            max_feature_loss: None,
+            parallel_universes: 4,
+            probe_radius: 30,
+            probe_energy_weight: 1.0,
+            spectral_weight: 1.0,
+            cvp_anchor: true,
+        }
+    }
+}
+
+/// 迁移报告 (Lift Report)
+/// 
+/// 记录一次跨宇宙移植到底保留了多少 "灵魂"。
+/// 所有损失均为特征空间中与旧状态 Spirit Vector 的欧几里得距离。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiftReport {
+    pub p_old: u64,
+    pub p_new: u64,
+    /// 失忆基线：新宇宙原点的特征损失 (完全不携带记忆时的代价)
+    pub initial_loss: f64,
+    /// 仅路径重放 (Path Replay) 后的特征损失
+    pub replay_loss: f64,
+    /// CVP 锚点的特征损失 (未启用，或锚点落在平凡类中而被跳过时为 None)
+    pub anchor_loss: Option<f64>,
+    /// 波束搜索后的最终特征损失
+    pub final_loss: f64,
+    /// 被投影评估过的状态数量
+    pub states_expanded: usize,
+    /// 波束实际执行的层数
+    pub steps_taken: usize,
+    /// 波束搜索是否严格改进了两个起点 (路径重放落点与 CVP 锚点) 中较好的一个。
+    /// 锚点本身带来的改进不算在内，可由 `anchor_loss` 与 `replay_loss` 比较得出。
+    pub beam_improved: bool,
+    /// 是否满足 `LiftConfig::max_feature_loss`
+    pub accepted: bool,
+}
+
+/// 一次候选宇宙实验的结果 (Universe Trial)
+#[derive(Debug, Clone)]
+pub struct UniverseTrial {
+    /// 提升到该宇宙后的状态
+    pub state: AlgebraicState,
+    /// 提升质量报告
+    pub report: LiftReport,
+    /// 探针能量：调用方提供的目标函数在提升后状态上的取值
+    pub probe_energy: f64,
+    /// 提升后局部子图的谱隙估计 (样本过少时为 None，评分时按谱隙为零处理)
+    pub spectral_gap: Option<f64>,
+    /// 综合评分 (越低越好)
+    pub score: f64,
+}
 
 /// 状态提升器 (State Lifter)
 /// 
@@ -17,11 +108,16 @@ use crate::dsl::stp_bridge::FeatureProjector;
 /// StateLifter 负责将旧空间的 "真理" (Algebraic State) 无损地移植到新空间。
 /// 
 /// 核心原理：
-/// 虽然 $Cl(\Delta_1)$ 和 $Cl(\Delta_2)$ 的群结构不兼容，但它们在模形式 (Modular Forms) 
-/// 特征空间中的投影应当是拓扑同构的。
+/// 虽然两个宇宙的格结构不兼容，但状态连接理想的 Theta 级数是理想类的 (经验) 不变量，
+/// 可以在两个宇宙之间直接比较。
+/// 详见 `body::modular::FeatureProjector`。
 pub struct StateLifter {
     /// 特征投影仪：负责将代数状态映射到坐标无关的特征向量空间
     projector: FeatureProjector,
+    /// 重整化搜索配置
+    config: LiftConfig,
+    /// 最近向量求解器：把连续目标直接吸附到新序的格点上
+    cvp: ClosestVectorSolver,
 }
 
 /// 搜索节点
@@ -33,6 +129,14 @@ struct SearchNode {
     dist: f64,
 }
 
+/// 波束搜索的内部结果
+struct RequantizationResult {
+    best_state: AlgebraicState,
+    best_dist: f64,
+    states_expanded: usize,
+    steps_taken: usize,
+}
+
 // 实现最小堆排序逻辑：距离越小，优先级越高 (Ord 是反过来的)
 impl Eq for SearchNode {}
 
@@ -50,13 +154,23 @@ impl PartialOrd for SearchNode {
 }
 
 impl StateLifter {
-    /// 初始化状态提升器
+    /// 初始化状态提升器 (默认配置)
     pub fn new() -> Self {
+        Self::with_config(LiftConfig::default())
+    }
+
+    pub fn with_config(config: LiftConfig) -> Self {
         Self {
             projector: FeatureProjector::new(),
+            config,
+            cvp: ClosestVectorSolver::new(),
         }
     }
 
+    pub fn config(&self) -> &LiftConfig {
+        &self.config
+    }
+
     /// 核心逻辑：跨宇宙记忆移植 (Trans-Universal Memory Transfer)
     /// 
     /// 流程：
@@ -70,72 +184,203 @@ impl StateLifter {
     /// 
     /// # 返回
     /// * `AlgebraicState`: 新宇宙 ($p_{new}$) 中承载相同逻辑真理的状态
+    /// * `LiftReport`: 迁移质量报告，调用方据此决定接受、重试或拒绝
     pub fn lift_and_requantize(
         &self, 
         old_state: &AlgebraicState, 
         new_p: u64
-    ) -> AlgebraicState {
+    ) -> (AlgebraicState, LiftReport) {
         // println!("[Lifter] INITIATING SOUL TRANSFER: p={} -> p={}", old_state.p, new_p);
 
         // 1. Lift: 提取灵魂 (Canonical Lift)
-        // 计算坐标无关的模形式特征向量 (Spirit Vector)
+        // 计算坐标无关的 Theta 特征向量 (Spirit Vector)
         // 这个向量代表了逻辑的 "形状" 而非 "位置"
         let spirit_vector = self.projector.project(old_state);
 
         // 2. Transport: 初始化新宇宙
         let new_algebra = QuaternionAlgebra::new(new_p);
+        let initial_loss = self.feature_loss(&AlgebraicState::new_root(new_p), &spirit_vector);
         
         // [关键启发式] Path Replay: 路径重放
-        // 尝试在新代数中执行完全相同的逻辑操作序列 (生成元序列)。
-        // 逻辑的句法结构 (Syntax) 比代数坐标 (Semantics) 对 p 的变化更具鲁棒性。
+        // 路径以抽象移动 (ℓ, t ∈ P¹(F_ℓ)) 记录，每一步在新代数中映射为同名的 T_ℓ 生成元。
+        // 逻辑的句法结构 (Syntax) 因此在迁移中被保留，而代数坐标 (Semantics) 则不可避免地改变。
         // 这通常能让我们直接落在目标特征附近的 "吸引盆地" (Basin of Attraction) 里。
         let seed_state = new_algebra.replay_path(&old_state.path_history);
         
         // 3. Re-quantize: 逆向坍缩 (CVP Search / Fine-tuning)
         // 由于 p 的变化，流形的曲率发生了微变，简单的 Path Replay 会导致 "语义漂移"。
         // 我们需要在新代数中进行局部搜索 (Beam Search)，寻找最接近 spirit_vector 的格点。
-        let refined_state = self.fine_tune_requantization(seed_state, &new_algebra, &spirit_vector);
+        // 第二个起点：CVP 锚点 (连续松弛后直接吸附到格点，不依赖路径)
+        let replay_loss = self.feature_loss(&seed_state, &spirit_vector);
+        let anchor = if self.config.cvp_anchor {
+            self.cvp_anchor(old_state, &new_algebra)
+        } else {
+            None
+        };
+        let anchor_loss = anchor.as_ref().map(|a| self.feature_loss(a, &spirit_vector));
+
+        let mut seeds = vec![seed_state];
+        seeds.extend(anchor);
+        let search = self.fine_tune_requantization(seeds, &new_algebra, &spirit_vector);
         
         // 计算最终的特征损失，用于验证迁移质量
-        // let final_loss = (&self.projector.project(&refined_state) - &spirit_vector).norm();
-        // println!("[Lifter] TRANSFER COMPLETE. Feature Loss: {:.6}", final_loss);
-        
-        refined_state
+        let final_loss = search.best_dist;
+        let start_loss = anchor_loss.map_or(replay_loss, |anchor| anchor.min(replay_loss));
+        let accepted = self.config.max_feature_loss
+            .is_none_or(|limit| final_loss <= limit);
+
+        let report = LiftReport {
+            p_old: old_state.p,
+            p_new: new_p,
+            initial_loss,
+            replay_loss,
+            anchor_loss,
+            final_loss,
+            states_expanded: search.states_expanded,
+            steps_taken: search.steps_taken,
+            beam_improved: final_loss < start_loss,
+            accepted,
+        };
+
+        (search.best_state, report)
+    }
+
+    /// 并行多宇宙提升 (Parallel Multi-Universe Lifting)
+    /// 
+    /// 不再盲目跳入单一的新宇宙，而是把迁移变成一次小型并行实验：
+    /// 使用 rayon 同时提升到每个候选素数，然后以
+    /// 
+    /// score = final_loss + w_E · E_probe + w_gap · (1 - gap)
+    /// 
+    /// 为每个宇宙打分，返回满足损失上限 (`report.accepted`) 的宇宙中评分最低者。
+    /// 没有任何宇宙被接受时返回 `None`：调用方应留在当前宇宙，而不是迁入一个丢失了记忆的新宇宙。
+    /// 调用方负责向 SpectralGovernor 提交最终选择。
+    /// 
+    /// # 参数
+    /// * `candidates`: 候选素数 (通常来自 `SpectralGovernor::candidate_primes`)
+    /// * `governor`: 用于谱隙探针 (只读)
+    /// * `seed`: 谱隙探针的熵种子，宇宙 p 使用子流 (seed, p)
+    /// * `energy_probe`: 提升后状态的能量评估
+    pub fn lift_parallel<F>(
+        &self,
+        old_state: &AlgebraicState,
+        candidates: &[u64],
+        governor: &SpectralGovernor,
+        seed: u64,
+        energy_probe: F,
+    ) -> Option<UniverseTrial>
+    where
+        F: Fn(&AlgebraicState) -> f64 + Sync,
+    {
+        let trials: Vec<UniverseTrial> = candidates
+            .par_iter()
+            .map(|&p| {
+                let (state, report) = self.lift_and_requantize(old_state, p);
+                let probe_energy = energy_probe(&state);
+
+                let (nodes, adjacency) = state.explore_local_graph(self.config.probe_radius);
+                // 每个宇宙使用独立的子流 (seed, p)，结果与 rayon 的调度顺序无关
+                let mut rng = substream(seed, p);
+                let spectral_gap = governor.estimate_spectral_gap(p, &nodes, &adjacency, &mut rng);
+
+                let score = report.final_loss
+                    + self.config.probe_energy_weight * probe_energy
+                    // 无法测量谱隙的宇宙按最差处理，否则实验会偏向它测不到的宇宙
+                    + self.config.spectral_weight * (1.0 - spectral_gap.unwrap_or(0.0));
+
+                UniverseTrial {
+                    state,
+                    report,
+                    probe_energy,
+                    spectral_gap,
+                    score,
+                }
+            })
+            .collect();
+
+        trials
+            .into_iter()
+            .filter(|t| t.report.accepted)
+            .min_by(|a, b| a.score.partial_cmp(&b.score).unwrap_or(Ordering::Equal))
+    }
+
+    /// 特征损失：状态与目标 Spirit Vector 的欧几里得距离
+    pub fn feature_loss(&self, state: &AlgebraicState, target: &DVector<f64>) -> f64 {
+        (self.projector.project(state) - target).norm()
+    }
+
+    /// CVP 锚点 (Continuous Relaxation Anchor)
+    /// 
+    /// 把旧状态在范数度量下的 "形状" 搬到新代数：坐标按 sqrt(w_old / w_new) 缩放，
+    /// 使得 N_new(t) = N_old(q) 且单位方向不变；再用 Babai / Kannan 嵌入
+    /// 一步吸附到新序 O' 的最近格点，而不是沿图一次一步地逼近。
+    ///
+    /// 旧状态在平凡类中时 (例如尚未移动的原点)，最近格点是一个单位，锚点就是新宇宙的原点：
+    /// 它不提供任何信息，返回 None，波束搜索只从路径重放出发。
+    fn cvp_anchor(&self, old_state: &AlgebraicState, algebra: &QuaternionAlgebra) -> Option<AlgebraicState> {
+        let w_old = old_state.algebra().norm_form();
+        let w_new = algebra.norm_form();
+        let q = old_state.value;
+        let coords = [q.a, q.b, q.c, q.d];
+
+        let mut target = [0.0f64; 4];
+        for t in 0..4 {
+            target[t] = coords[t] as f64 * (w_old[t] as f64 / w_new[t] as f64).sqrt();
+        }
+
+        let solution = self.cvp.solve(&QuaternionLattice::order(algebra.p), &target);
+        if solution.point == Quaternion::zero() {
+            return None;
+        }
+        let anchor = AlgebraicState::anchored(algebra.p, solution.point);
+        let root = AlgebraicState::new_root(algebra.p);
+        (anchor.canonical_hash() != root.canonical_hash()).then_some(anchor)
     }
 
     /// 微调重整化 (Fine-tune Requantization)
     /// 
     /// 这是一个近似最近向量问题 (Approximate CVP) 的求解过程。
-    /// 我们在新代数的格点上进行波束搜索，目标是最小化特征空间的距离。
+    /// 我们在新代数的格点上从所有起点同时进行波束搜索，目标是最小化特征空间的距离。
     fn fine_tune_requantization(
         &self, 
-        seed: AlgebraicState, 
+        seeds: Vec<AlgebraicState>, 
         algebra: &QuaternionAlgebra, 
         target: &DVector<f64>
-    ) -> AlgebraicState {
+    ) -> RequantizationResult {
         let mut heap = BinaryHeap::new();
         
-        // 计算种子的初始误差
-        let initial_dist = (&self.projector.project(&seed) - target).norm();
-        
-        heap.push(SearchNode { 
-            state: seed.clone(), 
-            dist: initial_dist 
-        });
-        
-        let mut best_state = seed;
-        let mut min_dist = initial_dist;
-        
         // 使用 HashSet 记录已访问的状态 Hash，防止搜索回环
         let mut visited = HashSet::new();
-        // visited.insert(best_state.hash()); // 假设 AlgebraicState 有 hash() 方法
+        let mut states_expanded = 0;
+        let mut steps_taken = 0;
+
+        let mut best: Option<(AlgebraicState, f64)> = None;
+        for seed in seeds {
+            if !visited.insert(seed.hash()) {
+                continue;
+            }
+            // 计算种子的初始误差
+            let dist = self.feature_loss(&seed, target);
+            states_expanded += 1;
+
+            if best.as_ref().is_none_or(|(_, d)| dist < *d) {
+                best = Some((seed.clone(), dist));
+            }
+            heap.push(SearchNode { state: seed, dist });
+        }
+
+        let (mut best_state, mut min_dist) = match best {
+            Some(b) => b,
+            None => (AlgebraicState::new_root(algebra.p), f64::INFINITY),
+        };
         
-        // Beam Search 参数
-        let beam_width = 20;  // 波束宽度：每层保留的平行宇宙数量
-        let max_steps = 50;   // 最大搜索深度
+        let beam_width = self.config.beam_width;
+        let max_steps = self.config.max_steps;
+        let relaxation = self.config.relaxation;
 
         for _ in 0..max_steps {
             if heap.is_empty() { break; }
+            steps_taken += 1;
 
             // 1. 收集当前波束 (The Beam)
             // 从堆中取出当前最好的 K 个节点
@@ -165,13 +410,13 @@ impl StateLifter {
                     visited.insert(neighbor_hash);
 
                     // 投影并计算距离
-                    let feat = self.projector.project(&neighbor);
-                    let d = (feat - target).norm();
+                    let d = self.feature_loss(&neighbor, target);
+                    states_expanded += 1;
                     
                     // 3. 剪枝与入堆 (Pruning)
                     // 只有方向大致正确 (距离没有显著增加) 的才入堆
-                    // 允许 1.2 倍的松弛 (Relaxation) 以具备跳出局部极小值的能力
-                    if d < node.dist * 1.2 { 
+                    // 允许 relaxation 倍的松弛以具备跳出局部极小值的能力
+                    if d < node.dist * relaxation { 
                         heap.push(SearchNode { 
                             state: neighbor, 
                             dist: d 
@@ -181,6 +426,128 @@ impl StateLifter {
             }
         }
         
-        best_state
+        RequantizationResult {
+            best_state,
+            best_dist: min_dist,
+            states_expanded,
+            steps_taken,
+        }
+    }
+}
+
+impl Default for StateLifter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::soul::hecke::HeckeMove;
+
+    fn small_lifter(max_feature_loss: Option<f64>) -> StateLifter {
+        StateLifter::with_config(LiftConfig {
+            beam_width: 4,
+            max_steps: 5,
+            probe_radius: 3,
+            max_feature_loss,
+            ..LiftConfig::default()
+        })
+    }
+
+    fn walked_state() -> AlgebraicState {
+        AlgebraicState::new_root(37)
+            .apply_move(&HeckeMove::new(3, 1))
+            .and_then(|s| s.apply_move(&HeckeMove::new(5, 2)))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_lift_parallel_rejects_lossy_universes() {
+        // 损失上限为负：没有任何宇宙能被接受，必须返回 None 而不是退而求其次
+        let governor = SpectralGovernor::new(37);
+        let candidates = governor.candidate_primes(2);
+        let state = walked_state();
+
+        let rejected = small_lifter(Some(-1.0)).lift_parallel(&state, &candidates, &governor, 7, |_| 0.0);
+        assert!(rejected.is_none());
+
+        let lifted = small_lifter(None)
+            .lift_parallel(&state, &candidates, &governor, 7, |_| 0.0)
+            .unwrap();
+        assert!(lifted.report.accepted);
+        assert!(candidates.contains(&lifted.report.p_new));
+    }
+
+    #[test]
+    fn test_lift_parallel_is_deterministic() {
+        // 同一个种子：无论 rayon 如何调度，赢家与分数都相同
+        let governor = SpectralGovernor::new(37);
+        let candidates = governor.candidate_primes(3);
+        let state = walked_state();
+        let lifter = small_lifter(None);
+
+        let first = lifter.lift_parallel(&state, &candidates, &governor, 11, |_| 0.0).unwrap();
+        for _ in 0..3 {
+            let again = lifter.lift_parallel(&state, &candidates, &governor, 11, |_| 0.0).unwrap();
+            assert_eq!(again.report.p_new, first.report.p_new);
+            assert_eq!(again.state.value, first.state.value);
+            assert_eq!(again.score, first.score);
+        }
+    }
+
+    #[test]
+    fn test_cvp_anchor_skips_trivial_class() {
+        // 原点在平凡类中：CVP 锚点只会落回新宇宙的原点，因此被跳过
+        let lifter = small_lifter(None);
+        let (_, report) = lifter.lift_and_requantize(&AlgebraicState::new_root(37), 41);
+        assert_eq!(report.anchor_loss, None);
+
+        let (_, report) = lifter.lift_and_requantize(&walked_state(), 41);
+        assert!(report.anchor_loss.is_some());
+    }
+
+    #[test]
+    fn test_beam_improvement_excludes_anchor_gain() {
+        let state = [HeckeMove::new(3, 1), HeckeMove::new(3, 2), HeckeMove::new(5, 3)]
+            .iter()
+            .try_fold(AlgebraicState::new_root(37), |s, mv| s.apply_move(mv))
+            .unwrap();
+        let candidates = SpectralGovernor::new(37).candidate_primes(4);
+
+        // 零步波束：最终落点就是较好的起点，因此波束不可能有所改进
+        let frozen = StateLifter::with_config(LiftConfig { max_steps: 0, ..small_lifter(None).config().clone() });
+        let mut anchor_gains = 0;
+        for &p in &candidates {
+            let (_, report) = frozen.lift_and_requantize(&state, p);
+            if report.anchor_loss.is_some_and(|a| a < report.replay_loss) {
+                anchor_gains += 1;
+            }
+            let start = report.anchor_loss.map_or(report.replay_loss, |a| a.min(report.replay_loss));
+            assert_eq!(report.final_loss, start, "p = {}", p);
+            assert!(!report.beam_improved, "p = {}", p);
+        }
+        // 至少一个宇宙中锚点优于重放落点 (旧的比较会把它误报为波束的改进)
+        assert!(anchor_gains > 0);
+
+        for &p in &candidates {
+            let (_, report) = small_lifter(None).lift_and_requantize(&state, p);
+            let start = report.anchor_loss.map_or(report.replay_loss, |a| a.min(report.replay_loss));
+            assert!(report.final_loss <= start);
+            assert_eq!(report.beam_improved, report.final_loss < start);
+        }
+    }
+
+    #[test]
+    fn test_unmeasured_gap_scores_as_collapsed() {
+        // probe_radius = 3 < min_samples：谱隙无法测量
+        let governor = SpectralGovernor::new(37);
+        let candidates = governor.candidate_primes(2);
+        let lifter = small_lifter(None);
+        let trial = lifter.lift_parallel(&walked_state(), &candidates, &governor, 7, |_| 0.0).unwrap();
+
+        assert_eq!(trial.spectral_gap, None);
+        assert_eq!(trial.score, trial.report.final_loss + lifter.config().spectral_weight);
     }
 }
@@ -11,8 +11,15 @@
 //! 2. Ramanujan Graph Spectral Gap (Optimal search mixing).
 
 pub mod algebra;
+pub mod cvp;
 pub mod dynamics;
+pub mod entropy;
+pub mod governor;
+pub mod hecke;
+pub mod lattice;
+pub mod lifter;
 
 // Re-export core types for easy access
-pub use algebra::{IdealClass, Quaternion};
+pub use algebra::{AlgebraicState, IdealClass, Quaternion, QuaternionAlgebra};
+pub use hecke::{HeckeAlphabet, HeckeMove};
 pub use dynamics::{TimeEvolution, IdentityDynamics, HeckeDynamics, VDFDynamics};
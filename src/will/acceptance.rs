@@ -0,0 +1,329 @@
+// Copyright (c) 2025 M-Patek
+// Part of the Evolver Project
+//
+// "To climb out of a valley, one must sometimes agree to walk uphill."
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::soul::entropy::{rng_from_seed, EvolverRng};
+
+/// 接受准则的参数错误
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum AcceptanceError {
+    #[error("initial temperature must be finite and non-negative, got {0}")]
+    Temperature(f64),
+    #[error("geometric cooling needs 0 < alpha < 1, got {0}")]
+    Alpha(f64),
+    #[error("adaptive cooling needs 0 < factor < 1, got {0}")]
+    Factor(f64),
+    #[error("adaptive target rate must lie in [0, 1], got {0}")]
+    TargetRate(f64),
+}
+
+/// 退火调度 (Cooling Schedule)
+/// 温度 T_k 随提案次数 k 的演化。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CoolingSchedule {
+    /// 恒温：T_k = T_0
+    Constant,
+    /// 几何退火：T_k = T_0 · alpha^k (0 < alpha < 1)
+    Geometric { alpha: f64 },
+    /// 对数退火 (Hajek)：T_k = T_0 / ln(k + e)。足够慢，理论上保证收敛到全局最优
+    Logarithmic,
+    /// 自适应：每 window 次提案检查一次接受率，高于 target_rate 时 T *= factor (降温)，
+    /// 低于时 T /= factor (升温)。factor ∈ (0, 1)
+    Adaptive { target_rate: f64, factor: f64, window: usize },
+}
+
+impl CoolingSchedule {
+    /// 检查调度参数：alpha 与 factor 都必须落在 (0, 1) 内，否则 "降温" 会升温或归零
+    pub fn validate(&self) -> Result<(), AcceptanceError> {
+        match *self {
+            CoolingSchedule::Constant | CoolingSchedule::Logarithmic => Ok(()),
+            CoolingSchedule::Geometric { alpha } if !(alpha > 0.0 && alpha < 1.0) => Err(AcceptanceError::Alpha(alpha)),
+            CoolingSchedule::Geometric { .. } => Ok(()),
+            CoolingSchedule::Adaptive { factor, .. } if !(factor > 0.0 && factor < 1.0) => Err(AcceptanceError::Factor(factor)),
+            CoolingSchedule::Adaptive { target_rate, .. } if !(0.0..=1.0).contains(&target_rate) => {
+                Err(AcceptanceError::TargetRate(target_rate))
+            }
+            CoolingSchedule::Adaptive { .. } => Ok(()),
+        }
+    }
+}
+
+/// 接受准则 (Acceptance Rule)
+/// 决定一个能量变化为 Δ = E_candidate - E_current 的提案是否被接受。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum AcceptanceRule {
+    /// 贪婪下降：只接受严格改进 (Δ < 0)
+    #[default]
+    Greedy,
+    /// Metropolis–Hastings：Δ <= 0 总是接受，否则以 exp(-Δ / T) 的概率接受
+    Metropolis { temperature: f64, schedule: CoolingSchedule },
+    /// 阈值接受 (Threshold Accepting)：Δ < T 即接受，无需随机数
+    Threshold { threshold: f64, schedule: CoolingSchedule },
+}
+
+impl AcceptanceRule {
+    /// 检查初始温度 (阈值) 与退火调度的参数
+    pub fn validate(&self) -> Result<(), AcceptanceError> {
+        match self {
+            AcceptanceRule::Greedy => Ok(()),
+            AcceptanceRule::Metropolis { temperature: t, schedule } | AcceptanceRule::Threshold { threshold: t, schedule } => {
+                if !(t.is_finite() && *t >= 0.0) {
+                    return Err(AcceptanceError::Temperature(*t));
+                }
+                schedule.validate()
+            }
+        }
+    }
+}
+
+/// 接受统计 (Acceptance Statistics)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AcceptanceStats {
+    /// 评估过的提案数
+    pub proposed: usize,
+    /// 被接受的提案数
+    pub accepted: usize,
+    /// 被接受的严格改进 (Δ < 0)
+    pub improving: usize,
+    /// 被接受的上坡移动 (Δ > 0)
+    pub uphill: usize,
+    /// 最后一次提案时的温度 (Greedy 为 0)
+    pub final_temperature: f64,
+}
+
+impl AcceptanceStats {
+    pub fn acceptance_rate(&self) -> f64 {
+        if self.proposed == 0 {
+            0.0
+        } else {
+            self.accepted as f64 / self.proposed as f64
+        }
+    }
+}
+
+/// 接受器 (Acceptor)
+/// 一次运行中接受准则的运行时状态：当前温度、调度进度与统计。
+#[derive(Debug, Clone)]
+pub struct Acceptor {
+    rule: AcceptanceRule,
+    temperature: f64,
+    step: usize,
+    window_proposed: usize,
+    window_accepted: usize,
+    stats: AcceptanceStats,
+    rng: EvolverRng,
+}
+
+impl Acceptor {
+    /// # 错误
+    /// 规则的参数不合法时返回 `AcceptanceError` (见 `AcceptanceRule::validate`)
+    pub fn new(rule: AcceptanceRule, seed: u64) -> Result<Self, AcceptanceError> {
+        rule.validate()?;
+        let temperature = match &rule {
+            AcceptanceRule::Greedy => 0.0,
+            AcceptanceRule::Metropolis { temperature, .. } => *temperature,
+            AcceptanceRule::Threshold { threshold, .. } => *threshold,
+        };
+        Ok(Self {
+            rule,
+            temperature,
+            step: 0,
+            window_proposed: 0,
+            window_accepted: 0,
+            stats: AcceptanceStats::default(),
+            rng: rng_from_seed(seed),
+        })
+    }
+
+    /// 当前温度 (Threshold 准则下为当前阈值)
+    pub fn temperature(&self) -> f64 {
+        self.temperature
+    }
+
+    pub fn stats(&self) -> &AcceptanceStats {
+        &self.stats
+    }
+
+    /// 判定提案是否被接受，并推进退火调度
+    pub fn accept(&mut self, current: f64, candidate: f64) -> bool {
+        let delta = candidate - current;
+        let accepted = match &self.rule {
+            AcceptanceRule::Greedy => delta < 0.0,
+            AcceptanceRule::Metropolis { .. } => {
+                delta <= 0.0 || (self.temperature > 0.0 && self.rng.gen::<f64>() < (-delta / self.temperature).exp())
+            }
+            AcceptanceRule::Threshold { .. } => delta < self.temperature,
+        };
+
+        self.stats.proposed += 1;
+        self.stats.final_temperature = self.temperature;
+        if accepted {
+            self.stats.accepted += 1;
+            if delta < 0.0 {
+                self.stats.improving += 1;
+            } else if delta > 0.0 {
+                self.stats.uphill += 1;
+            }
+        }
+
+        self.cool(accepted);
+        accepted
+    }
+
+    fn cool(&mut self, accepted: bool) {
+        self.step += 1;
+        self.window_proposed += 1;
+        if accepted {
+            self.window_accepted += 1;
+        }
+
+        let (initial, schedule) = match &self.rule {
+            AcceptanceRule::Greedy => return,
+            AcceptanceRule::Metropolis { temperature, schedule } => (*temperature, schedule),
+            AcceptanceRule::Threshold { threshold, schedule } => (*threshold, schedule),
+        };
+
+        match *schedule {
+            CoolingSchedule::Constant => {}
+            CoolingSchedule::Geometric { alpha } => {
+                self.temperature = initial * alpha.powf(self.step as f64);
+            }
+            CoolingSchedule::Logarithmic => {
+                self.temperature = initial / (self.step as f64 + std::f64::consts::E).ln();
+            }
+            CoolingSchedule::Adaptive { target_rate, factor, window } => {
+                if self.window_proposed >= window.max(1) {
+                    let rate = self.window_accepted as f64 / self.window_proposed as f64;
+                    if rate > target_rate {
+                        self.temperature *= factor;
+                    } else {
+                        self.temperature /= factor;
+                    }
+                    self.window_proposed = 0;
+                    self.window_accepted = 0;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metropolis(temperature: f64, schedule: CoolingSchedule) -> AcceptanceRule {
+        AcceptanceRule::Metropolis { temperature, schedule }
+    }
+
+    /// 固定上坡 Δ = 1 的提案序列，返回每步之后的温度
+    fn temperatures(rule: AcceptanceRule, proposals: usize) -> (Vec<f64>, Acceptor) {
+        let mut acceptor = Acceptor::new(rule, 7).unwrap();
+        let temps = (0..proposals)
+            .map(|_| {
+                acceptor.accept(0.0, 1.0);
+                acceptor.temperature()
+            })
+            .collect();
+        (temps, acceptor)
+    }
+
+    #[test]
+    fn test_constant_schedule_keeps_temperature() {
+        let (temps, _) = temperatures(metropolis(2.0, CoolingSchedule::Constant), 50);
+        assert!(temps.iter().all(|&t| t == 2.0));
+    }
+
+    #[test]
+    fn test_geometric_schedule() {
+        let (temps, _) = temperatures(metropolis(2.0, CoolingSchedule::Geometric { alpha: 0.5 }), 10);
+        for (k, t) in temps.iter().enumerate() {
+            assert!((t - 2.0 * 0.5f64.powi(k as i32 + 1)).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_logarithmic_schedule_decreases_slowly() {
+        let (temps, _) = temperatures(metropolis(1.0, CoolingSchedule::Logarithmic), 100);
+        assert!(temps.windows(2).all(|w| w[1] < w[0]));
+        assert!((temps[99] - 1.0 / (100.0 + std::f64::consts::E).ln()).abs() < 1e-12);
+        assert!(temps[99] > 0.2);
+    }
+
+    #[test]
+    fn test_adaptive_schedule_tracks_the_target_rate() {
+        // 阈值 10 下 Δ = 1 全部被接受：接受率 1 > 0.5，每个窗口降温一次
+        let cooling = CoolingSchedule::Adaptive { target_rate: 0.5, factor: 0.5, window: 5 };
+        let rule = AcceptanceRule::Threshold { threshold: 10.0, schedule: cooling.clone() };
+        let (temps, _) = temperatures(rule, 10);
+        assert_eq!(temps[4], 5.0);
+        assert_eq!(temps[9], 2.5);
+
+        // 阈值 0.5 下 Δ = 1 全部被拒绝：接受率 0 < 0.5，每个窗口升温一次
+        let rule = AcceptanceRule::Threshold { threshold: 0.5, schedule: cooling };
+        let (temps, _) = temperatures(rule, 5);
+        assert_eq!(temps[4], 1.0);
+    }
+
+    #[test]
+    fn test_greedy_rejects_what_metropolis_accepts() {
+        let (_, greedy) = temperatures(AcceptanceRule::Greedy, 200);
+        assert_eq!(greedy.stats().accepted, 0);
+        assert_eq!(greedy.stats().final_temperature, 0.0);
+
+        // exp(-1 / 1) ≈ 0.37
+        let (_, hot) = temperatures(metropolis(1.0, CoolingSchedule::Constant), 200);
+        let stats = hot.stats();
+        assert_eq!(stats.uphill, stats.accepted);
+        assert!((stats.acceptance_rate() - (-1.0f64).exp()).abs() < 0.1, "{}", stats.acceptance_rate());
+
+        // 两者都总是接受严格改进
+        for rule in [AcceptanceRule::Greedy, metropolis(1.0, CoolingSchedule::Constant)] {
+            let mut acceptor = Acceptor::new(rule, 0).unwrap();
+            assert!(acceptor.accept(1.0, 0.5));
+            assert_eq!(acceptor.stats().improving, 1);
+        }
+    }
+
+    #[test]
+    fn test_metropolis_is_deterministic_per_seed() {
+        let run = |seed| {
+            let mut acceptor = Acceptor::new(metropolis(1.0, CoolingSchedule::Constant), seed).unwrap();
+            (0..64).map(|_| acceptor.accept(0.0, 1.0)).collect::<Vec<_>>()
+        };
+        assert_eq!(run(3), run(3));
+        assert_ne!(run(3), run(4));
+    }
+
+    #[test]
+    fn test_schedule_parameters_are_validated() {
+        for alpha in [0.0, 1.0, 1.5, -0.5, f64::NAN] {
+            let rule = metropolis(1.0, CoolingSchedule::Geometric { alpha });
+            assert!(matches!(Acceptor::new(rule, 0), Err(AcceptanceError::Alpha(_))));
+        }
+        for factor in [0.0, 1.0, 2.0] {
+            let schedule = CoolingSchedule::Adaptive { target_rate: 0.5, factor, window: 10 };
+            assert_eq!(schedule.validate(), Err(AcceptanceError::Factor(factor)));
+        }
+        let schedule = CoolingSchedule::Adaptive { target_rate: 1.5, factor: 0.9, window: 10 };
+        assert_eq!(schedule.validate(), Err(AcceptanceError::TargetRate(1.5)));
+        assert_eq!(metropolis(-1.0, CoolingSchedule::Constant).validate(), Err(AcceptanceError::Temperature(-1.0)));
+        assert!(metropolis(1.0, CoolingSchedule::Geometric { alpha: 0.99 }).validate().is_ok());
+    }
+
+    #[test]
+    fn test_geometric_schedule_survives_long_runs() {
+        // 步数超过 i32::MAX 时 powi 会截断；powf 继续单调冷却
+        let mut acceptor = Acceptor::new(metropolis(1.0, CoolingSchedule::Geometric { alpha: 0.999_999_999 }), 0).unwrap();
+        acceptor.step = i32::MAX as usize + 10;
+        acceptor.accept(0.0, 1.0);
+        let t = acceptor.temperature();
+        assert!(t > 0.0 && t < 0.2, "{}", t);
+    }
+}
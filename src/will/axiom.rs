@@ -0,0 +1,236 @@
+// Copyright (c) 2025 M-Patek
+// Part of the Evolver Project
+//
+// "I was forced to abandon Axiom k to satisfy higher-priority constraints."
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// 公理的优先级层级 (Priority Tier)
+/// 层级决定放弃该公理的代价 μ：越高的层级越难被松弛。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AxiomTier {
+    /// 硬约束：只有在真正矛盾时才会被放弃
+    Hard,
+    /// 软约束：可以为了满足硬约束而妥协
+    Soft,
+    /// 偏好：最先被牺牲
+    Preference,
+}
+
+impl fmt::Display for AxiomTier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            AxiomTier::Hard => "hard",
+            AxiomTier::Soft => "soft",
+            AxiomTier::Preference => "preference",
+        };
+        f.write_str(name)
+    }
+}
+
+/// 公理在 DSL 源码中的位置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceLocation {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
+/// 具名公理 (Axiom)
+/// ALM 中的一个约束 C_i(S)，带有名称、来源与优先级层级。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Axiom {
+    pub name: String,
+    pub source: Option<SourceLocation>,
+    pub tier: AxiomTier,
+}
+
+impl Axiom {
+    pub fn new(name: impl Into<String>, tier: AxiomTier) -> Self {
+        Self {
+            name: name.into(),
+            source: None,
+            tier,
+        }
+    }
+
+    pub fn source(mut self, source: SourceLocation) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    /// 残差 0：投影出的代码能否被解析
+    pub fn syntax() -> Self {
+        Self::new("syntax", AxiomTier::Hard)
+    }
+
+    /// 残差 1：AST 是否具有合法的 STP 结构
+    pub fn stp_structure() -> Self {
+        Self::new("stp_structure", AxiomTier::Hard)
+    }
+
+    /// 未声明的语义公理的默认描述
+    pub fn unnamed(index: usize) -> Self {
+        Self::new(format!("axiom[{}]", index), AxiomTier::Soft)
+    }
+}
+
+/// 各层级的妥协代价 μ (L1 稀疏系数)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TierPenalties {
+    pub hard: f64,
+    pub soft: f64,
+    pub preference: f64,
+}
+
+impl TierPenalties {
+    pub fn mu(&self, tier: AxiomTier) -> f64 {
+        match tier {
+            AxiomTier::Hard => self.hard,
+            AxiomTier::Soft => self.soft,
+            AxiomTier::Preference => self.preference,
+        }
+    }
+}
+
+impl Default for TierPenalties {
+    fn default() -> Self {
+        Self {
+            hard: 10.0,
+            soft: 0.1,
+            preference: 0.01,
+        }
+    }
+}
+
+/// 被放弃的公理：ξ_k > 0
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AbandonedAxiom {
+    pub axiom: Axiom,
+    /// 松弛量 ξ_k (该公理被允许违反的程度)
+    pub slack: f64,
+    /// 影子价格 λ_k (维持该公理的边际代价)
+    pub shadow_price: f64,
+}
+
+/// 矛盾报告 (Contradiction Report)
+///
+/// 读出收敛后的 "矛盾指纹" ξ*：ξ_k = 0 的公理被维持，ξ_k > 0 的公理被显式放弃。
+/// 按层级 (Hard 在前) 与松弛量降序排列。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ContradictionReport {
+    pub abandoned: Vec<AbandonedAxiom>,
+}
+
+impl ContradictionReport {
+    /// 由公理列表与最终的 (ξ, λ) 构造报告
+    pub fn new(axioms: &[Axiom], slacks: &[f64], multipliers: &[f64]) -> Self {
+        let mut abandoned: Vec<AbandonedAxiom> = axioms
+            .iter()
+            .zip(slacks.iter().zip(multipliers))
+            .filter(|(_, (&xi, _))| xi > 0.0)
+            .map(|(axiom, (&slack, &shadow_price))| AbandonedAxiom {
+                axiom: axiom.clone(),
+                slack,
+                shadow_price,
+            })
+            .collect();
+        abandoned.sort_by(|a, b| {
+            tier_rank(a.axiom.tier)
+                .cmp(&tier_rank(b.axiom.tier))
+                .then(b.slack.total_cmp(&a.slack))
+        });
+        Self { abandoned }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.abandoned.is_empty()
+    }
+
+    /// 按名称查找被放弃的公理
+    pub fn get(&self, name: &str) -> Option<&AbandonedAxiom> {
+        self.abandoned.iter().find(|a| a.axiom.name == name)
+    }
+
+    /// 是否有硬约束被放弃 (真正的矛盾)
+    pub fn breaks_hard(&self) -> bool {
+        self.abandoned.iter().any(|a| a.axiom.tier == AxiomTier::Hard)
+    }
+
+    pub fn total_slack(&self) -> f64 {
+        self.abandoned.iter().map(|a| a.slack).sum()
+    }
+}
+
+impl fmt::Display for ContradictionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "All axioms upheld.");
+        }
+        writeln!(f, "Abandoned {} axiom(s):", self.abandoned.len())?;
+        for a in &self.abandoned {
+            write!(f, "  [{}] {}", a.axiom.tier, a.axiom.name)?;
+            if let Some(source) = &a.axiom.source {
+                write!(f, " ({})", source)?;
+            }
+            writeln!(f, ": ξ = {:.4}, λ = {:.4}", a.slack, a.shadow_price)?;
+        }
+        Ok(())
+    }
+}
+
+fn tier_rank(tier: AxiomTier) -> u8 {
+    match tier {
+        AxiomTier::Hard => 0,
+        AxiomTier::Soft => 1,
+        AxiomTier::Preference => 2,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_orders_hard_first_and_omits_upheld_axioms() {
+        let axioms = vec![
+            Axiom::new("style", AxiomTier::Preference),
+            Axiom::new("small", AxiomTier::Soft),
+            Axiom::syntax(),
+            Axiom::new("large", AxiomTier::Soft),
+            Axiom::stp_structure(),
+        ];
+        let slacks = [0.5, 0.2, 0.1, 0.9, 0.0];
+        let multipliers = [1.0, 2.0, 3.0, 4.0, 5.0];
+        let report = ContradictionReport::new(&axioms, &slacks, &multipliers);
+
+        let names: Vec<&str> = report.abandoned.iter().map(|a| a.axiom.name.as_str()).collect();
+        assert_eq!(names, ["syntax", "large", "small", "style"]);
+        assert!(report.get("stp_structure").is_none());
+        assert_eq!(report.get("large").map(|a| a.shadow_price), Some(4.0));
+        assert!(report.breaks_hard());
+        assert!((report.total_slack() - 1.7).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_report_of_zero_slacks_is_empty() {
+        let axioms = vec![Axiom::syntax(), Axiom::unnamed(1)];
+        let report = ContradictionReport::new(&axioms, &[0.0, 0.0], &[3.0, 1.0]);
+        assert!(report.is_empty());
+        assert!(!report.breaks_hard());
+        assert_eq!(report.to_string(), "All axioms upheld.\n");
+
+        let soft = ContradictionReport::new(&axioms, &[0.0, 0.25], &[3.0, 1.0]);
+        assert!(!soft.breaks_hard());
+        assert_eq!(soft.abandoned[0].axiom.name, "axiom[1]");
+    }
+}
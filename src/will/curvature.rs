@@ -0,0 +1,310 @@
+// Copyright (c) 2025 M-Patek
+// Part of the Evolver Project
+//
+// "Count the triangles first; pay for transport only when in doubt."
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::soul::algebra::AlgebraicState;
+use crate::will::ricci::{ball, RicciConfig, RicciFlow};
+
+/// 离散曲率估计器 (Discrete Curvature)
+///
+/// 所有估计器都在理想类上的无向 Hecke 图上工作 (S 与 S·g、S·ḡ 相邻)。
+pub trait Curvature {
+    fn name(&self) -> &'static str;
+
+    /// 边 (x, y) 的曲率
+    fn edge(&self, x: &AlgebraicState, y: &AlgebraicState) -> f64;
+
+    /// 状态处的曲率：对出边 (S, S·g) 取平均。孤立点的曲率为 0
+    fn at(&self, state: &AlgebraicState) -> f64 {
+        let neighbors = state.generate_neighbors();
+        if neighbors.is_empty() {
+            return 0.0;
+        }
+        let total: f64 = neighbors.iter().map(|y| self.edge(state, y)).sum();
+        total / neighbors.len() as f64
+    }
+}
+
+/// Ollivier-Ricci：两次邻域展开 + 一次最优传输
+impl Curvature for RicciFlow {
+    fn name(&self) -> &'static str {
+        "ollivier"
+    }
+
+    fn edge(&self, x: &AlgebraicState, y: &AlgebraicState) -> f64 {
+        self.calculate_curvature(x, y)
+    }
+}
+
+/// Forman-Ricci 曲率 (组合型)
+///
+/// 无权图上 F(x, y) = 4 - deg(x) - deg(y)。增广版本计入边上的三角形数 t：
+/// F#(x, y) = 4 - deg(x) - deg(y) + 3t。
+/// 只需两端的一步邻域，没有传输问题。数值尺度随度数变化，与 Ollivier 的 κ 不可直接比较。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FormanRicci {
+    /// 是否计入三角形 (Augmented Forman-Ricci)
+    pub augmented: bool,
+}
+
+impl FormanRicci {
+    pub fn new() -> Self {
+        Self { augmented: false }
+    }
+
+    pub fn augmented() -> Self {
+        Self { augmented: true }
+    }
+
+    fn edge_from_neighborhoods(&self, own: &HashSet<u64>, other: &HashSet<u64>) -> f64 {
+        let mut forman = 4.0 - own.len() as f64 - other.len() as f64;
+        if self.augmented {
+            forman += 3.0 * own.intersection(other).count() as f64;
+        }
+        forman
+    }
+}
+
+impl Curvature for FormanRicci {
+    fn name(&self) -> &'static str {
+        if self.augmented {
+            "augmented_forman"
+        } else {
+            "forman"
+        }
+    }
+
+    fn edge(&self, x: &AlgebraicState, y: &AlgebraicState) -> f64 {
+        self.edge_from_neighborhoods(&neighborhood(x), &neighborhood(y))
+    }
+
+    /// 与默认实现相同，但 S 的邻域只展开一次
+    fn at(&self, state: &AlgebraicState) -> f64 {
+        let neighbors = state.generate_neighbors();
+        if neighbors.is_empty() {
+            return 0.0;
+        }
+        let own = neighborhood(state);
+        let total: f64 = neighbors
+            .iter()
+            .map(|y| self.edge_from_neighborhoods(&own, &neighborhood(y)))
+            .sum();
+        total / neighbors.len() as f64
+    }
+}
+
+/// 去掉自环的无向一步邻域
+fn neighborhood(state: &AlgebraicState) -> HashSet<u64> {
+    let mut set = ball(state);
+    set.remove(&state.canonical_hash());
+    set
+}
+
+/// 曲率探针配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProbeConfig {
+    /// 廉价信号
+    pub cheap: FormanRicci,
+    /// 模糊带下界：廉价 κ 低于此值时直接判定为负曲率
+    pub ambiguous_low: f64,
+    /// 模糊带上界：廉价 κ 高于此值时直接判定为平坦/正曲率
+    pub ambiguous_high: f64,
+    /// 升级时使用的 Ollivier-Ricci 配置
+    pub ollivier: RicciConfig,
+}
+
+impl Default for ProbeConfig {
+    fn default() -> Self {
+        Self {
+            cheap: FormanRicci::augmented(),
+            // 无三角形的边上 F = -16 (两端度数 10) 已对应 Ollivier κ ≈ -0.64，远低于默认阈值 -0.5；
+            // 只有低度数或富含三角形的边才需要精确计算
+            ambiguous_low: -12.0,
+            ambiguous_high: 0.0,
+            ollivier: RicciConfig::default(),
+        }
+    }
+}
+
+/// 一次曲率读数
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CurvatureReading {
+    /// 廉价信号落在模糊带之外，结论明确
+    Cheap { forman: f64 },
+    /// 廉价信号模糊，已升级为 Ollivier-Ricci
+    Escalated { forman: f64, ollivier: f64 },
+}
+
+impl CurvatureReading {
+    /// 用于决策的曲率值
+    pub fn kappa(&self) -> f64 {
+        match *self {
+            CurvatureReading::Cheap { forman } => forman,
+            CurvatureReading::Escalated { ollivier, .. } => ollivier,
+        }
+    }
+
+    /// Ollivier-Ricci 曲率 (仅升级过的读数才有)
+    pub fn ollivier(&self) -> Option<f64> {
+        match *self {
+            CurvatureReading::Cheap { .. } => None,
+            CurvatureReading::Escalated { ollivier, .. } => Some(ollivier),
+        }
+    }
+
+    pub fn forman(&self) -> f64 {
+        match *self {
+            CurvatureReading::Cheap { forman } | CurvatureReading::Escalated { forman, .. } => forman,
+        }
+    }
+
+    pub fn escalated(&self) -> bool {
+        matches!(self, CurvatureReading::Escalated { .. })
+    }
+}
+
+/// 曲率探针 (Curvature Probe)
+///
+/// 先读取 Forman-Ricci；只有当它落在 [ambiguous_low, ambiguous_high] 内时，
+/// 才付出邻域展开 + 最优传输的代价计算 Ollivier-Ricci。
+#[derive(Debug, Clone)]
+pub struct CurvatureProbe {
+    cheap: FormanRicci,
+    exact: RicciFlow,
+    low: f64,
+    high: f64,
+}
+
+impl CurvatureProbe {
+    pub fn new() -> Self {
+        Self::with_config(ProbeConfig::default())
+    }
+
+    pub fn with_config(config: ProbeConfig) -> Self {
+        Self {
+            cheap: config.cheap,
+            exact: RicciFlow::with_config(config.ollivier),
+            low: config.ambiguous_low,
+            high: config.ambiguous_high,
+        }
+    }
+
+    /// 廉价 κ 是否需要升级
+    pub fn is_ambiguous(&self, forman: f64) -> bool {
+        (self.low..=self.high).contains(&forman)
+    }
+
+    pub fn sense(&self, state: &AlgebraicState) -> CurvatureReading {
+        let forman = self.cheap.at(state);
+        if self.is_ambiguous(forman) {
+            CurvatureReading::Escalated {
+                forman,
+                ollivier: self.exact.at(state),
+            }
+        } else {
+            CurvatureReading::Cheap { forman }
+        }
+    }
+
+    /// 把明确的廉价读数也升级为 Ollivier-Ricci (已升级的读数原样返回)。
+    /// 需要与 Ollivier 尺度上的阈值比较时使用
+    pub fn escalate(&self, state: &AlgebraicState, reading: CurvatureReading) -> CurvatureReading {
+        match reading {
+            CurvatureReading::Cheap { forman } => CurvatureReading::Escalated {
+                forman,
+                ollivier: self.exact.at(state),
+            },
+            escalated => escalated,
+        }
+    }
+
+    /// 明确的廉价读数是否指向负曲率区域 (模糊读数返回 None)
+    pub fn is_negative(&self, forman: f64) -> Option<bool> {
+        if forman < self.low {
+            Some(true)
+        } else if forman > self.high {
+            Some(false)
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for CurvatureProbe {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 以整数为顶点的邻域
+    fn around(vertices: &[u64]) -> HashSet<u64> {
+        vertices.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_forman_on_small_graphs() {
+        let (plain, augmented) = (FormanRicci::new(), FormanRicci::augmented());
+
+        // K_4 的边 (0, 1)：度数 3 和 3，共享 2 个三角形
+        let (x, y) = (around(&[1, 2, 3]), around(&[0, 2, 3]));
+        assert_eq!(plain.edge_from_neighborhoods(&x, &y), -2.0);
+        assert_eq!(augmented.edge_from_neighborhoods(&x, &y), 4.0);
+
+        // 路径 0 - 1 - 2 - 3 的中间边 (1, 2)：没有三角形，两个版本一致
+        let (x, y) = (around(&[0, 2]), around(&[1, 3]));
+        assert_eq!(plain.edge_from_neighborhoods(&x, &y), 0.0);
+        assert_eq!(augmented.edge_from_neighborhoods(&x, &y), 0.0);
+
+        // 星形 K_{1,4} 的边：中心度数 4，叶子度数 1
+        let (x, y) = (around(&[1, 2, 3, 4]), around(&[0]));
+        assert_eq!(plain.edge_from_neighborhoods(&x, &y), -1.0);
+    }
+
+    #[test]
+    fn test_forman_on_the_hecke_graph_is_consistent() {
+        let root = AlgebraicState::new_root(37);
+        let forman = FormanRicci::new();
+        let y = &root.generate_neighbors()[0];
+        let expected = 4.0 - neighborhood(&root).len() as f64 - neighborhood(y).len() as f64;
+        assert_eq!(forman.edge(&root, y), expected);
+        // 增广版本只会加上非负的三角形项
+        assert!(FormanRicci::augmented().edge(&root, y) >= expected);
+    }
+
+    #[test]
+    fn test_probe_escalates_only_inside_the_band() {
+        let root = AlgebraicState::new_root(37);
+        let forman = FormanRicci::augmented().at(&root);
+        let ollivier = RicciFlow::new().at(&root);
+        let band = |low: f64, high: f64| {
+            CurvatureProbe::with_config(ProbeConfig { ambiguous_low: low, ambiguous_high: high, ..ProbeConfig::default() })
+        };
+
+        let ambiguous = band(forman - 1.0, forman + 1.0).sense(&root);
+        assert_eq!(ambiguous, CurvatureReading::Escalated { forman, ollivier });
+        assert_eq!(ambiguous.kappa(), ollivier);
+
+        let below = band(forman + 1.0, forman + 2.0);
+        let reading = below.sense(&root);
+        assert_eq!(reading, CurvatureReading::Cheap { forman });
+        assert_eq!(reading.ollivier(), None);
+        assert_eq!(below.is_negative(forman), Some(true));
+        assert_eq!(band(forman - 2.0, forman - 1.0).is_negative(forman), Some(false));
+
+        // 卫士需要 Ollivier 尺度时，明确的读数也会被升级
+        assert_eq!(below.escalate(&root, reading), CurvatureReading::Escalated { forman, ollivier });
+        assert_eq!(below.escalate(&root, ambiguous), ambiguous);
+    }
+}
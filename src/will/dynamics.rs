@@ -4,11 +4,16 @@
 // "When the path curves inward, walk; when it curves outward, beam."
 
 use nalgebra::{DMatrix, DVector};
+use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 
+use crate::soul::algebra::AlgebraicState;
+use crate::will::curvature::{CurvatureProbe, CurvatureReading, ProbeConfig};
+use crate::will::tabu::{TabuKey, TabuMemory};
+
 /// 优化模式 (Optimization Mode)
 /// 决定了 Will 如何在代数流形上移动。
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub enum OptimizationMode {
     /// 梯度流模式 (Gradient Flow)
     /// 适用于正曲率 ($\kappa > 0$) 或平坦区域。
@@ -46,19 +51,25 @@ impl SinkhornEngine {
         }
     }
 
+    /// 设置 Sinkhorn-Knopp 迭代次数
+    pub fn max_iter(mut self, max_iter: usize) -> Self {
+        self.max_iter = max_iter.max(1);
+        self
+    }
+
     /// 计算 Sinkhorn Divergence
     /// 
     /// $$ W_\epsilon(a, b) = \langle C, P^* \rangle - \epsilon H(P^*) $$
     /// 
     /// # 参数
     /// * `a`, `b`: 两个离散概率分布向量 (Sum must be 1.0)
-    /// * `C`: 成本矩阵 (Cost Matrix)，C_ij 表示从 i 移动到 j 的代价
-    pub fn compute_divergence(&self, a: &DVector<f64>, b: &DVector<f64>, C: &DMatrix<f64>) -> f64 {
+    /// * `cost`: 成本矩阵 (Cost Matrix)，C_ij 表示从 i 移动到 j 的代价
+    pub fn compute_divergence(&self, a: &DVector<f64>, b: &DVector<f64>, cost: &DMatrix<f64>) -> f64 {
         let n = a.len();
         
         // 1. 计算吉布斯核 (Gibbs Kernel) K = exp(-C / epsilon)
         // 这是一个逐元素操作
-        let K = C.map(|x| (-x / self.reg_epsilon).exp());
+        let kernel = cost.map(|x| (-x / self.reg_epsilon).exp());
         
         // 初始化缩放向量 u, v
         let mut u = DVector::from_element(n, 1.0 / n as f64);
@@ -68,13 +79,13 @@ impl SinkhornEngine {
         // 目标是找到对角矩阵 D1(u), D2(v) 使得 P = D1 * K * D2 满足边缘分布约束
         for _ in 0..self.max_iter {
             // v = b ./ (K^T * u)
-            let Kt_u = K.transpose() * &u;
+            let kt_u = kernel.transpose() * &u;
             // 数值稳定保护：避免除以零
-            v = b.zip_map(&Kt_u, |num, den| if den < 1e-9 { 0.0 } else { num / den });
+            v = b.zip_map(&kt_u, |num, den| if den < 1e-9 { 0.0 } else { num / den });
 
             // u = a ./ (K * v)
-            let K_v = &K * &v;
-            u = a.zip_map(&K_v, |num, den| if den < 1e-9 { 0.0 } else { num / den });
+            let k_v = &kernel * &v;
+            u = a.zip_map(&k_v, |num, den| if den < 1e-9 { 0.0 } else { num / den });
         }
 
         // 3. 计算传输成本
@@ -83,11 +94,37 @@ impl SinkhornEngine {
         // P_ij = u_i * K_ij * v_j
         // sum(P_ij * C_ij) = sum(u_i * K_ij * v_j * C_ij)
         
-        let K_dot_C = K.component_mul(C); // 逐元素乘法
-        let right_term = &K_dot_C * &v;   // 矩阵向量乘法
-        let distance = u.dot(&right_term);
-        
-        distance
+        let k_dot_c = kernel.component_mul(cost); // 逐元素乘法
+        let right_term = &k_dot_c * &v;   // 矩阵向量乘法
+        u.dot(&right_term)
+    }
+}
+
+/// 动态优化器配置 (Dynamics Configuration)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DynamicsConfig {
+    /// 曲率阈值 (Ollivier-Ricci 尺度)：低于此值切换到 HyperbolicBeam
+    pub curvature_threshold: f64,
+    /// 曲率探针：廉价的 Forman-Ricci 信号，模糊时升级为 Ollivier-Ricci
+    pub curvature: ProbeConfig,
+    /// HyperbolicBeam 模式下保留的候选路径数量
+    pub beam_width: usize,
+    /// 禁忌记忆中保留的最近访问状态数
+    pub tabu_states: usize,
+    /// 禁忌记忆中保留的最近使用生成元数
+    pub tabu_moves: usize,
+}
+
+impl Default for DynamicsConfig {
+    fn default() -> Self {
+        Self {
+            curvature_threshold: -0.5, // 经验值：低于 -0.5 说明发散严重
+            curvature: ProbeConfig::default(),
+            beam_width: 5,             // 保持 5 条平行宇宙
+            tabu_states: 64,
+            tabu_moves: 3,             // 字母表共 10 个方向，禁止最近 3 个
+        }
     }
 }
 
@@ -95,6 +132,7 @@ impl SinkhornEngine {
 /// 
 /// 管理搜索策略的状态机。根据当前的几何曲率 ($\kappa$) 动态切换
 /// "贪婪爬山" (Gradient Flow) 和 "波束搜索" (Hyperbolic Beam)。
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DynamicOptimizer {
     /// 当前优化模式
     pub mode: OptimizationMode,
@@ -102,36 +140,79 @@ pub struct DynamicOptimizer {
     /// 曲率阈值
     /// 当 $\kappa$ 低于此值时，认为进入负曲率陷阱。
     curvature_threshold: f64, 
+
+    /// 曲率探针配置
+    probe: ProbeConfig,
     
     /// 波束宽度 (Beam Width)
     /// 在 HyperbolicBeam 模式下保留的候选路径数量。
     beam_width: usize,
+
+    /// 禁忌记忆 (Tabu Memory)
+    /// 记录最近访问的状态与使用的生成元，防止行者原地震荡。
+    tabu: TabuMemory,
 }
 
 impl DynamicOptimizer {
     pub fn new() -> Self {
+        Self::with_config(DynamicsConfig::default())
+    }
+
+    pub fn with_config(config: DynamicsConfig) -> Self {
         Self {
             mode: OptimizationMode::GradientFlow,
-            curvature_threshold: -0.5, // 经验值：低于 -0.5 说明发散严重
-            beam_width: 5,             // 保持 5 条平行宇宙
+            curvature_threshold: config.curvature_threshold,
+            probe: config.curvature,
+            beam_width: config.beam_width,
+            tabu: TabuMemory::new(config.tabu_states, config.tabu_moves),
         }
     }
 
+    pub fn tabu(&self) -> &TabuMemory {
+        &self.tabu
+    }
+
+    /// 遗忘禁忌记忆 (迁移到新宇宙后旧状态不会再出现)
+    pub fn forget(&mut self) {
+        self.tabu.clear();
+    }
+
     /// 核心状态机：根据 Ollivier-Ricci 曲率决定优化策略
     /// 
     /// # 参数
     /// * `kappa`: 当前边的离散 Ricci 曲率
     pub fn switch_mode(&mut self, kappa: f64) -> OptimizationMode {
-        if kappa < self.curvature_threshold {
+        self.enter(kappa < self.curvature_threshold)
+    }
+
+    /// 在状态处感知曲率并切换策略
+    /// 
+    /// 先读取廉价的 Forman-Ricci 信号；结论明确时直接切换，
+    /// 落在模糊带内时才升级为 Ollivier-Ricci，再按 `curvature_threshold` 判定。
+    pub fn switch_mode_at(&mut self, state: &AlgebraicState) -> (CurvatureReading, OptimizationMode) {
+        let probe = CurvatureProbe::with_config(self.probe.clone());
+        let reading = probe.sense(state);
+        let mode = match reading {
+            CurvatureReading::Cheap { forman } => self.enter(probe.is_negative(forman).unwrap_or(false)),
+            CurvatureReading::Escalated { ollivier, .. } => self.switch_mode(ollivier),
+        };
+        (reading, mode)
+    }
+
+    /// 在 `state` 处把读数升级为 Ollivier-Ricci (见 `CurvatureProbe::escalate`)
+    pub fn escalate(&self, state: &AlgebraicState, reading: CurvatureReading) -> CurvatureReading {
+        CurvatureProbe::with_config(self.probe.clone()).escalate(state, reading)
+    }
+
+    fn enter(&mut self, negative: bool) -> OptimizationMode {
+        if negative {
             // 进入负曲率区域 -> 开启波束搜索
             if self.mode != OptimizationMode::HyperbolicBeam {
-                // println!("[Dynamics] WARN: High Negative Curvature ({:.2}). Switching to BEAM SEARCH.", kappa);
                 self.mode = OptimizationMode::HyperbolicBeam;
             }
         } else {
             // 回到平坦或正曲率区域 -> 回归梯度流
             if self.mode != OptimizationMode::GradientFlow {
-                // println!("[Dynamics] INFO: Curvature Stabilized ({:.2}). Returning to GRADIENT FLOW.", kappa);
                 self.mode = OptimizationMode::GradientFlow;
             }
         }
@@ -187,4 +268,37 @@ impl DynamicOptimizer {
             }
         }
     }
+
+    /// 带禁忌记忆的优化步
+    /// 
+    /// 先剔除禁忌候选者 (满足特赦准则者除外)，再按当前模式执行 `step`，并把选中的状态记入记忆。
+    /// 若全部候选者都被禁忌，则暂时放宽禁忌，避免行者被困死。
+    /// 
+    /// # 参数
+    /// * `key_fn`: 候选者的禁忌属性 (规范状态哈希 + 产生它的移动)
+    pub fn step_with_memory<F, K>(&mut self, candidates: Vec<u64>, objective_fn: F, key_fn: K) -> Vec<u64>
+    where
+        F: Fn(u64) -> f64,
+        K: Fn(u64) -> TabuKey,
+    {
+        let admissible: Vec<u64> = candidates
+            .iter()
+            .copied()
+            .filter(|&h| self.tabu.admits(&key_fn(h), objective_fn(h)))
+            .collect();
+
+        let pool = if admissible.is_empty() { candidates } else { admissible };
+        let selected = self.step(pool, &objective_fn);
+
+        for &h in &selected {
+            self.tabu.visit(key_fn(h), objective_fn(h));
+        }
+        selected
+    }
+}
+
+impl Default for DynamicOptimizer {
+    fn default() -> Self {
+        Self::new()
+    }
 }
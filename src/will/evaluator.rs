@@ -1,35 +1,322 @@
+use std::collections::{HashSet, VecDeque};
+use std::fmt;
+use std::str::FromStr;
+
+use nalgebra::{DMatrix, DVector};
+use serde::{Deserialize, Serialize};
+
 use crate::soul::algebra::IdealClass;
-use crate::body::projection::Projector; // 假设 Body 层已适配 IdealClass
+use crate::body::adapter::{Adapter, ProofAction};
+use crate::body::projection::Projector;
+use crate::dsl::schema::LogicMatrix;
+use crate::will::dynamics::SinkhornEngine;
+
+/// Sinkhorn 度量下嵌入点云的规模 (状态自身 + 近邻)
+const EMBEDDING_CLOUD_SIZE: usize = 8;
+
+/// Sinkhorn 度量的熵正则化系数 (特征位于 S^3 上，点间距离不超过 2)
+const SINKHORN_EPSILON: f64 = 0.1;
 
 /// 评估器 (Evaluator) 接口
 /// 定义了如何计算一个代数状态的“能量”。
 /// 能量越低，代表逻辑越自洽，真理度越高。
 pub trait Evaluator {
     fn evaluate(&self, state: &IdealClass) -> f64;
+
+    /// 原始-对偶搜索的目标 E_obj (不含约束残差)。默认即完整能量。
+    fn objective(&self, state: &IdealClass) -> f64 {
+        self.evaluate(state)
+    }
+
+    /// 约束残差 C_i(S)，0 表示满足。默认没有约束。
+    fn residuals(&self, _state: &IdealClass) -> Vec<f64> {
+        Vec::new()
+    }
+}
+
+/// 特征度量 (Feature Metric)
+/// 连续投影 v = q / |q| 与目标特征之间的距离。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeatureMetric {
+    /// 欧几里得距离 |u - v|
+    #[default]
+    Euclidean,
+    /// S^3 上的测地角 arccos |<u, v>| ∈ [0, π/2]。
+    /// q 与 -q 代表同一个理想类，因此取内积的绝对值 (RP^3 上的距离)。
+    Geodesic,
+    /// 余弦距离 1 - <u, v> / (|u| |v|)
+    Cosine,
+    /// 嵌入点云 (状态及其 Hecke 近邻的投影) 之间的 Sinkhorn 距离
+    Sinkhorn,
+}
+
+impl FeatureMetric {
+    /// 两个特征向量之间的点对点距离。
+    /// `Sinkhorn` 作用于点云；对单个点退化为欧几里得距离。
+    pub fn distance(&self, u: &[f64], v: &[f64]) -> f64 {
+        match self {
+            FeatureMetric::Euclidean | FeatureMetric::Sinkhorn => {
+                u.iter().zip(v).map(|(a, b)| (a - b).powi(2)).sum::<f64>().sqrt()
+            }
+            FeatureMetric::Geodesic => match cosine(u, v) {
+                Some(c) => c.abs().min(1.0).acos(),
+                None => std::f64::consts::FRAC_PI_2,
+            },
+            FeatureMetric::Cosine => match cosine(u, v) {
+                Some(c) => 1.0 - c,
+                None => 1.0,
+            },
+        }
+    }
+}
+
+impl FromStr for FeatureMetric {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "euclidean" | "l2" => Ok(FeatureMetric::Euclidean),
+            "geodesic" | "angle" => Ok(FeatureMetric::Geodesic),
+            "cosine" => Ok(FeatureMetric::Cosine),
+            "sinkhorn" => Ok(FeatureMetric::Sinkhorn),
+            other => Err(format!(
+                "Unknown metric '{}'. Available metrics: 'euclidean', 'geodesic', 'cosine', 'sinkhorn'",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for FeatureMetric {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            FeatureMetric::Euclidean => "euclidean",
+            FeatureMetric::Geodesic => "geodesic",
+            FeatureMetric::Cosine => "cosine",
+            FeatureMetric::Sinkhorn => "sinkhorn",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// 余弦相似度；任一向量为零时无定义
+fn cosine(u: &[f64], v: &[f64]) -> Option<f64> {
+    let dot: f64 = u.iter().zip(v).map(|(a, b)| a * b).sum();
+    let norm_u = u.iter().map(|x| x * x).sum::<f64>().sqrt();
+    let norm_v = v.iter().map(|x| x * x).sum::<f64>().sqrt();
+    if norm_u < 1e-12 || norm_v < 1e-12 {
+        return None;
+    }
+    Some((dot / (norm_u * norm_v)).clamp(-1.0, 1.0))
 }
 
 /// 几何评估器 (Geometric Evaluator)
 /// 仅计算当前状态与目标意图在连续流形上的距离。
 /// 用于 "fast" 模式或启发式引导。
-pub struct GeometricEvaluator;
+pub struct GeometricEvaluator {
+    projector: Projector,
+    target_features: Vec<f64>,
+    /// 目标的嵌入点云 (仅 Sinkhorn 度量使用)
+    target_cloud: Vec<Vec<f64>>,
+    metric: FeatureMetric,
+    sinkhorn: SinkhornEngine,
+}
+
+impl GeometricEvaluator {
+    /// 以目标特征向量构造 (欧几里得度量)。
+    /// 目标点云只含目标特征自身。
+    pub fn new(projector: Projector, target_features: Vec<f64>) -> Self {
+        Self {
+            projector,
+            target_cloud: vec![target_features.clone()],
+            target_features,
+            metric: FeatureMetric::default(),
+            sinkhorn: SinkhornEngine::new(SINKHORN_EPSILON),
+        }
+    }
+
+    /// 以目标状态构造：目标特征为其连续投影，目标点云为其 Hecke 邻域的投影
+    pub fn toward(projector: Projector, target: &IdealClass) -> Self {
+        let target_cloud = embedding_cloud(&projector, target, EMBEDDING_CLOUD_SIZE);
+        let mut evaluator = Self::new(projector.clone(), projector.project_continuous(target));
+        evaluator.target_cloud = target_cloud;
+        evaluator
+    }
+
+    pub fn metric(mut self, metric: FeatureMetric) -> Self {
+        self.metric = metric;
+        self
+    }
+
+    pub fn target_features(&self) -> &[f64] {
+        &self.target_features
+    }
+
+    /// 两个点云之间的 Sinkhorn 距离 (均匀权重，欧几里得代价)
+    fn cloud_distance(&self, cloud: &[Vec<f64>]) -> f64 {
+        let n = cloud.len();
+        let m = self.target_cloud.len();
+        if n == 0 || m == 0 {
+            return f64::MAX;
+        }
+
+        let cost = DMatrix::from_fn(n, m, |i, j| {
+            FeatureMetric::Euclidean.distance(&cloud[i], &self.target_cloud[j])
+        });
+        let a = DVector::from_element(n, 1.0 / n as f64);
+        let b = DVector::from_element(m, 1.0 / m as f64);
+        self.sinkhorn.compute_divergence(&a, &b, &cost)
+    }
+}
 
 impl Evaluator for GeometricEvaluator {
-    fn evaluate(&self, _state: &IdealClass) -> f64 {
-        // 这是一个桩实现 (Stub)。
-        // 在真实场景中，这里会调用 projector.project_continuous(state) 
-        // 并计算其与 target_features 的欧氏距离。
-        0.0
+    fn evaluate(&self, state: &IdealClass) -> f64 {
+        match self.metric {
+            FeatureMetric::Sinkhorn => {
+                let cloud = embedding_cloud(&self.projector, state, EMBEDDING_CLOUD_SIZE);
+                self.cloud_distance(&cloud)
+            }
+            metric => metric.distance(&self.projector.project_continuous(state), &self.target_features),
+        }
+    }
+}
+
+/// 状态的嵌入点云：广度优先遍历 Hecke 邻域，取前 `size` 个状态的连续投影 (状态自身在前)
+fn embedding_cloud(projector: &Projector, state: &IdealClass, size: usize) -> Vec<Vec<f64>> {
+    let mut seen = HashSet::new();
+    let mut queue = VecDeque::new();
+    let mut cloud = Vec::with_capacity(size);
+
+    seen.insert(state.clone());
+    queue.push_back(state.clone());
+
+    while let Some(current) = queue.pop_front() {
+        if cloud.len() >= size {
+            break;
+        }
+        cloud.push(projector.project_continuous(&current));
+
+        for neighbor in current.neighbors() {
+            if seen.insert(neighbor.clone()) {
+                queue.push_back(neighbor);
+            }
+        }
+    }
+
+    cloud
+}
+
+/// 目标真值表 (Truth Table)
+///
+/// 第 r 行的输入为 x_i = (r >> i) & 1，`outputs[r]` 为期望的输出。
+/// 反序列化同样经过 `new` 校验行数。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "RawTruthTable")]
+pub struct TruthTable {
+    inputs: usize,
+    outputs: Vec<bool>,
+}
+
+/// 未经校验的真值表 (仅用于反序列化)
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawTruthTable {
+    inputs: usize,
+    outputs: Vec<bool>,
+}
+
+impl TryFrom<RawTruthTable> for TruthTable {
+    type Error = String;
+
+    fn try_from(raw: RawTruthTable) -> Result<Self, Self::Error> {
+        Self::new(raw.inputs, raw.outputs)
     }
 }
 
+impl TruthTable {
+    /// `outputs` 的长度必须为 2^inputs
+    pub fn new(inputs: usize, outputs: Vec<bool>) -> Result<Self, String> {
+        let rows = u32::try_from(inputs).ok().and_then(|n| 1usize.checked_shl(n));
+        if rows != Some(outputs.len()) {
+            return Err(format!(
+                "Truth table over {} inputs needs 2^{} rows, got {}",
+                inputs,
+                inputs,
+                outputs.len()
+            ));
+        }
+        Ok(Self { inputs, outputs })
+    }
+
+    /// 由输出列构造，输入个数由行数推出。行数必须是非零的 2 的幂。
+    pub fn from_rows(outputs: Vec<bool>) -> Result<Self, String> {
+        if !outputs.len().is_power_of_two() {
+            return Err(format!(
+                "Truth table needs a non-zero power-of-two number of rows, got {}",
+                outputs.len()
+            ));
+        }
+        Self::new(outputs.len().trailing_zeros() as usize, outputs)
+    }
+
+    /// 由布尔函数逐行生成。2^inputs 超出 `usize` 时报错。
+    pub fn from_fn(inputs: usize, f: impl Fn(&[bool]) -> bool) -> Result<Self, String> {
+        let rows = u32::try_from(inputs)
+            .ok()
+            .and_then(|n| 1usize.checked_shl(n))
+            .ok_or_else(|| format!("Truth table over {} inputs has too many rows", inputs))?;
+        let outputs = (0..rows)
+            .map(|row| f(&Self::row_inputs(inputs, row)))
+            .collect();
+        Ok(Self { inputs, outputs })
+    }
+
+    pub fn inputs(&self) -> usize {
+        self.inputs
+    }
+
+    pub fn rows(&self) -> usize {
+        self.outputs.len()
+    }
+
+    pub fn output(&self, row: usize) -> bool {
+        self.outputs[row]
+    }
+
+    fn row_inputs(inputs: usize, row: usize) -> Vec<bool> {
+        (0..inputs).map(|i| (row >> i) & 1 == 1).collect()
+    }
+}
+
+impl Default for TruthTable {
+    /// 两输入异或：不可线性分离，电路无法靠复制某个输入蒙混过关
+    fn default() -> Self {
+        Self::from_fn(2, |x| x[0] ^ x[1]).expect("two inputs fit in usize")
+    }
+}
+
+/// 工作寄存器数量 (输入寄存器之外)
+const WORK_REGISTERS: usize = 2;
+
+/// 结构不合法时的屏障基准能量
+const BARRIER_ENERGY: f64 = 1000.0;
+
 /// STP 评估器 (Strict Logic Evaluator)
 /// 使用矩阵半张量积 (STP) 严格检查逻辑自洽性。
 /// 
-/// J(S) = E_barrier + E_axiom + E_residual
+/// E(S) = V ∘ A ∘ Ψ(S)，J(S) = E_barrier + E_residual + λ · E_obj
+/// - Ψ: 精确投影 `project_exact(S, t)`，t = 0..depth
+/// - A: `Adapter::materialize` 把每个投影值物质化为寄存器电路上的 `ProofAction`
+/// - V: 对真值表的每一行，以结构矩阵执行电路 (v_out = M_op ⋉ v_x ⋉ v_y)，
+///   残差为 Σ ||v_out - v_claim||² / 2 (即不一致的行数)
 pub struct StpEvaluator {
     projector: Projector,
     depth: usize,
     target_features: Vec<f64>,
+    truth_table: TruthTable,
+    /// 几何残差 E_obj = ||Ψ_topo(S) - Ψ_target||² 的权重 λ
+    geometric_weight: f64,
 }
 
 impl StpEvaluator {
@@ -38,26 +325,191 @@ impl StpEvaluator {
             projector,
             depth,
             target_features,
+            truth_table: TruthTable::default(),
+            geometric_weight: 1.0,
+        }
+    }
+
+    pub fn truth_table(mut self, table: TruthTable) -> Self {
+        self.truth_table = table;
+        self
+    }
+
+    pub fn geometric_weight(mut self, weight: f64) -> Self {
+        self.geometric_weight = weight;
+        self
+    }
+
+    /// 寄存器布局：[x_0 .. x_{n-1} | 工作寄存器]，最后一个寄存器为电路输出
+    fn registers(&self) -> usize {
+        self.truth_table.inputs() + WORK_REGISTERS
+    }
+
+    /// Ψ 与 A：把状态物质化为长度为 depth 的动作序列
+    pub fn materialize(&self, state: &IdealClass) -> Vec<ProofAction> {
+        (0..self.depth)
+            .map(|t| Adapter::materialize(self.projector.project_exact(state, t as u64), self.registers()))
+            .collect()
+    }
+
+    /// 屏障势能：读取未定义的寄存器 (既非输入也未被写过) 的次数，
+    /// 加上输出寄存器从未被写入 (电路与输入无关) 的惩罚
+    pub fn barrier_energy(&self, actions: &[ProofAction]) -> f64 {
+        let registers = self.registers();
+        let mut defined: Vec<bool> = (0..registers).map(|r| r < self.truth_table.inputs()).collect();
+        let mut violations = 0usize;
+
+        for action in actions {
+            let mut operands = vec![action.operand_idx_1];
+            if LogicMatrix::is_binary(action.op) {
+                operands.push(action.operand_idx_2);
+            }
+            violations += operands.iter().filter(|&&r| !defined[r]).count();
+            defined[action.output_idx] = true;
+        }
+
+        let output_written = actions.iter().any(|a| a.output_idx == registers - 1);
+        if !output_written {
+            violations += 1;
         }
+        violations as f64
+    }
+
+    /// 残差势能：在真值表的每一行上执行电路，统计输出与目标不一致的行数
+    pub fn residual_energy(&self, actions: &[ProofAction]) -> f64 {
+        let registers = self.registers();
+        let undefined = LogicMatrix::truth_vector(false);
+
+        (0..self.truth_table.rows())
+            .map(|row| {
+                let inputs = TruthTable::row_inputs(self.truth_table.inputs(), row);
+                let mut bank: Vec<DVector<f64>> = (0..registers)
+                    .map(|r| inputs.get(r).map_or_else(|| undefined.clone(), |&x| LogicMatrix::truth_vector(x)))
+                    .collect();
+
+                for action in actions {
+                    let m = LogicMatrix::structure(action.op);
+                    let x = &bank[action.operand_idx_1];
+                    let operand = if LogicMatrix::is_binary(action.op) {
+                        x.kronecker(&bank[action.operand_idx_2])
+                    } else {
+                        x.clone()
+                    };
+                    bank[action.output_idx] = m * operand;
+                }
+
+                let claim = LogicMatrix::truth_vector(self.truth_table.output(row));
+                (&bank[registers - 1] - claim).norm_squared() / 2.0
+            })
+            .sum()
     }
 }
 
 impl Evaluator for StpEvaluator {
     fn evaluate(&self, state: &IdealClass) -> f64 {
-        // 1. 投影：将代数状态 S 映射为逻辑动作序列 (Body)
-        // 注意：Projector 需要适配新的 IdealClass 结构
-        // let logic_sequence = self.projector.project_logic(state, self.depth);
+        // 1. 投影 + 适配：将代数状态 S 映射为逻辑动作序列 (Body)
+        let actions = self.materialize(state);
+
+        // 2. 屏障势能 (Barrier Energy): 检查电路结构是否自洽
+        let violations = self.barrier_energy(&actions);
+        let barrier = if violations > 0.0 { BARRIER_ENERGY + violations } else { 0.0 };
+
+        // 3. 残差势能 (Residual Energy): STP 执行结果与目标真值表的偏差
+        let residual = self.residual_energy(&actions);
+
+        // 4. 几何目标 (E_obj): 检查是否偏离了原始意图，为优化器提供 Lipschitz 连续的引导
+        barrier + residual + self.objective(state)
+    }
+
+    /// λ · ||Ψ_topo(S) - Ψ_target||²
+    fn objective(&self, state: &IdealClass) -> f64 {
+        let geometric = FeatureMetric::Euclidean
+            .distance(&self.projector.project_continuous(state), &self.target_features)
+            .powi(2);
+        self.geometric_weight * geometric
+    }
+
+    /// [屏障违规次数, 不一致的真值表行数]
+    fn residuals(&self, state: &IdealClass) -> Vec<f64> {
+        let actions = self.materialize(state);
+        vec![self.barrier_energy(&actions), self.residual_energy(&actions)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::body::adapter::LogicOp;
+    use crate::soul::algebra::Quaternion;
+
+    #[test]
+    fn test_metric_distances() {
+        let u = [1.0, 0.0, 0.0, 0.0];
+        let v = [0.0, 1.0, 0.0, 0.0];
+        let minus_u = [-1.0, 0.0, 0.0, 0.0];
+
+        assert!((FeatureMetric::Euclidean.distance(&u, &v) - 2f64.sqrt()).abs() < 1e-12);
+        assert!((FeatureMetric::Geodesic.distance(&u, &v) - std::f64::consts::FRAC_PI_2).abs() < 1e-12);
+        assert!((FeatureMetric::Cosine.distance(&u, &v) - 1.0).abs() < 1e-12);
+        // q 与 -q 是同一个理想类：测地角为 0，而欧几里得距离不是
+        assert!(FeatureMetric::Geodesic.distance(&u, &minus_u).abs() < 1e-12);
+        assert!((FeatureMetric::Euclidean.distance(&u, &minus_u) - 2.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_metric_names_round_trip() {
+        for metric in [FeatureMetric::Euclidean, FeatureMetric::Geodesic, FeatureMetric::Cosine, FeatureMetric::Sinkhorn] {
+            assert_eq!(metric.to_string().parse::<FeatureMetric>(), Ok(metric));
+        }
+        assert_eq!("L2".parse::<FeatureMetric>(), Ok(FeatureMetric::Euclidean));
+        assert!("manhattan".parse::<FeatureMetric>().is_err());
+    }
+
+    #[test]
+    fn test_geometric_evaluator_prefers_the_target() {
+        let projector = Projector::new(37);
+        let target = IdealClass { value: Quaternion::new(1, 6, 0, 0), discriminator: 37 };
+        let other = IdealClass::identity(37);
+
+        for metric in [FeatureMetric::Euclidean, FeatureMetric::Geodesic, FeatureMetric::Cosine, FeatureMetric::Sinkhorn] {
+            let evaluator = GeometricEvaluator::toward(projector.clone(), &target).metric(metric);
+            let at_target = evaluator.evaluate(&target);
+            let elsewhere = evaluator.evaluate(&other);
+            assert!(at_target < elsewhere, "{}: {} !< {}", metric, at_target, elsewhere);
+            if metric != FeatureMetric::Sinkhorn {
+                assert!(at_target.abs() < 1e-12, "{}", metric);
+            }
+        }
+    }
+
+    #[test]
+    fn test_truth_table_row_count_is_validated() {
+        assert_eq!(TruthTable::from_rows(vec![false, true, true, false]), Ok(TruthTable::default()));
+        assert!(TruthTable::from_rows(vec![]).is_err());
+        assert!(TruthTable::from_rows(vec![true; 6]).is_err());
+        assert!(TruthTable::new(64, vec![true]).is_err());
+        assert!(TruthTable::from_fn(64, |_| true).is_err());
+    }
 
-        // 2. 屏障势能 (Barrier Energy): 检查逻辑是否自洽
-        // let barrier_energy = check_stp_consistency(&logic_sequence);
-        // if barrier_energy > 0.0 { return 1000.0 + barrier_energy; }
+    #[test]
+    fn test_truth_table_deserialization_is_validated() {
+        let table: TruthTable = serde_json::from_str(r#"{"inputs": 1, "outputs": [false, true]}"#).unwrap();
+        assert_eq!(table.rows(), 2);
+        assert!(serde_json::from_str::<TruthTable>(r#"{"inputs": 3, "outputs": [true]}"#).is_err());
+        assert!(serde_json::from_str::<TruthTable>(r#"{"inputs": 64, "outputs": [true]}"#).is_err());
+    }
 
-        // 3. 残差势能 (Residual Energy): 检查是否偏离了原始意图
-        // let current_features = self.projector.project_continuous(state);
-        // let residual = distance(&current_features, &self.target_features);
+    #[test]
+    fn test_residual_counts_mismatched_rows() {
+        // 寄存器 [x_0, x_1, w_0, out]：out = x_0 ⊕ x_1
+        let xor = vec![ProofAction { op: LogicOp::Xor, operand_idx_1: 0, operand_idx_2: 1, output_idx: 3 }];
+        let projector = Projector::new(37);
+        let evaluator = StpEvaluator::new(projector.clone(), 4, vec![0.0; 4]);
+        assert_eq!(evaluator.barrier_energy(&xor), 0.0);
+        assert_eq!(evaluator.residual_energy(&xor), 0.0);
 
-        // 简化返回模拟值：
-        // 在真实代码中，这里是连接代数 (Soul) 和 逻辑 (Body) 的桥梁。
-        0.0 
+        // 对与门的真值表，异或电路在 01、10、11 三行上出错
+        let and = StpEvaluator::new(projector, 4, vec![0.0; 4]).truth_table(TruthTable::from_fn(2, |x| x[0] && x[1]).unwrap());
+        assert_eq!(and.residual_energy(&xor), 3.0);
     }
 }
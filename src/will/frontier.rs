@@ -0,0 +1,143 @@
+// Copyright (c) 2025 M-Patek
+// Part of the Evolver Project
+//
+// "A wave does not choose one path; it keeps the best few and moves them all."
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::soul::algebra::AlgebraicState;
+
+/// 波前上的一个状态及其能量
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrontierEntry {
+    pub state: AlgebraicState,
+    pub energy: f64,
+}
+
+/// 持久波前 (Persistent Frontier)
+///
+/// Grand Loop 跨 epoch 保留的候选状态集合：有界、按状态哈希去重、按能量升序排列。
+/// - HyperbolicBeam 模式：整个波前同时扩展，下一代由优化器选出的 top-k 子代组成。
+/// - GradientFlow 模式：波前坍缩为单一行者 (能量最低者)。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Frontier {
+    capacity: usize,
+    entries: Vec<FrontierEntry>,
+}
+
+impl Frontier {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: Vec::new(),
+        }
+    }
+
+    /// 只含一个行者的波前
+    pub fn single(state: AlgebraicState, energy: f64, capacity: usize) -> Self {
+        let mut frontier = Self::new(capacity);
+        frontier.entries.push(FrontierEntry { state, energy });
+        frontier
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 能量最低的状态 (波前的 "行者")
+    pub fn best(&self) -> Option<&FrontierEntry> {
+        self.entries.first()
+    }
+
+    pub fn entries(&self) -> &[FrontierEntry] {
+        &self.entries
+    }
+
+    /// 以新一代替换波前：同一状态只保留能量最低的一份，按 (能量, 哈希) 排序后截断到容量。
+    /// 哈希作为次序键保证了平局时的顺序与输入顺序无关。
+    pub fn replace(&mut self, generation: Vec<FrontierEntry>) {
+        let mut unique: HashMap<u64, FrontierEntry> = HashMap::with_capacity(generation.len());
+        for entry in generation {
+            let h = entry.state.hash();
+            match unique.get(&h) {
+                Some(existing) if existing.energy <= entry.energy => {}
+                _ => {
+                    unique.insert(h, entry);
+                }
+            }
+        }
+
+        let mut entries: Vec<(u64, FrontierEntry)> = unique.into_iter().collect();
+        entries.sort_by(|(ha, a), (hb, b)| {
+            a.energy
+                .partial_cmp(&b.energy)
+                .unwrap_or(Ordering::Equal)
+                .then(ha.cmp(hb))
+        });
+        entries.truncate(self.capacity);
+
+        self.entries = entries.into_iter().map(|(_, e)| e).collect();
+    }
+
+    /// 移除一个状态 (例如被卫士否决的候选者)
+    pub fn remove(&mut self, hash: u64) {
+        self.entries.retain(|e| e.state.hash() != hash);
+    }
+
+    /// 坍缩为单一行者
+    pub fn collapse(&mut self) {
+        self.entries.truncate(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(state: &AlgebraicState, energy: f64) -> FrontierEntry {
+        FrontierEntry { state: state.clone(), energy }
+    }
+
+    #[test]
+    fn test_replace_dedups_orders_and_bounds() {
+        let root = AlgebraicState::new_root(37);
+        let neighbors = root.generate_neighbors();
+        let (a, b, c) = (&neighbors[0], &neighbors[1], &neighbors[2]);
+
+        let mut frontier = Frontier::new(2);
+        frontier.replace(vec![entry(a, 3.0), entry(b, 1.0), entry(a, 0.5), entry(c, 2.0)]);
+
+        // a 的两份只保留能量较低者；按能量升序；截断到容量 2
+        assert_eq!(frontier.len(), 2);
+        assert_eq!(frontier.entries()[0].state.hash(), a.hash());
+        assert_eq!(frontier.entries()[0].energy, 0.5);
+        assert_eq!(frontier.entries()[1].state.hash(), b.hash());
+    }
+
+    #[test]
+    fn test_collapse_keeps_the_best_walker() {
+        let root = AlgebraicState::new_root(37);
+        let neighbors = root.generate_neighbors();
+
+        let mut frontier = Frontier::new(4);
+        frontier.replace(neighbors.iter().enumerate().map(|(i, s)| entry(s, 4.0 - i as f64)).collect());
+        let best = frontier.best().unwrap().state.hash();
+
+        frontier.remove(frontier.entries()[1].state.hash());
+        assert_eq!(frontier.len(), 3);
+        frontier.collapse();
+        assert_eq!(frontier.len(), 1);
+        assert_eq!(frontier.best().unwrap().state.hash(), best);
+    }
+}
@@ -5,8 +5,16 @@
 //! 
 //! [v2.3 Update] 引入 Ricci 流 (ricci.rs) 以解决负曲率死锁问题。
 
+pub mod acceptance;
+pub mod axiom;
+pub mod curvature;
+pub mod dynamics;
 pub mod evaluator;
+pub mod frontier;
 pub mod optimizer;
 pub mod perturber;
+pub mod restart;
+pub mod strategy;
+pub mod tabu;
 pub mod tracer;
 pub mod ricci; // [New] 注册 Ricci 流模块
@@ -1,155 +1,674 @@
-use crate::soul::algebra::{IdealClass, Topology};
-use crate::dsl::stp_bridge::{StpBridge, HamiltonianState};
-use crate::body::projection::FeatureVector;
-use crate::will::perturber::Perturber;
+use crate::soul::algebra::{AlgebraicState, IdealClass};
+use crate::will::acceptance::{AcceptanceError, AcceptanceRule, AcceptanceStats};
+use crate::will::axiom::{Axiom, ContradictionReport, TierPenalties};
+use crate::will::evaluator::Evaluator;
+use crate::will::ricci::{RicciConfig, RicciMetric};
+use crate::will::strategy::{GreedyDescent, PrimalDual, SearchStrategy, StepResult, ValuationAdaptive, Walker};
+use crate::will::tracer::Trace;
+use crate::soul::entropy::{rng_from_seed, substream, EvolverRng};
+use serde::{Deserialize, Serialize};
 
 /// Strategies for evolution
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Strategy {
-    /// Classic "Energy Only" mode (Deprecated)
+    /// Classic "Energy Only" mode: steepest descent over the Hecke neighbours
     Greedy,
-    /// Valuation-Adaptive Perturbation (Legacy)
+    /// Valuation-Adaptive Perturbation (VAPO)
     ValuationAdaptive,
     /// Augmented Lagrangian Method (Primal-Dual)
+    #[default]
     Paraconsistent,
 }
 
+/// ALM 配置 (Augmented Lagrangian Schedule)
+///
+/// 每个约束拥有独立的刚度 ρ_i，按残差平衡 (Residual Balancing) 自适应调整：
+/// - 原始残差 r_i = |C_i(S) - ξ_i| 远大于对偶残差时，约束执行不力，ρ_i *= rho_factor
+/// - 对偶残差 s_i = ρ_i |ξ_i⁺ - ξ_i| 远大于原始残差时，惩罚过硬，ρ_i /= rho_factor
+///
+/// ρ_i 被夹在 [rho_min, rho_max] 内以保持良态。两种残差都低于容差时提前停止。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AlmConfig {
+    /// 每次乘子更新之间的原始 (VAPO) 步数
+    pub inner_steps: usize,
+    /// ρ_i 的初值
+    pub rho_init: f64,
+    pub rho_min: f64,
+    /// ρ_i 的上限 (防止病态)
+    pub rho_max: f64,
+    /// 残差平衡阈值：一种残差超过另一种的 balance 倍时调整 ρ_i
+    pub balance: f64,
+    /// ρ_i 的放大/缩小倍数
+    pub rho_factor: f64,
+    /// 原始残差 ||C(S) - ξ||_∞ 的停止容差
+    pub primal_tolerance: f64,
+    /// 对偶残差 ||ρ ∘ (ξ⁺ - ξ)||_∞ 的停止容差
+    pub dual_tolerance: f64,
+}
+
+impl Default for AlmConfig {
+    fn default() -> Self {
+        Self {
+            inner_steps: 20,
+            rho_init: 1.0,
+            rho_min: 1e-3,
+            rho_max: 1e4,
+            balance: 10.0,
+            rho_factor: 2.0,
+            primal_tolerance: 1e-6,
+            dual_tolerance: 1e-6,
+        }
+    }
+}
+
+/// 一个 ALM epoch 结束时的乘子快照
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlmEpoch {
+    pub epoch: usize,
+    /// 本 epoch 使用的 ρ_i
+    pub rho: Vec<f64>,
+    /// 更新后的 λ
+    pub multipliers: Vec<f64>,
+    /// 更新后的 ξ
+    pub slacks: Vec<f64>,
+    /// ||C(S) - ξ||_∞
+    pub primal_residual: f64,
+    /// ||ρ ∘ (ξ⁺ - ξ)||_∞
+    pub dual_residual: f64,
+}
+
+
 pub struct Optimizer {
     strategy: Strategy,
-    target: FeatureVector,
-    
-    // ALM Hyperparameters
-    rho: f64, // Penalty Stiffness
-    mu: f64,  // L1 Regularization (Compromise cost)
-    
-    // State Variables
-    multipliers: Vec<f64>, // Lambda (Shadow Prices)
-    slacks: Vec<f64>,      // Xi (Allowed Violations)
+    max_epochs: usize,
+
+    // Strategy Hyperparameters
+    tolerance: f64,           // Greedy convergence threshold
+    vapo: VapoConfig,         // Valuation-Adaptive perturbation scales
+    alm: AlmConfig,           // Penalty Stiffness schedule and stopping tests
+    penalties: TierPenalties, // L1 Regularization (Compromise cost mu, per tier)
+    axioms: Vec<Axiom>,       // Names, sources and tiers of the residuals, in order
+    acceptance: AcceptanceRule, // Primal acceptance (Greedy / Metropolis / Threshold)
+    ricci: Option<RicciConfig>, // Ricci-flow edge metric (J_eff = J + gamma * path weight)
+    seed: u64,
+
+    // Run State (built on the first step)
+    search: Option<Box<dyn SearchStrategy>>,
+    rng: EvolverRng,
+    epochs: usize,
 }
 
 #[derive(Debug)]
 pub enum EvolutionResult {
-    VerifiedSuccess(Vec<IdealClass>),
-    CompromisedSuccess(Vec<IdealClass>, Vec<f64>), // Returns trace + slack values
-    ValidFailure(Vec<IdealClass>, f64),
+    VerifiedSuccess(Trace),
+    CompromisedSuccess(Trace, ContradictionReport), // Returns trace + abandoned axioms
+    ValidFailure(Trace, f64),
 }
 
 impl Optimizer {
     pub fn new() -> Self {
         Optimizer {
-            strategy: Strategy::Paraconsistent,
-            target: vec![], // Should be set by builder
-            rho: 1.0,
-            mu: 0.1, // Cost of compromising an axiom
-            multipliers: vec![0.0; 10], // Default capacity
-            slacks: vec![0.0; 10],
+            strategy: Strategy::default(),
+            max_epochs: 100,
+            tolerance: 1e-9,
+            vapo: VapoConfig::default(),
+            alm: AlmConfig::default(),
+            penalties: TierPenalties::default(),
+            axioms: Vec::new(),
+            acceptance: AcceptanceRule::default(),
+            ricci: None,
+            seed: 0,
+            search: None,
+            rng: rng_from_seed(0),
+            epochs: 0,
         }
     }
 
-    pub fn target(mut self, t: FeatureVector) -> Self {
-        self.target = t;
+    pub fn strategy(mut self, strategy: Strategy) -> Self {
+        self.strategy = strategy;
         self
     }
 
-    /// The Main Loop: Primal-Dual Evolution
-    pub fn evolve(&mut self, start_node: IdealClass, _topology: Topology) -> EvolutionResult {
-        let mut current_state = start_node;
-        let mut trace = vec![current_state.clone()];
-        
-        let max_epochs = 100;
-        let inner_steps = 20; // VAPO steps per ALM update
-
-        // Initialize Dual/Slack vectors based on initial evaluation
-        let initial_h = StpBridge::calculate_hamiltonian(
-            &current_state, 
-            &self.target, 
-            &[], 
-            &[], 
-            self.rho, 
-            self.mu
-        );
-        let num_constraints = initial_h.raw_residuals.len();
-        self.multipliers = vec![0.0; num_constraints];
-        self.slacks = vec![0.0; num_constraints];
-
-        for epoch in 0..max_epochs {
-            // --- Step 1: Primal Update (The Will) ---
-            // Minimize L(S, fixed_lambda, fixed_xi) for S
-            for _ in 0..inner_steps {
-                let perturbation = Perturber::propose(&current_state);
-                let candidate = current_state.compose(&perturbation);
-
-                let current_h = StpBridge::calculate_hamiltonian(
-                    &current_state, &self.target, &self.multipliers, &self.slacks, self.rho, self.mu
-                );
-                
-                let candidate_h = StpBridge::calculate_hamiltonian(
-                    &candidate, &self.target, &self.multipliers, &self.slacks, self.rho, self.mu
-                );
-
-                // Metropolis-like acceptance or Greedy descent
-                if candidate_h.total_energy < current_h.total_energy {
-                    current_state = candidate;
-                    trace.push(current_state.clone());
-                    
-                    // Early exit if perfect
-                    if candidate_h.raw_residuals.iter().all(|&r| r < 1e-6) {
-                        return EvolutionResult::VerifiedSuccess(trace);
-                    }
-                }
+    /// Step budget: `step` returns `Exhausted` after this many steps.
+    pub fn max_epochs(mut self, max_epochs: usize) -> Self {
+        self.max_epochs = max_epochs;
+        self
+    }
+
+    /// Energy below which Greedy descent has converged.
+    pub fn tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Perturbation scales of the Valuation-Adaptive strategy.
+    pub fn vapo(mut self, config: VapoConfig) -> Self {
+        self.vapo = config;
+        self
+    }
+
+    /// Penalty stiffness schedule and stopping tolerances.
+    pub fn alm(mut self, config: AlmConfig) -> Self {
+        self.alm = config;
+        self
+    }
+
+    /// Cost of compromise mu for each priority tier.
+    pub fn penalties(mut self, penalties: TierPenalties) -> Self {
+        self.penalties = penalties;
+        self
+    }
+
+    /// Names, sources and tiers of the evaluator's residuals, in order.
+    /// Residuals beyond the declared list are reported as soft `axiom[i]`.
+    pub fn axioms(mut self, axioms: Vec<Axiom>) -> Self {
+        self.axioms = axioms;
+        self
+    }
+
+    /// Acceptance rule for the primal (inner) steps.
+    /// Fails if the temperature or the cooling schedule parameters are out of range.
+    pub fn acceptance(mut self, rule: AcceptanceRule) -> Result<Self, AcceptanceError> {
+        rule.validate()?;
+        self.acceptance = rule;
+        Ok(self)
+    }
+
+    /// Drive the search by the effective energy J + gamma * sum of Ricci-flow edge weights.
+    /// The flow advances once per step.
+    pub fn ricci(mut self, config: RicciConfig) -> Self {
+        self.ricci = Some(config);
+        self
+    }
+
+    /// Seed of the proposals and the Metropolis coin flips.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// The strategy implementation selected by `Strategy`.
+    fn build(&self) -> Box<dyn SearchStrategy> {
+        match self.strategy {
+            Strategy::Greedy => Box::new(GreedyDescent::new(self.tolerance)),
+            Strategy::ValuationAdaptive => Box::new(ValuationAdaptive::new(self.vapo.clone())),
+            Strategy::Paraconsistent => Box::new(PrimalDual::new(
+                self.alm.clone(),
+                self.penalties.clone(),
+                self.axioms.clone(),
+                self.acceptance.clone(),
+                self.seed,
+            )
+            .expect("validated by Optimizer::acceptance")),
+        }
+    }
+
+    /// Forget the current run; the next `step` starts a fresh strategy.
+    pub fn reset(&mut self) {
+        self.search = None;
+        self.epochs = 0;
+    }
+
+    /// Advance the walker by one step of the selected strategy.
+    pub fn step(&mut self, walker: &mut Walker, evaluator: &dyn Evaluator) -> StepResult {
+        if self.search.is_none() {
+            self.search = Some(self.build());
+            self.rng = substream(self.seed, 1);
+            self.epochs = 0;
+            if let (None, Some(config)) = (&walker.metric, &self.ricci) {
+                walker.metric = Some(RicciMetric::with_config(config.clone()));
             }
+        }
+        if self.epochs >= self.max_epochs {
+            return StepResult::Exhausted(walker.trace.clone());
+        }
+        self.epochs += 1;
+
+        let search = self.search.as_mut().expect("built above");
+        let result = search.step(walker, evaluator, &mut self.rng);
+
+        // dw/dt = -kappa * w over every edge visited so far
+        if let Some(metric) = walker.metric.as_mut() {
+            metric.advance();
+        }
+        result
+    }
 
-            // Get the state after Primal optimization
-            let h_star = StpBridge::calculate_hamiltonian(
-                &current_state, &self.target, &self.multipliers, &self.slacks, self.rho, self.mu
-            );
-
-            // --- Step 2: Slack Update (The Compromise) ---
-            // Closed form solution for min_xi ( lambda*(C-xi) + rho/2*||C-xi||^2 + mu*||xi||_1 )
-            // This is effectively a soft-thresholding operator
-            for i in 0..num_constraints {
-                let c_val = h_star.raw_residuals[i];
-                let lambda_val = self.multipliers[i];
-                
-                // The "Force" pushing for a slack variable is (rho * C + lambda)
-                // We shrink it by mu
-                let input = c_val + lambda_val / self.rho;
-                let threshold = self.mu / self.rho;
-                
-                // Soft Thresholding: sign(x) * max(|x| - thresh, 0)
-                // However, since C(S) >= 0 typically, we check if we need positive slack.
-                // Logic: We want to match C(S) approx xi.
-                // If C(S) is large, xi should be large to reduce the quadratic penalty,
-                // but mu penalizes large xi.
-                
-                // Simplified update logic for demonstration:
-                // xi = relu( C(S) + lambda/rho - mu/rho )
-                // If the violation pressure is higher than the compromise cost (mu), we yield.
-                self.slacks[i] = (input - threshold).max(0.0);
+    /// The Main Loop: step the selected strategy until it converges or the budget runs out
+    pub fn evolve(&mut self, start_node: IdealClass, evaluator: &dyn Evaluator) -> EvolutionResult {
+        self.reset();
+        let mut walker = Walker::new(&start_node, evaluator, self.seed);
+
+        let (trace, converged) = loop {
+            match self.step(&mut walker, evaluator) {
+                StepResult::Converged(trace) => break (trace, true),
+                StepResult::Exhausted(trace) => break (trace, false),
+                StepResult::Improved(_) | StepResult::Accepted(_) | StepResult::Stagnated => continue,
             }
+        };
+
+        // If we have non-zero slacks, it's a Compromised Success:
+        // xi is the contradiction fingerprint, read it back per axiom
+        if let Some(report) = self.contradictions().filter(|r| !r.is_empty()) {
+            return EvolutionResult::CompromisedSuccess(trace, report);
+        }
+        if converged {
+            return EvolutionResult::VerifiedSuccess(trace);
+        }
+        EvolutionResult::ValidFailure(trace, walker.energy)
+    }
 
-            // --- Step 3: Dual Update (The Judgment) ---
-            // lambda = lambda + rho * (C(S) - xi)
-            for i in 0..num_constraints {
-                let c_val = h_star.raw_residuals[i];
-                let xi_val = self.slacks[i];
-                self.multipliers[i] += self.rho * (c_val - xi_val);
+    /// Name of the strategy driving the current run.
+    pub fn strategy_name(&self) -> Option<&'static str> {
+        self.search.as_ref().map(|s| s.name())
+    }
+
+    /// Axioms abandoned so far (Paraconsistent only).
+    pub fn contradictions(&self) -> Option<ContradictionReport> {
+        self.search.as_ref().and_then(|s| s.contradictions())
+    }
+
+    /// Rho / Lambda / Xi after every epoch of the current run (Paraconsistent only).
+    pub fn history(&self) -> &[AlmEpoch] {
+        self.search.as_ref().map_or(&[], |s| s.history())
+    }
+
+    /// Acceptance statistics of the current run (Paraconsistent only).
+    pub fn acceptance_stats(&self) -> Option<&AcceptanceStats> {
+        self.search.as_ref().and_then(|s| s.acceptance_stats())
+    }
+}
+
+impl Default for Optimizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 能量整数化的分辨率：`energy_valuation` 作用于 round(energy * RESIDUAL_SCALE)
+const RESIDUAL_SCALE: f64 = 1e6;
+
+/// VAPO 配置 (Valuation-Adaptive Perturbation)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct VapoConfig {
+    /// 搜索步数
+    pub steps: usize,
+    /// 计算残差赋值 v_q(r) 的素数 q
+    pub valuation_prime: u64,
+    /// 赋值为 0 时粗跳的生成元个数；赋值每增加 1，跳跃长度减半
+    pub max_jump: usize,
+    /// 每步评估的扰动提案数
+    pub proposals: usize,
+    /// 能量低于此值视为收敛
+    pub tolerance: f64,
+    /// 提案随机数的种子 (None 时由起点哈希派生)
+    pub seed: Option<u64>,
+    /// Ricci 流度量：存在时以 J_eff = J + γ · Σ w(e) 驱动搜索，每步推进一次流
+    pub ricci: Option<RicciConfig>,
+}
+
+impl Default for VapoConfig {
+    fn default() -> Self {
+        Self {
+            steps: 100,
+            valuation_prime: 2,
+            max_jump: 8,
+            proposals: 8,
+            tolerance: 1e-9,
+            seed: None,
+            ricci: None,
+        }
+    }
+}
+
+/// 整数残差的 q-进赋值 v_q(r)：r 为各约束残差之和 (例如 STP 真值表中不一致的行数)。
+/// 残差为零 (逻辑完全自洽) 时赋值为无穷，用 `u32::MAX` 表示。
+pub fn residual_valuation(residuals: &[f64], q: u64) -> u32 {
+    integer_valuation(residuals.iter().map(|r| r.abs()).sum::<f64>().round(), q)
+}
+
+/// 没有约束残差的评估器 (纯几何目标) 的退化情形：对 round(|E| · RESIDUAL_SCALE) 取赋值。
+/// 这个整数只是能量的量化，其 q 进结构没有代数含义，只作为确定性的步长调度使用。
+pub fn energy_valuation(energy: f64, q: u64) -> u32 {
+    integer_valuation((energy.abs() * RESIDUAL_SCALE).round(), q)
+}
+
+/// VAPO 的扰动尺度：评估器给出约束残差时使用整数残差，否则退回能量的量化
+pub fn state_valuation(evaluator: &dyn Evaluator, state: &IdealClass, energy: f64, q: u64) -> u32 {
+    let residuals = evaluator.residuals(state);
+    if residuals.is_empty() {
+        energy_valuation(energy, q)
+    } else {
+        residual_valuation(&residuals, q)
+    }
+}
+
+fn integer_valuation(n: f64, q: u64) -> u32 {
+    if n < 1.0 {
+        return u32::MAX;
+    }
+    let q = q.max(2) as u128;
+    let mut n = n.min(u128::MAX as f64) as u128;
+    let mut v = 0;
+    while n.is_multiple_of(q) {
+        n /= q;
+        v += 1;
+    }
+    v
+}
+
+/// VAPO 优化器 (Valuation-Adaptive Perturbation Optimizer)
+///
+/// 在种子所在的宇宙 B_{p,∞} 上反复执行 `ValuationAdaptive` 策略，
+/// 扰动的尺度由当前整数残差的 q-进赋值决定 (见 `state_valuation`)。
+///
+/// 每步评估若干提案，只接受 (有效能量的) 严格改进。没有 Ricci 度量时当前状态始终是最佳状态。
+/// 接受的每一步都记录在 `Trace` 中，从种子格点重放即可复现结果。
+pub struct VapoOptimizer {
+    evaluator: Box<dyn Evaluator>,
+    config: VapoConfig,
+}
+
+impl VapoOptimizer {
+    pub fn new(evaluator: Box<dyn Evaluator>, steps: usize) -> Self {
+        Self::with_config(evaluator, VapoConfig { steps, ..VapoConfig::default() })
+    }
+
+    pub fn with_config(evaluator: Box<dyn Evaluator>, config: VapoConfig) -> Self {
+        Self { evaluator, config }
+    }
+
+    pub fn config(&self) -> &VapoConfig {
+        &self.config
+    }
+
+    /// 从种子出发搜索，返回最佳状态及其转移记录
+    pub fn search(&self, seed: &IdealClass) -> (IdealClass, Trace) {
+        let rng_seed = self
+            .config
+            .seed
+            .unwrap_or_else(|| AlgebraicState::anchored(seed.discriminator, seed.value).hash());
+        let mut rng = rng_from_seed(rng_seed);
+        let mut walker = Walker::new(seed, self.evaluator.as_ref(), rng_seed);
+        if let Some(config) = &self.config.ricci {
+            walker.metric = Some(RicciMetric::with_config(config.clone()));
+        }
+        let mut strategy = ValuationAdaptive::new(self.config.clone());
+
+        for _ in 0..self.config.steps {
+            if matches!(strategy.step(&mut walker, self.evaluator.as_ref(), &mut rng), StepResult::Converged(_)) {
+                break;
+            }
+            if let Some(metric) = walker.metric.as_mut() {
+                metric.advance();
             }
+        }
 
-            // Annealing / Dynamics adjustment
-            self.rho *= 1.05; // Gradually stiffen the penalties
+        (walker.ideal(), walker.trace)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::body::projection::Projector;
+    use crate::soul::hecke::{HeckeMove, HECKE_PRIMES};
+    use crate::will::acceptance::CoolingSchedule;
+    use crate::will::evaluator::GeometricEvaluator;
+
+    fn vapo(steps: usize, seed: u64) -> (VapoOptimizer, IdealClass) {
+        let target = AlgebraicState::new_root(37)
+            .apply_move(&HeckeMove::new(3, 0))
+            .and_then(|s| s.apply_move(&HeckeMove::new(5, 1)))
+            .expect("generators exist for p = 37");
+        let target = IdealClass { value: target.value, discriminator: 37 };
+        let evaluator = GeometricEvaluator::toward(Projector::new(37), &target);
+        let config = VapoConfig { steps, seed: Some(seed), ..VapoConfig::default() };
+        (VapoOptimizer::with_config(Box::new(evaluator), config), IdealClass::identity(37))
+    }
+
+    #[test]
+    fn test_residual_valuation() {
+        assert_eq!(residual_valuation(&[], 2), u32::MAX);
+        assert_eq!(residual_valuation(&[0.0, 0.0], 2), u32::MAX);
+        // 1 + 3 = 4 = 2^2 处违规
+        assert_eq!(residual_valuation(&[1.0, 3.0], 2), 2);
+        assert_eq!(residual_valuation(&[9.0], 3), 2);
+        assert_eq!(residual_valuation(&[7.0], 2), 0);
+    }
+
+    #[test]
+    fn test_configs_reject_unknown_fields() {
+        assert!(serde_json::from_str::<VapoConfig>(r#"{"steps": 3}"#).is_ok());
+        assert!(serde_json::from_str::<VapoConfig>(r#"{"step": 3}"#).is_err());
+        assert!(serde_json::from_str::<AlmConfig>(r#"{"rho": 1.0}"#).is_err());
+        assert!(serde_json::from_str::<TierPenalties>(r#"{"hardd": 1.0}"#).is_err());
+    }
+
+    #[test]
+    fn test_energy_valuation() {
+        assert_eq!(energy_valuation(0.0, 2), u32::MAX);
+        assert_eq!(energy_valuation(1e-9, 2), u32::MAX);
+        // 8e-6 * 1e6 = 8 = 2^3
+        assert_eq!(energy_valuation(8e-6, 2), 3);
+        assert_eq!(energy_valuation(-8e-6, 2), 3);
+        assert_eq!(energy_valuation(9e-6, 3), 2);
+        assert_eq!(energy_valuation(7e-6, 2), 0);
+    }
+
+    /// 能量的量化是 8 = 2^3，但只有一处约束违规
+    struct OneViolation;
+
+    impl Evaluator for OneViolation {
+        fn evaluate(&self, _state: &IdealClass) -> f64 {
+            8e-6
         }
 
-        // Final Check
-        let final_h = StpBridge::calculate_hamiltonian(
-            &current_state, &self.target, &self.multipliers, &self.slacks, self.rho, self.mu
-        );
+        fn residuals(&self, _state: &IdealClass) -> Vec<f64> {
+            vec![1.0, 0.0]
+        }
+    }
 
-        // If we have non-zero slacks but converged, it's a Compromised Success
-        let total_slack: f64 = self.slacks.iter().sum();
-        if total_slack > 0.0 {
-            return EvolutionResult::CompromisedSuccess(trace, self.slacks.clone());
+    #[test]
+    fn test_state_valuation_prefers_integer_residuals() {
+        let state = IdealClass::identity(37);
+        assert_eq!(state_valuation(&OneViolation, &state, 8e-6, 2), 0);
+
+        // 纯几何评估器没有残差，退回能量的量化
+        let (vapo, seed) = vapo(0, 0);
+        let energy = vapo.evaluator.evaluate(&seed);
+        assert_eq!(state_valuation(vapo.evaluator.as_ref(), &seed, energy, 2), energy_valuation(energy, 2));
+    }
+
+    #[test]
+    fn test_vapo_energy_is_non_increasing_and_replays() {
+        let (optimizer, seed) = vapo(40, 11);
+        let start = optimizer.evaluator.evaluate(&seed);
+        let (best, trace) = optimizer.search(&seed);
+
+        let log = trace.energy_log();
+        assert!(!log.is_empty(), "the target is reachable, so some proposal improves");
+        assert!(log.windows(2).all(|w| w[1] < w[0]), "{:?}", log);
+        assert!(log.first().is_none_or(|&e| e < start));
+        assert!(optimizer.evaluator.evaluate(&best) <= start);
+
+        let replayed = trace.replay().expect("every accepted step replays");
+        assert_eq!(replayed.value, best.value);
+        assert_eq!(replayed.p, best.discriminator);
+    }
+
+    #[test]
+    fn test_vapo_is_deterministic_per_seed() {
+        let (a, seed) = vapo(20, 5);
+        let (b, _) = vapo(20, 5);
+        let (ideal_a, trace_a) = a.search(&seed);
+        let (ideal_b, trace_b) = b.search(&seed);
+        assert_eq!(ideal_a, ideal_b);
+        assert_eq!(trace_a.energy_log(), trace_b.energy_log());
+    }
+
+    #[test]
+    fn test_vapo_converged_seed_does_not_move() {
+        let target = IdealClass::identity(37);
+        let evaluator = GeometricEvaluator::toward(Projector::new(37), &target);
+        let optimizer = VapoOptimizer::with_config(Box::new(evaluator), VapoConfig { seed: Some(3), ..VapoConfig::default() });
+        let (best, trace) = optimizer.search(&target);
+        assert_eq!(best, target);
+        assert!(trace.is_empty());
+    }
+
+    #[test]
+    fn test_uphill_epochs_are_accepted_not_improved() {
+        let (vapo, seed) = vapo(0, 0);
+        let evaluator = vapo.evaluator.as_ref();
+        let hot = AcceptanceRule::Metropolis { temperature: 100.0, schedule: CoolingSchedule::Constant };
+        let mut optimizer = Optimizer::new().max_epochs(40).seed(9).acceptance(hot).unwrap();
+        let mut walker = Walker::new(&seed, evaluator, 9);
+
+        let mut accepted = 0;
+        for _ in 0..40 {
+            let before = walker.energy;
+            match optimizer.step(&mut walker, evaluator) {
+                StepResult::Improved(e) => assert!(e < before, "{} !< {}", e, before),
+                StepResult::Accepted(e) => {
+                    assert!(e >= before);
+                    accepted += 1;
+                }
+                _ => {}
+            }
+        }
+        assert!(accepted > 0);
+    }
+
+    #[test]
+    fn test_invalid_acceptance_is_rejected() {
+        let rule = AcceptanceRule::Metropolis { temperature: 1.0, schedule: CoolingSchedule::Geometric { alpha: 1.5 } };
+        assert_eq!(Optimizer::new().acceptance(rule).err(), Some(AcceptanceError::Alpha(1.5)));
+    }
+
+    /// 一个永远无法满足的约束：C(S) = 5 (远低于硬约束的妥协代价 μ = 10)
+    struct Unsatisfiable;
+
+    impl Evaluator for Unsatisfiable {
+        fn evaluate(&self, _state: &IdealClass) -> f64 {
+            1.0
+        }
+
+        fn residuals(&self, _state: &IdealClass) -> Vec<f64> {
+            vec![5.0]
+        }
+    }
+
+    #[test]
+    fn test_rho_stiffens_when_primal_dominates() {
+        let alm = AlmConfig { rho_init: 1.0, ..AlmConfig::default() };
+        let mut optimizer = Optimizer::new().alm(alm).axioms(vec![Axiom::syntax()]).max_epochs(8).seed(1);
+        let mut walker = Walker::new(&IdealClass::identity(37), &Unsatisfiable, 1);
+        for _ in 0..8 {
+            optimizer.step(&mut walker, &Unsatisfiable);
         }
 
-        EvolutionResult::ValidFailure(trace, final_h.total_energy)
+        let history = optimizer.history();
+        assert_eq!(history.len(), 8);
+        // 第一个 epoch：ξ = relu(5 - 10) = 0，原始残差 5 而对偶残差 0，ρ 翻倍
+        assert_eq!(history[0].slacks, [0.0]);
+        assert_eq!(history[0].dual_residual, 0.0);
+        assert_eq!(history[1].rho, [2.0]);
+
+        // 之后每当原始残差压倒对偶残差，下一个 epoch 的 ρ 都被放大
+        let balance = AlmConfig::default().balance;
+        for pair in history.windows(2) {
+            if pair[0].primal_residual > balance * pair[0].dual_residual {
+                assert_eq!(pair[1].rho[0], pair[0].rho[0] * 2.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_every_strategy_is_selectable() {
+        let (vapo, seed) = vapo(0, 0);
+        let evaluator = vapo.evaluator.as_ref();
+        for (strategy, name) in [
+            (Strategy::Greedy, "greedy"),
+            (Strategy::ValuationAdaptive, "valuation_adaptive"),
+            (Strategy::Paraconsistent, "paraconsistent"),
+        ] {
+            let mut optimizer = Optimizer::new().strategy(strategy);
+            assert_eq!(optimizer.strategy_name(), None);
+            optimizer.step(&mut Walker::new(&seed, evaluator, 0), evaluator);
+            assert_eq!(optimizer.strategy_name(), Some(name));
+        }
+    }
+
+    #[test]
+    fn test_greedy_and_vapo_take_different_steps() {
+        let (vapo, seed) = vapo(0, 0);
+        let evaluator = vapo.evaluator.as_ref();
+
+        // Greedy：移动到能量最低的 Hecke 邻居
+        let mut greedy = Optimizer::new().strategy(Strategy::Greedy);
+        let mut walker = Walker::new(&seed, evaluator, 0);
+        let best_neighbour = walker
+            .state
+            .generate_neighbors()
+            .iter()
+            .map(|n| evaluator.evaluate(&IdealClass { value: n.value, discriminator: n.p }))
+            .fold(f64::INFINITY, f64::min);
+        assert!(matches!(greedy.step(&mut walker, evaluator), StepResult::Improved(_)));
+        assert_eq!(walker.energy, best_neighbour);
+        assert_eq!(walker.state.path_history.len(), 1);
+
+        // VAPO：跳跃长度与 ℓ 由起点残差的赋值决定，而不是由邻居的能量
+        let valuation = state_valuation(evaluator, &seed, evaluator.evaluate(&seed), 2);
+        let length = 8usize.checked_shr(valuation).unwrap_or(0).max(1);
+        let ell = if length == 1 { HECKE_PRIMES[0] } else { HECKE_PRIMES[HECKE_PRIMES.len() - 1] };
+        let mut moved = 0;
+        for s in 0..8 {
+            let mut optimizer = Optimizer::new().strategy(Strategy::ValuationAdaptive).seed(s);
+            let mut walker = Walker::new(&seed, evaluator, s);
+            if let StepResult::Improved(e) = optimizer.step(&mut walker, evaluator) {
+                assert!(e >= best_neighbour || length > 1);
+                assert!(walker.state.path_history.len() <= length);
+                assert!(walker.state.path_history.iter().all(|mv| mv.ell == ell));
+                moved += 1;
+            }
+        }
+        assert!(moved > 0);
+    }
+
+    #[test]
+    fn test_ricci_metric_reaches_vapo() {
+        let (free, seed) = vapo(10, 11);
+        let (_, trace) = free.search(&seed);
+        assert!(!trace.is_empty());
+
+        // γ 远大于任何能量差：每一步都比原地不动更贵
+        let stiff = RicciConfig { sensitivity: 1e6, ..RicciConfig::default() };
+        let (optimizer, _) = vapo(10, 11);
+        let config = VapoConfig { ricci: Some(stiff), ..optimizer.config().clone() };
+        let (best, trace) = VapoOptimizer::with_config(optimizer.evaluator, config).search(&seed);
+        assert!(trace.is_empty());
+        assert_eq!(best, seed);
+    }
+
+    #[test]
+    fn test_walker_compares_effective_energies() {
+        let (vapo, seed) = vapo(0, 0);
+        let evaluator = vapo.evaluator.as_ref();
+        let mut walker = Walker::new(&seed, evaluator, 0).metric(RicciMetric::new());
+
+        // 走两步：当前状态的 J_eff 计入整条路径 (两条权重为 1 的边)
+        for mv in [HeckeMove::new(3, 0), HeckeMove::new(5, 1)] {
+            let next = walker.state.apply_move(&mv).unwrap();
+            let e = evaluator.evaluate(&IdealClass { value: next.value, discriminator: next.p });
+            assert_eq!(walker.effective(&next, e), e + walker.effective_current(0.0) + 1.0);
+            walker.advance(next, e);
+        }
+        assert_eq!(walker.effective_current(walker.energy), walker.energy + 2.0);
+        let here = walker.state.clone();
+        assert_eq!(walker.effective(&here, walker.energy), walker.effective_current(walker.energy));
     }
 }
@@ -19,6 +19,12 @@ impl HeckePerturber {
     }
 }
 
+impl Default for HeckePerturber {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Perturber for HeckePerturber {
     fn get_moves(&self) -> Vec<Quaternion> {
         // 硬编码 B_{37, \infty} 的生成元集合。
@@ -0,0 +1,258 @@
+// Copyright (c) 2025 M-Patek
+// Part of the Evolver Project
+//
+// "To begin again is not to forget; it is to walk back onto the graph on purpose."
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::soul::algebra::AlgebraicState;
+use crate::soul::hecke::HeckeMove;
+
+/// 重启调度 (Restart Schedule)
+/// 决定何时在没有外部触发 (卫士否决 / 死胡同) 的情况下主动重启。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RestartSchedule {
+    /// 只在卫士否决或死胡同时重启
+    OnDemand,
+    /// Luby 序列：第 i 次重启前运行 unit * luby(i) 个 epoch (1, 1, 2, 1, 1, 2, 4, ...)
+    Luby { unit: usize },
+    /// 几何序列：第 i 次重启前运行 initial * factor^i 个 epoch
+    Geometric { initial: usize, factor: f64 },
+    /// 能量平台：行者的能量连续 patience 个 epoch 没有下降超过 tolerance 时重启
+    Plateau { patience: usize, tolerance: f64 },
+}
+
+/// 重启游走的起点
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RestartOrigin {
+    /// 当前宇宙中迄今为止能量最低的状态
+    Best,
+    /// 当前宇宙的种子状态 (初始根，或迁移后提升得到的状态)
+    Seed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RestartConfig {
+    pub schedule: RestartSchedule,
+    pub origin: RestartOrigin,
+    /// 每次重启游走的生成元个数 k
+    pub walk_length: usize,
+}
+
+impl Default for RestartConfig {
+    fn default() -> Self {
+        Self {
+            schedule: RestartSchedule::OnDemand,
+            origin: RestartOrigin::Best,
+            walk_length: 8,
+        }
+    }
+}
+
+/// 重启的起因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RestartReason {
+    /// 卫士否决了行者，且波前中没有可以接替的状态
+    GuardVeto,
+    /// 死胡同：没有生成任何候选者
+    Stuck,
+    /// Luby / Geometric 调度到期
+    Scheduled,
+    /// 能量平台
+    Plateau,
+    /// 组合搜索中的其他成员已经找到了低得多的能量 (见 `LagPolicy`)
+    Lagging,
+}
+
+/// 一次重启：从起点出发、由 k 个真实生成元组成的随机游走。
+/// 从 `origin` 依次应用 `walk` 即可复现 `state`，因此重启后的状态仍在 Cayley 图上且可验证。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Restart {
+    pub reason: RestartReason,
+    /// 游走起点
+    pub origin: AlgebraicState,
+    /// 游走依次使用的生成元
+    pub walk: Vec<HeckeMove>,
+    /// 游走终点 (新的行者)
+    pub state: AlgebraicState,
+}
+
+impl Restart {
+    /// 从起点重放游走，检验是否到达记录的终点
+    pub fn verify(&self) -> bool {
+        self.walk
+            .iter()
+            .try_fold(self.origin.clone(), |state, mv| state.apply_move(mv))
+            .is_some_and(|state| state == self.state)
+    }
+}
+
+/// 重启策略 (Restart Strategy)
+///
+/// 取代了无记录的随机跳跃：所有重启 (调度触发或被迫) 都是从起点出发的非回溯随机游走，
+/// 并以 `Restart` 的形式完整记录。任何一次重启都会重置调度时钟。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestartStrategy {
+    config: RestartConfig,
+    /// 已执行的重启次数 (决定 Luby / Geometric 的下一个间隔)
+    restarts: usize,
+    /// 距上次重启的 epoch 数
+    since_restart: usize,
+    /// 自上次重启以来行者达到的最低能量 (平台检测；JSON 无法表示无穷大，因此用 None 表示尚无记录)
+    plateau_energy: Option<f64>,
+    /// 能量没有下降的连续 epoch 数
+    stale: usize,
+}
+
+impl RestartStrategy {
+    pub fn new() -> Self {
+        Self::with_config(RestartConfig::default())
+    }
+
+    pub fn with_config(config: RestartConfig) -> Self {
+        Self {
+            config,
+            restarts: 0,
+            since_restart: 0,
+            plateau_energy: None,
+            stale: 0,
+        }
+    }
+
+    pub fn config(&self) -> &RestartConfig {
+        &self.config
+    }
+
+    pub fn restarts(&self) -> usize {
+        self.restarts
+    }
+
+    /// 每个 epoch 结束时以行者的能量调用，返回调度是否要求重启
+    pub fn tick(&mut self, energy: f64) -> Option<RestartReason> {
+        self.since_restart += 1;
+
+        match self.config.schedule {
+            RestartSchedule::OnDemand => None,
+            RestartSchedule::Luby { unit } => {
+                let interval = unit.max(1) * luby(self.restarts + 1);
+                (self.since_restart >= interval).then_some(RestartReason::Scheduled)
+            }
+            RestartSchedule::Geometric { initial, factor } => {
+                let interval = (initial.max(1) as f64 * factor.max(1.0).powf(self.restarts as f64)).ceil();
+                (self.since_restart as f64 >= interval).then_some(RestartReason::Scheduled)
+            }
+            RestartSchedule::Plateau { patience, tolerance } => {
+                if self.plateau_energy.is_none_or(|plateau| energy < plateau - tolerance) {
+                    self.plateau_energy = Some(energy);
+                    self.stale = 0;
+                } else {
+                    self.stale += 1;
+                }
+                (self.stale >= patience.max(1)).then_some(RestartReason::Plateau)
+            }
+        }
+    }
+
+    /// 执行一次重启：从 `origin` 出发走 k 步非回溯随机游走
+    pub fn restart<R: Rng + ?Sized>(&mut self, reason: RestartReason, origin: &AlgebraicState, rng: &mut R) -> Restart {
+        self.restarts += 1;
+        self.since_restart = 0;
+        self.plateau_energy = None;
+        self.stale = 0;
+
+        let state = origin.random_walk(self.config.walk_length, rng);
+        Restart {
+            reason,
+            origin: origin.clone(),
+            walk: state.path_history[origin.path_history.len()..].to_vec(),
+            state,
+        }
+    }
+}
+
+impl Default for RestartStrategy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Luby 序列的第 i 项 (i >= 1)：1, 1, 2, 1, 1, 2, 4, 1, 1, 2, 1, 1, 2, 4, 8, ...
+pub fn luby(i: usize) -> usize {
+    let mut i = i.max(1);
+    loop {
+        let mut k = 1;
+        while (1usize << k) - 1 < i {
+            k += 1;
+        }
+        if (1usize << k) - 1 == i {
+            return 1usize << (k - 1);
+        }
+        i -= (1usize << (k - 1)) - 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::soul::entropy::rng_from_seed;
+
+    /// 从头开始，直到调度要求重启时经过的 epoch 数 (重启后时钟归零)
+    fn intervals(schedule: RestartSchedule, count: usize) -> Vec<usize> {
+        let mut strategy = RestartStrategy::with_config(RestartConfig { schedule, ..RestartConfig::default() });
+        let mut rng = rng_from_seed(1);
+        let root = AlgebraicState::new_root(37);
+        (0..count)
+            .map(|_| {
+                let mut epochs = 1;
+                while strategy.tick(0.0).is_none() {
+                    epochs += 1;
+                }
+                strategy.restart(RestartReason::Scheduled, &root, &mut rng);
+                epochs
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_luby_values() {
+        let values: Vec<usize> = (1..=15).map(luby).collect();
+        assert_eq!(values, vec![1, 1, 2, 1, 1, 2, 4, 1, 1, 2, 1, 1, 2, 4, 8]);
+        assert_eq!(intervals(RestartSchedule::Luby { unit: 3 }, 7), vec![3, 3, 6, 3, 3, 6, 12]);
+    }
+
+    #[test]
+    fn test_geometric_growth() {
+        let schedule = RestartSchedule::Geometric { initial: 2, factor: 2.0 };
+        assert_eq!(intervals(schedule, 4), vec![2, 4, 8, 16]);
+    }
+
+    #[test]
+    fn test_plateau_trigger() {
+        let schedule = RestartSchedule::Plateau { patience: 3, tolerance: 0.1 };
+        let mut strategy = RestartStrategy::with_config(RestartConfig { schedule, ..RestartConfig::default() });
+
+        // 持续下降：不触发
+        for energy in [5.0, 4.0, 3.0, 2.0] {
+            assert_eq!(strategy.tick(energy), None);
+        }
+        // 下降不足 tolerance 的 epoch 计为停滞，连续 3 个后触发
+        assert_eq!(strategy.tick(1.95), None);
+        assert_eq!(strategy.tick(2.5), None);
+        assert_eq!(strategy.tick(1.92), Some(RestartReason::Plateau));
+    }
+
+    #[test]
+    fn test_restart_walk_verifies() {
+        let mut strategy = RestartStrategy::new();
+        let mut rng = rng_from_seed(5);
+        let origin = AlgebraicState::new_root(37);
+        let restart = strategy.restart(RestartReason::Stuck, &origin, &mut rng);
+
+        assert_eq!(restart.walk.len(), RestartConfig::default().walk_length);
+        assert!(restart.verify());
+        assert_eq!(strategy.restarts(), 1);
+    }
+}
@@ -1,116 +1,560 @@
-use crate::soul::algebra::IdealClass;
-use crate::will::perturber::Perturber; // 假设 Perturber 提供了获取邻居的方法
-use crate::body::projection::Projector; // 用于在特征空间计算距离
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
-/// Discrete Ricci Flow Engine
-/// 
-/// 负责计算 Ollivier-Ricci 曲率并管理度量场的演化。
-/// 核心思想：通过拉长负曲率（高熵）区域的边，迫使优化器绕道。
-pub struct RicciFlow {
+use nalgebra::{DMatrix, DVector};
+use serde::{Deserialize, Serialize};
+
+use crate::soul::algebra::AlgebraicState;
+use crate::will::dynamics::SinkhornEngine;
+
+/// 可行流的数值容差：剩余容量低于此值视为饱和
+const FLOW_EPSILON: f64 = 1e-12;
+
+/// 边权重的下限 (正曲率的边收缩，但长度保持为正)
+const MIN_WEIGHT: f64 = 1e-6;
+
+/// W_1 的传输求解器
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TransportSolver {
+    /// 精确最优传输 (逐次最短路最小费用流)。支撑集只有 deg + 1 个点，代价很小
+    Exact,
+    /// 熵正则化近似 (Sinkhorn-Knopp)：更快，但 W_1 被高估约 O(epsilon · log n)。
+    /// 成本最大为 3，epsilon 过小 (< 0.2) 时 Gibbs 核下溢，边缘约束不再成立
+    Sinkhorn { epsilon: f64, iterations: usize },
+}
+
+/// Ricci 流配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RicciConfig {
+    /// 惰性参数 alpha：m_x 在 x 自身保留 alpha 的质量，其余均分给邻居
+    pub alpha: f64,
+    pub solver: TransportSolver,
     /// 曲率修正强度 (gamma)
-    sensitivity: f64,
+    pub sensitivity: f64,
     /// 模拟的时间步长 (dt)
-    flow_step: f64,
-    /// 投影仪，用于将代数状态映射到几何空间以计算 Wasserstein 距离
-    projector: Projector,
+    pub flow_step: f64,
+    /// 每次推进后把总体积 Σ w(e) 归一化为边数 (平均权重保持为 1)
+    pub normalize: bool,
 }
 
-impl RicciFlow {
-    pub fn new(sensitivity: f64, p: u64) -> Self {
+impl Default for RicciConfig {
+    fn default() -> Self {
         Self {
-            sensitivity,
+            alpha: 0.5,
+            solver: TransportSolver::Exact,
+            sensitivity: 1.0,
             flow_step: 0.1, // 默认流速
-            projector: Projector::new(p),
+            normalize: false,
         }
     }
+}
+
+/// Discrete Ricci Flow Engine
+///
+/// 负责计算 Ollivier-Ricci 曲率并管理度量场的演化。
+/// 核心思想：通过拉长负曲率（高熵）区域的边，迫使优化器绕道。
+#[derive(Debug, Clone)]
+pub struct RicciFlow {
+    config: RicciConfig,
+}
+
+impl RicciFlow {
+    pub fn new() -> Self {
+        Self::with_config(RicciConfig::default())
+    }
 
-    /// 计算两个状态（边）之间的 Ollivier-Ricci 曲率
-    /// 
+    pub fn with_config(config: RicciConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn config(&self) -> &RicciConfig {
+        &self.config
+    }
+
+    /// 计算一条边 (x, y) 的 Ollivier-Ricci 曲率
+    ///
     /// Formula: \kappa(x, y) = 1 - W_1(m_x, m_y) / d(x, y)
-    /// 
-    /// 注意：在巨大的隐式图中计算精确的 Wasserstein 距离 (W1) 是昂贵的。
-    /// 这里我们使用基于特征空间投影的贪婪近似 (Greedy Approximation)。
-    pub fn calculate_curvature(
-        &self, 
-        state_x: &IdealClass, 
-        state_y: &IdealClass,
-        perturber: &Perturber
-    ) -> f64 {
-        // 1. 获取 x 和 y 的邻域 (1-hop neighbors)
-        // 注意：Perturber 需要支持 peek_neighbors 操作
-        let neighbors_x = perturber.peek_neighbors(state_x);
-        let neighbors_y = perturber.peek_neighbors(state_y);
+    ///
+    /// m_x 是行者的惰性随机游走 (右乘生成元)；d 是理想类上无向 Hecke 图的跳数距离
+    /// (S 与 S·g、S·ḡ 相邻)，边的两端 d(x, y) = 1。
+    /// 支撑点之间的距离不超过 3 (u ~ x ~ y ~ v)，因此成本矩阵可以从各支撑点的一步邻域精确得到，
+    /// 无需在隐式图上做全局搜索。
+    pub fn calculate_curvature(&self, state_x: &AlgebraicState, state_y: &AlgebraicState) -> f64 {
+        // 1. 惰性随机游走测度 m_x, m_y
+        let measure_x = self.measure(state_x);
+        let measure_y = self.measure(state_y);
 
-        if neighbors_x.is_empty() || neighbors_y.is_empty() {
+        if measure_x.is_empty() || measure_y.is_empty() {
             return 0.0; // 孤立点，无曲率信息
         }
 
-        // 2. 计算基准距离 d(x, y)
-        // 在 Cayley 图中，相邻节点的图距离通常为 1，但在特征流形上并非如此。
-        // 我们使用特征空间的欧氏距离作为度量。
-        let feat_x = self.projector.project_continuous(state_x);
-        let feat_y = self.projector.project_continuous(state_y);
-        let base_dist = self.euclidean_dist(&feat_x, &feat_y).max(1e-6);
+        // 2. 支撑点之间的图距离
+        let mut balls: HashMap<u64, HashSet<u64>> = HashMap::new();
+        let endpoints = [state_x, state_y];
+        for state in endpoints.into_iter().chain(measure_x.iter().chain(&measure_y).map(|(s, _)| s)) {
+            balls.entry(state.canonical_hash()).or_insert_with(|| ball(state));
+        }
+        let base_dist = graph_distance(state_x.canonical_hash(), state_y.canonical_hash(), &balls).max(1) as f64;
+
+        let support = |measure: &[(AlgebraicState, f64)]| -> Vec<(u64, f64)> {
+            measure.iter().map(|(s, m)| (s.canonical_hash(), *m)).collect()
+        };
+        self.transport_curvature(&support(&measure_x), &support(&measure_y), base_dist, |u, v| {
+            graph_distance(u, v, &balls)
+        })
+    }
+
+    /// κ = 1 - W_1(m_x, m_y) / d(x, y)，测度以支撑点的键给出，`distance` 是支撑点之间的跳数
+    fn transport_curvature(
+        &self,
+        measure_x: &[(u64, f64)],
+        measure_y: &[(u64, f64)],
+        base_dist: f64,
+        distance: impl Fn(u64, u64) -> usize,
+    ) -> f64 {
+        let cost = DMatrix::from_fn(measure_x.len(), measure_y.len(), |i, j| distance(measure_x[i].0, measure_y[j].0) as f64);
+        let a = DVector::from_iterator(measure_x.len(), measure_x.iter().map(|(_, m)| *m));
+        let b = DVector::from_iterator(measure_y.len(), measure_y.iter().map(|(_, m)| *m));
 
-        // 3. 近似计算 W1 距离 (Earth Mover's Distance)
-        // 简化逻辑：计算邻居集合在特征空间重心的移动距离 + 散度差异
-        // 这是一个快速的启发式替代方案，比求解线性规划快得多。
-        let w1_dist = self.approximate_w1(&neighbors_x, &neighbors_y);
+        // 3. W1 距离 (Earth Mover's Distance)
+        let w1_dist = match &self.config.solver {
+            TransportSolver::Exact => exact_w1(&a, &b, &cost),
+            TransportSolver::Sinkhorn { epsilon, iterations } => {
+                SinkhornEngine::new(*epsilon).max_iter(*iterations).compute_divergence(&a, &b, &cost)
+            }
+        };
 
         // 4. 计算曲率
-        let kappa = 1.0 - (w1_dist / base_dist);
+        1.0 - w1_dist / base_dist
+    }
+
+    /// m_x = alpha · δ_x + (1 - alpha) / deg(x) · Σ_{z ~ x} δ_z (重边的质量合并到同一点)
+    fn measure(&self, state: &AlgebraicState) -> Vec<(AlgebraicState, f64)> {
+        let neighbors = state.generate_neighbors();
+        if neighbors.is_empty() {
+            return Vec::new();
+        }
 
-        // 钳制曲率范围，防止数值不稳定
-        kappa.max(-2.0).min(1.0)
+        let alpha = self.config.alpha.clamp(0.0, 1.0);
+        let share = (1.0 - alpha) / neighbors.len() as f64;
+        let mut measure: Vec<(AlgebraicState, f64)> = Vec::new();
+        if alpha > 0.0 {
+            measure.push((state.clone(), alpha));
+        }
+        for n in neighbors {
+            let h = n.canonical_hash();
+            match measure.iter_mut().find(|(s, _)| s.canonical_hash() == h) {
+                Some((_, mass)) => *mass += share,
+                None => measure.push((n, share)),
+            }
+        }
+        measure
     }
 
     /// 计算“有效梯度修正项”
-    /// 
+    ///
     /// \nabla_{eff} = \nabla E + \gamma * exp(-\kappa)
-    /// 
+    ///
     /// 如果曲率 \kappa 是负的（陷阱），exp(-\kappa) 会很大，产生巨大的排斥势能。
     pub fn compute_penalty(&self, kappa: f64) -> f64 {
         if kappa >= 0.0 {
             // 正曲率区域（结构稳固），给予少量奖励或无惩罚
-            return -0.1 * kappa.abs(); 
+            -0.1 * kappa.abs()
         } else {
             // 负曲率区域（熵增陷阱），给予指数级惩罚
             // 物理意义：将有效距离拉长
-            return self.sensitivity * (-kappa).exp();
+            self.config.sensitivity * (-kappa).exp()
+        }
+    }
+}
+
+impl Default for RicciFlow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 无向一步邻域：S·g 与 S·ḡ 的理想类。
+/// 字母表对共轭不一定封闭 (逆移动可能落在另一个代表元上)，因此两个方向都要计入。
+pub(crate) fn ball(state: &AlgebraicState) -> HashSet<u64> {
+    let algebra = state.algebra();
+    algebra
+        .generators()
+        .iter()
+        .flat_map(|g| [algebra.checked_mul(&state.value, g), algebra.checked_mul(&state.value, &g.conjugate())])
+        .filter_map(Result::ok)
+        .map(|q| AlgebraicState::anchored(state.p, q).canonical_hash())
+        .collect()
+}
+
+/// 一条边的度量状态
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EdgeWeight {
+    /// 当前权重 w(e)，初值为 1
+    pub weight: f64,
+    /// 边的 Ollivier-Ricci 曲率 κ(e) (在组合图上计算一次并缓存)
+    pub curvature: f64,
+}
+
+/// Ricci 度量 (Ricci Metric)
+///
+/// 已访问边的权重表，按 Topological Amendment 的流方程演化：
+///
+/// dw/dt = -κ(e) · w(e)  (显式 Euler：w ← w · (1 - dt · κ))
+///
+/// 负曲率 (陷阱) 的边被拉长，正曲率的边被缩短。优化器的有效能量
+/// J_eff(S) = J(S) + γ · Σ_{e ∈ path} w(e) 因而会绕开高熵区域。
+/// 边是无向的，以两端理想类的 `canonical_hash` 为键。
+#[derive(Debug, Clone)]
+pub struct RicciMetric {
+    flow: RicciFlow,
+    edges: HashMap<(u64, u64), EdgeWeight>,
+    epoch: usize,
+}
+
+impl RicciMetric {
+    pub fn new() -> Self {
+        Self::with_config(RicciConfig::default())
+    }
+
+    pub fn with_config(config: RicciConfig) -> Self {
+        Self {
+            flow: RicciFlow::with_config(config),
+            edges: HashMap::new(),
+            epoch: 0,
+        }
+    }
+
+    pub fn config(&self) -> &RicciConfig {
+        self.flow.config()
+    }
+
+    /// 已推进的流步数
+    pub fn epoch(&self) -> usize {
+        self.epoch
+    }
+
+    /// 已访问的边数
+    pub fn len(&self) -> usize {
+        self.edges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.edges.is_empty()
+    }
+
+    /// 总体积 Σ w(e)
+    pub fn volume(&self) -> f64 {
+        self.edges.values().map(|e| e.weight).sum()
+    }
+
+    /// 已访问的边 (不触发曲率计算)
+    pub fn edge(&self, x: &AlgebraicState, y: &AlgebraicState) -> Option<&EdgeWeight> {
+        self.edges.get(&edge_key(x, y))
+    }
+
+    /// 边 (x, y) 的当前权重；首次访问时计算曲率并以 w = 1 登记
+    pub fn weight(&mut self, x: &AlgebraicState, y: &AlgebraicState) -> f64 {
+        let flow = &self.flow;
+        self.edges
+            .entry(edge_key(x, y))
+            .or_insert_with(|| EdgeWeight {
+                weight: 1.0,
+                curvature: flow.calculate_curvature(x, y),
+            })
+            .weight
+    }
+
+    /// 从 `from` 沿 `to` 的路径后缀 (`from` 的路径之后的生成元) 走到 `to` 的路径权重 Σ w(e)
+    ///
+    /// 只对 `from` 的延伸有定义；`to` 的路径不以 `from` 的路径为前缀时返回 0。
+    pub fn path_weight(&mut self, from: &AlgebraicState, to: &AlgebraicState) -> f64 {
+        let Some(suffix) = to.path_history.strip_prefix(from.path_history.as_slice()) else {
+            return 0.0;
+        };
+
+        let mut total = 0.0;
+        let mut current = from.clone();
+        for mv in suffix {
+            let Some(next) = current.apply_move(mv) else {
+                break;
+            };
+            total += self.weight(&current, &next);
+            current = next;
+        }
+        total
+    }
+
+    /// 路径积分惩罚 γ · Σ w(e)
+    pub fn penalty(&mut self, from: &AlgebraicState, to: &AlgebraicState) -> f64 {
+        self.flow.config().sensitivity * self.path_weight(from, to)
+    }
+
+    /// 推进一个流步：w ← w · (1 - dt · κ)，可选地把总体积归一化为边数
+    pub fn advance(&mut self) {
+        let dt = self.flow.config().flow_step;
+        for edge in self.edges.values_mut() {
+            edge.weight = (edge.weight * (1.0 - dt * edge.curvature)).max(MIN_WEIGHT);
+        }
+
+        if self.flow.config().normalize && !self.edges.is_empty() {
+            let scale = self.edges.len() as f64 / self.volume();
+            for edge in self.edges.values_mut() {
+                edge.weight *= scale;
+            }
+        }
+        self.epoch += 1;
+    }
+}
+
+impl Default for RicciMetric {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 无向边的键
+fn edge_key(x: &AlgebraicState, y: &AlgebraicState) -> (u64, u64) {
+    let (a, b) = (x.canonical_hash(), y.canonical_hash());
+    (a.min(b), a.max(b))
+}
+
+/// 两个支撑点之间的跳数距离 (0, 1, 2, 或 3)。
+///
+/// 只对边 x ~ y 两端测度的支撑点调用：u ∈ {x} ∪ B(x)，v ∈ {y} ∪ B(y)。
+/// 由三角不等式 d(u, v) <= d(u, x) + d(x, y) + d(y, v) <= 3，
+/// 因此两个一步邻域不相交时距离恰为 3，无需继续搜索。对任意两点调用时 3 只是一个截断值。
+fn graph_distance(u: u64, v: u64, balls: &HashMap<u64, HashSet<u64>>) -> usize {
+    if u == v {
+        return 0;
+    }
+    match (balls.get(&u), balls.get(&v)) {
+        (Some(bu), _) if bu.contains(&v) => 1,
+        (Some(bu), Some(bv)) if !bu.is_disjoint(bv) => 2,
+        _ => 3,
+    }
+}
+
+/// 精确的 W_1：运输问题 min <P, C> s.t. P 1 = a, P^T 1 = b, P >= 0。
+///
+/// 以逐次最短路 (Successive Shortest Path) 求解二部图上的最小费用流：
+/// 源点 → 供给 i (容量 a_i) → 需求 j (容量 ∞, 费用 C_ij) → 汇点 (容量 b_j)。
+/// 每次沿残量网络的最短路增广 (Bellman-Ford，允许反向边的负费用)，
+/// 直到 min(Σa, Σb) 的质量全部运完。结果是线性规划的精确最优值。
+pub fn exact_w1(a: &DVector<f64>, b: &DVector<f64>, cost: &DMatrix<f64>) -> f64 {
+    let (n, m) = (a.len(), b.len());
+    let (source, sink) = (n + m, n + m + 1);
+    let mut graph = FlowGraph::new(n + m + 2);
+
+    for i in 0..n {
+        graph.add_edge(source, i, a[i], 0.0);
+        for j in 0..m {
+            graph.add_edge(i, n + j, f64::INFINITY, cost[(i, j)]);
+        }
+    }
+    for j in 0..m {
+        graph.add_edge(n + j, sink, b[j], 0.0);
+    }
+
+    let mut remaining = a.sum().min(b.sum());
+    let mut total_cost = 0.0;
+    while remaining > FLOW_EPSILON {
+        let Some((path, distance)) = graph.shortest_path(source, sink) else {
+            break;
+        };
+        let bottleneck = path
+            .iter()
+            .map(|&e| graph.edges[e].capacity)
+            .fold(remaining, f64::min);
+        for &e in &path {
+            graph.push(e, bottleneck);
+        }
+        remaining -= bottleneck;
+        total_cost += bottleneck * distance;
+    }
+    total_cost
+}
+
+struct FlowEdge {
+    to: usize,
+    capacity: f64,
+    cost: f64,
+}
+
+/// 残量网络：边 2k 与 2k + 1 互为反向边
+struct FlowGraph {
+    edges: Vec<FlowEdge>,
+    adjacency: Vec<Vec<usize>>,
+}
+
+impl FlowGraph {
+    fn new(nodes: usize) -> Self {
+        Self {
+            edges: Vec::new(),
+            adjacency: vec![Vec::new(); nodes],
         }
     }
 
-    // --- Helper Methods ---
+    fn add_edge(&mut self, from: usize, to: usize, capacity: f64, cost: f64) {
+        self.adjacency[from].push(self.edges.len());
+        self.edges.push(FlowEdge { to, capacity, cost });
+        self.adjacency[to].push(self.edges.len());
+        self.edges.push(FlowEdge { to: from, capacity: 0.0, cost: -cost });
+    }
 
-    fn approximate_w1(&self, set_a: &[IdealClass], set_b: &[IdealClass]) -> f64 {
-        // 计算两个点集的质心距离作为 W1 的下界代理
-        let centroid_a = self.centroid(set_a);
-        let centroid_b = self.centroid(set_b);
-        self.euclidean_dist(&centroid_a, &centroid_b)
+    fn push(&mut self, edge: usize, flow: f64) {
+        self.edges[edge].capacity -= flow;
+        self.edges[edge ^ 1].capacity += flow;
     }
 
-    fn centroid(&self, states: &[IdealClass]) -> Vec<f64> {
-        if states.is_empty() { return vec![0.0; 8]; } // 假设 8维特征
-        
-        let mut sum = vec![0.0; 8]; 
-        let count = states.len() as f64;
+    /// Bellman-Ford 最短路：返回路径上的边及其总费用
+    fn shortest_path(&self, source: usize, sink: usize) -> Option<(Vec<usize>, f64)> {
+        let nodes = self.adjacency.len();
+        let mut distance = vec![f64::INFINITY; nodes];
+        let mut via: Vec<Option<usize>> = vec![None; nodes];
+        distance[source] = 0.0;
 
-        for s in states {
-            let feat = self.projector.project_continuous(s);
-            // 简单的维度对齐，实际应根据 Projector 维度动态调整
-            for (i, v) in feat.iter().enumerate().take(8) {
-                sum[i] += v;
+        for _ in 0..nodes {
+            let mut relaxed = false;
+            for u in 0..nodes {
+                if distance[u].is_infinite() {
+                    continue;
+                }
+                for &e in &self.adjacency[u] {
+                    let edge = &self.edges[e];
+                    if edge.capacity > FLOW_EPSILON && distance[u] + edge.cost < distance[edge.to] - FLOW_EPSILON {
+                        distance[edge.to] = distance[u] + edge.cost;
+                        via[edge.to] = Some(e);
+                        relaxed = true;
+                    }
+                }
+            }
+            if !relaxed {
+                break;
             }
         }
 
-        sum.iter().map(|v| v / count).collect()
+        if distance[sink].is_infinite() {
+            return None;
+        }
+        let mut path = Vec::new();
+        let mut node = sink;
+        while let Some(e) = via[node] {
+            path.push(e);
+            node = self.edges[e ^ 1].to;
+        }
+        path.reverse();
+        Some((path, distance[sink]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::soul::hecke::HeckeMove;
+
+    fn w1(a: &[f64], b: &[f64], cost: &[f64]) -> f64 {
+        exact_w1(
+            &DVector::from_column_slice(a),
+            &DVector::from_column_slice(b),
+            &DMatrix::from_row_slice(a.len(), b.len(), cost),
+        )
+    }
+
+    #[test]
+    fn test_exact_w1_of_identical_measures_is_zero() {
+        let cost = [0.0, 1.0, 2.0, 1.0, 0.0, 1.0, 2.0, 1.0, 0.0];
+        assert!(w1(&[0.2, 0.5, 0.3], &[0.2, 0.5, 0.3], &cost).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_exact_w1_two_points_closed_form() {
+        // 两点之间距离为 c：W_1 = c · |a_0 - b_0|
+        for (a0, b0, c) in [(0.7, 0.4, 1.0), (0.1, 0.9, 2.5), (1.0, 0.0, 3.0)] {
+            let expected = c * f64::abs(a0 - b0);
+            let got = w1(&[a0, 1.0 - a0], &[b0, 1.0 - b0], &[0.0, c, c, 0.0]);
+            assert!((got - expected).abs() < 1e-12, "{} != {}", got, expected);
+        }
+    }
+
+    #[test]
+    fn test_exact_w1_unbalanced_moves_the_smaller_mass() {
+        // 只运送 min(Σa, Σb) = 0.5，且走最便宜的路线
+        let got = w1(&[1.0, 0.0], &[0.0, 0.5], &[0.0, 1.0, 1.0, 0.0]);
+        assert!((got - 0.5).abs() < 1e-12);
+        let got = w1(&[0.3, 0.3], &[1.0], &[2.0, 5.0]);
+        assert!((got - 2.1).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_complete_graph_has_positive_curvature() {
+        // K_4，alpha = 1/2：m_x = 1/2 δ_x + 1/6 (δ_y + δ_u + δ_v)。
+        // 只需把 1/3 的质量从 x 运到 y，W_1 = 1/3，κ = 2/3
+        let flow = RicciFlow::new();
+        let measure = |x: u64| -> Vec<(u64, f64)> {
+            (0..4).map(|z| (z, if z == x { 0.5 } else { 1.0 / 6.0 })).collect()
+        };
+        let kappa = flow.transport_curvature(&measure(0), &measure(1), 1.0, |u, v| usize::from(u != v));
+        assert!((kappa - 2.0 / 3.0).abs() < 1e-12, "{}", kappa);
+    }
+
+    #[test]
+    fn test_graph_distance_saturates_at_three() {
+        let balls: HashMap<u64, HashSet<u64>> =
+            [(1, HashSet::from([2])), (2, HashSet::from([1, 3])), (3, HashSet::from([2])), (4, HashSet::from([5]))].into();
+        assert_eq!(graph_distance(1, 1, &balls), 0);
+        assert_eq!(graph_distance(1, 2, &balls), 1);
+        assert_eq!(graph_distance(1, 3, &balls), 2);
+        assert_eq!(graph_distance(1, 4, &balls), 3);
+    }
+
+    fn metric_with(config: RicciConfig, curvatures: &[f64]) -> RicciMetric {
+        let mut metric = RicciMetric::with_config(config);
+        for (i, &curvature) in curvatures.iter().enumerate() {
+            metric.edges.insert((i as u64, i as u64 + 1), EdgeWeight { weight: 1.0, curvature });
+        }
+        metric
+    }
+
+    #[test]
+    fn test_flow_shrinks_positive_and_stretches_negative_edges() {
+        let mut metric = metric_with(RicciConfig { flow_step: 0.1, ..RicciConfig::default() }, &[0.5, -0.5, 0.0]);
+        for _ in 0..3 {
+            metric.advance();
+        }
+        let weight = |i: u64| metric.edges[&(i, i + 1)].weight;
+        assert!((weight(0) - 0.95f64.powi(3)).abs() < 1e-12);
+        assert!((weight(1) - 1.05f64.powi(3)).abs() < 1e-12);
+        assert_eq!(weight(2), 1.0);
+        assert_eq!(metric.epoch(), 3);
+
+        // 正曲率的边收缩，但长度保持为正
+        let mut collapsing = metric_with(RicciConfig { flow_step: 1.0, ..RicciConfig::default() }, &[2.0]);
+        collapsing.advance();
+        assert_eq!(collapsing.edges[&(0, 1)].weight, MIN_WEIGHT);
+    }
+
+    #[test]
+    fn test_normalization_keeps_volume_equal_to_edge_count() {
+        let config = RicciConfig { flow_step: 0.2, normalize: true, ..RicciConfig::default() };
+        let mut metric = metric_with(config, &[0.9, 0.1, -0.3, -1.0, 0.0]);
+        for _ in 0..20 {
+            metric.advance();
+            assert!((metric.volume() - metric.len() as f64).abs() < 1e-9, "{}", metric.volume());
+        }
+        // 归一化不改变相对顺序：负曲率的边仍然最长
+        assert!(metric.edges[&(3, 4)].weight > metric.edges[&(0, 1)].weight);
     }
 
-    fn euclidean_dist(&self, a: &[f64], b: &[f64]) -> f64 {
-        a.iter().zip(b.iter())
-            .map(|(x, y)| (x - y).powi(2))
-            .sum::<f64>()
-            .sqrt()
+    #[test]
+    fn test_path_weight_outside_an_extension_is_zero() {
+        let root = AlgebraicState::new_root(37);
+        let a = root.apply_move(&HeckeMove::new(3, 0)).unwrap();
+        let b = root.apply_move(&HeckeMove::new(5, 0)).unwrap();
+        let mut metric = RicciMetric::new();
+        assert_eq!(metric.path_weight(&root, &a), 1.0);
+        assert_eq!(metric.path_weight(&a, &b), 0.0);
+        assert_eq!(metric.path_weight(&a, &root), 0.0);
     }
 }
@@ -0,0 +1,478 @@
+// Copyright (c) 2025 M-Patek
+// Part of the Evolver Project
+//
+// "Three ways down the mountain: the steepest, the measured, and the negotiated."
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::dsl::stp_bridge::{HamiltonianState, StpBridge};
+use crate::soul::algebra::{AlgebraicState, IdealClass};
+use crate::soul::entropy::EvolverRng;
+use crate::soul::hecke::{HeckeMove, HECKE_PRIMES};
+use crate::will::acceptance::{AcceptanceError, AcceptanceRule, AcceptanceStats, Acceptor};
+use crate::will::axiom::{Axiom, ContradictionReport, TierPenalties};
+use crate::will::evaluator::Evaluator;
+use crate::will::optimizer::{state_valuation, AlmConfig, AlmEpoch, VapoConfig};
+use crate::will::ricci::RicciMetric;
+use crate::will::tracer::{Trace, Transition};
+
+/// 单步搜索的结果
+#[derive(Debug, Clone)]
+pub enum StepResult {
+    /// 行者移动到了严格更好的状态 (携带新的能量)
+    Improved(f64),
+    /// 行者移动了，但没有严格改进 (Metropolis / 阈值准则接受的上坡或平移，携带新的能量)
+    Accepted(f64),
+    /// 本步没有接受任何移动
+    Stagnated,
+    /// 收敛：能量低于容差 (或 ALM 的约束全部满足)，携带完整的转移记录
+    Converged(Trace),
+    /// 步数预算耗尽 (由 `Optimizer` 判定)
+    Exhausted(Trace),
+}
+
+/// 行者 (Walker)
+/// 搜索策略之间共享的运行状态：当前格点、其能量、已走过的步数与转移记录。
+#[derive(Debug, Clone)]
+pub struct Walker {
+    pub state: AlgebraicState,
+    /// 当前状态在策略眼中的能量 (Greedy / VAPO 为评估器能量，ALM 为目标 E_obj)
+    pub energy: f64,
+    pub steps: usize,
+    pub trace: Trace,
+    /// Ricci 流度量：存在时，移动的代价加上 γ · Σ w(e) (见 `effective`)
+    pub metric: Option<RicciMetric>,
+    /// 当前路径的权重 Σ_{e ∈ path} w(e) 及其所在的流步 (权重只在流推进时变化)
+    path_weight: Option<(usize, f64)>,
+}
+
+impl Walker {
+    /// 从种子格点出发 (见 `AlgebraicState::anchored`)
+    pub fn new(seed: &IdealClass, evaluator: &dyn Evaluator, rng_seed: u64) -> Self {
+        let state = AlgebraicState::anchored(seed.discriminator, seed.value);
+        let trace = Trace::anchored(state.p, state.value, rng_seed);
+        Self {
+            energy: evaluator.evaluate(seed),
+            state,
+            steps: 0,
+            trace,
+            metric: None,
+            path_weight: None,
+        }
+    }
+
+    pub fn metric(mut self, metric: RicciMetric) -> Self {
+        self.metric = Some(metric);
+        self
+    }
+
+    pub fn ideal(&self) -> IdealClass {
+        ideal(&self.state)
+    }
+
+    /// `candidate` 的有效能量 J_eff = J(candidate) + γ · Σ_{e ∈ path(candidate)} w(e)。
+    /// 路径积分从锚点量起；候选者总是当前路径的延伸，因此它等于当前路径的权重加上新边的权重。
+    /// 没有度量时即 J。
+    pub fn effective(&mut self, candidate: &AlgebraicState, energy: f64) -> f64 {
+        let walked = self.path_weight();
+        match self.metric.as_mut() {
+            Some(metric) => energy + metric.config().sensitivity * walked + metric.penalty(&self.state, candidate),
+            None => energy,
+        }
+    }
+
+    /// 当前状态在能量为 `energy` 时的有效能量 J_eff，与 `effective` 使用同一条路径积分
+    pub fn effective_current(&mut self, energy: f64) -> f64 {
+        let walked = self.path_weight();
+        match self.metric.as_ref() {
+            Some(metric) => energy + metric.config().sensitivity * walked,
+            None => energy,
+        }
+    }
+
+    /// 从锚点到当前状态的路径权重 Σ w(e)，在同一个流步内缓存
+    fn path_weight(&mut self) -> f64 {
+        let Some(metric) = self.metric.as_mut() else {
+            return 0.0;
+        };
+        match self.path_weight {
+            Some((epoch, weight)) if epoch == metric.epoch() => weight,
+            _ => {
+                let origin = AlgebraicState::anchored(self.trace.initial_p, self.trace.origin);
+                let weight = metric.path_weight(&origin, &self.state);
+                self.path_weight = Some((metric.epoch(), weight));
+                weight
+            }
+        }
+    }
+
+    /// 移动到 `to` 并记录转移
+    pub fn advance(&mut self, to: AlgebraicState, energy: f64) {
+        if let Some(transition) = Transition::between(&self.state, &to) {
+            self.trace.record(self.steps, transition, energy);
+        }
+        self.state = to;
+        self.energy = energy;
+        self.path_weight = None;
+    }
+}
+
+fn ideal(state: &AlgebraicState) -> IdealClass {
+    IdealClass {
+        value: state.value,
+        discriminator: state.p,
+    }
+}
+
+/// 搜索策略 (Search Strategy)
+///
+/// `Optimizer` 按 `Strategy` 选择的实现。每次 `step` 至多推进行者一次 "宏步"
+/// (一次下降、一次 VAPO 提案轮或一个 ALM epoch)，并返回 `Improved` / `Accepted` / `Stagnated` / `Converged`。
+pub trait SearchStrategy {
+    fn name(&self) -> &'static str;
+
+    fn step(&mut self, walker: &mut Walker, evaluator: &dyn Evaluator, rng: &mut EvolverRng) -> StepResult;
+
+    /// 被放弃的公理 (只有原始-对偶策略会妥协)
+    fn contradictions(&self) -> Option<ContradictionReport> {
+        None
+    }
+
+    /// 每个 epoch 的 ρ / λ / ξ 历史
+    fn history(&self) -> &[AlmEpoch] {
+        &[]
+    }
+
+    /// 接受准则的统计
+    fn acceptance_stats(&self) -> Option<&AcceptanceStats> {
+        None
+    }
+}
+
+/// 纯能量贪婪下降 (Greedy)
+/// 评估全部 Hecke 邻居，移动到能量最低者，仅当严格改进时。
+pub struct GreedyDescent {
+    tolerance: f64,
+}
+
+impl GreedyDescent {
+    pub fn new(tolerance: f64) -> Self {
+        Self { tolerance }
+    }
+}
+
+impl SearchStrategy for GreedyDescent {
+    fn name(&self) -> &'static str {
+        "greedy"
+    }
+
+    fn step(&mut self, walker: &mut Walker, evaluator: &dyn Evaluator, _rng: &mut EvolverRng) -> StepResult {
+        if walker.energy < self.tolerance {
+            return StepResult::Converged(walker.trace.clone());
+        }
+
+        let best = walker
+            .state
+            .generate_neighbors()
+            .into_iter()
+            .map(|n| {
+                let e = evaluator.evaluate(&ideal(&n));
+                let effective = walker.effective(&n, e);
+                (n, e, effective)
+            })
+            .min_by(|a, b| a.2.total_cmp(&b.2));
+
+        let current = walker.effective_current(walker.energy);
+        let result = match best {
+            Some((next, e, effective)) if effective < current => {
+                walker.advance(next, e);
+                StepResult::Improved(e)
+            }
+            _ => StepResult::Stagnated,
+        };
+        walker.steps += 1;
+        result
+    }
+}
+
+/// 赋值自适应扰动 (Valuation-Adaptive Perturbation)
+///
+/// 扰动的尺度由当前残差的 q-进赋值决定：
+/// - 赋值大 (残差在 q-进意义下已经 "很接近" 零)：精细移动，单步、只用最小的 ℓ (T_3)
+/// - 赋值小：粗跳，max_jump / 2^v 步的多步游走、只用最大的 ℓ
+///
+/// 每步评估若干提案，只接受严格改进。
+pub struct ValuationAdaptive {
+    config: VapoConfig,
+}
+
+impl ValuationAdaptive {
+    pub fn new(config: VapoConfig) -> Self {
+        Self { config }
+    }
+
+    /// 赋值 v 对应的扰动：(跳跃长度, 使用的 ℓ)
+    fn perturbation(&self, valuation: u32) -> (usize, u64) {
+        let length = self.config.max_jump.max(1).checked_shr(valuation).unwrap_or(0).max(1);
+        let ell = if length == 1 { HECKE_PRIMES[0] } else { HECKE_PRIMES[HECKE_PRIMES.len() - 1] };
+        (length, ell)
+    }
+
+    /// 只使用 ℓ-生成元的非回溯随机游走 (本宇宙没有 ℓ-生成元时退回完整字母表)
+    fn propose<R: Rng + ?Sized>(&self, state: &AlgebraicState, length: usize, ell: u64, rng: &mut R) -> AlgebraicState {
+        let alphabet = state.algebra().alphabet();
+        let all = alphabet.moves();
+        let restricted: Vec<HeckeMove> = all.iter().copied().filter(|mv| mv.ell == ell).collect();
+        let moves = if restricted.is_empty() { all } else { restricted };
+
+        let mut walker = state.clone();
+        for _ in 0..length {
+            let backtrack = walker.path_history.last().and_then(|mv| alphabet.inverse(mv));
+            let forward: Vec<&HeckeMove> = moves.iter().filter(|mv| Some(**mv) != backtrack).collect();
+            if let Some(next) = forward.choose(rng).and_then(|mv| walker.apply_move(mv)) {
+                walker = next;
+            }
+        }
+        walker
+    }
+}
+
+impl SearchStrategy for ValuationAdaptive {
+    fn name(&self) -> &'static str {
+        "valuation_adaptive"
+    }
+
+    fn step(&mut self, walker: &mut Walker, evaluator: &dyn Evaluator, rng: &mut EvolverRng) -> StepResult {
+        if walker.energy < self.config.tolerance {
+            return StepResult::Converged(walker.trace.clone());
+        }
+
+        let valuation = state_valuation(evaluator, &ideal(&walker.state), walker.energy, self.config.valuation_prime);
+        let (length, ell) = self.perturbation(valuation);
+
+        let best = (0..self.config.proposals.max(1))
+            .map(|_| {
+                let candidate = self.propose(&walker.state, length, ell, rng);
+                let e = evaluator.evaluate(&ideal(&candidate));
+                let effective = walker.effective(&candidate, e);
+                (candidate, e, effective)
+            })
+            .min_by(|a, b| a.2.partial_cmp(&b.2).unwrap_or(std::cmp::Ordering::Equal));
+
+        let current = walker.effective_current(walker.energy);
+        let result = match best {
+            Some((candidate, e, effective)) if effective < current => {
+                walker.advance(candidate, e);
+                StepResult::Improved(e)
+            }
+            _ => StepResult::Stagnated,
+        };
+        walker.steps += 1;
+        result
+    }
+}
+
+/// 原始-对偶增广拉格朗日 (Paraconsistent ALM)
+///
+/// 一步 = 一个 ALM epoch：
+/// 1. Primal：在固定的 (λ, ξ) 下做 inner_steps 次单生成元提案，由接受准则裁决
+/// 2. Slack：软阈值更新 ξ (妥协)
+/// 3. Dual：λ += ρ ∘ (C(S) - ξ) (审判)
+/// 4. 原始/对偶残差的停止检验，与残差平衡的 ρ_i 调整
+///
+/// 目标 E_obj 与约束残差 C(S) 分别来自 `Evaluator::objective` 与 `Evaluator::residuals`。
+pub struct PrimalDual {
+    alm: AlmConfig,
+    penalties: TierPenalties,
+    /// 声明的公理，按残差顺序
+    axioms: Vec<Axiom>,
+    acceptance: AcceptanceRule,
+    seed: u64,
+
+    // State Variables (sized on the first step)
+    resolved: Vec<Axiom>,
+    mu: Vec<f64>,           // Mu_i (Compromise cost of each axiom's tier)
+    rho: Vec<f64>,          // Rho_i (Per-constraint Penalty Stiffness)
+    multipliers: Vec<f64>,  // Lambda (Shadow Prices)
+    slacks: Vec<f64>,       // Xi (Allowed Violations)
+    history: Vec<AlmEpoch>, // Rho / Lambda / Xi after every epoch
+    acceptor: Option<Acceptor>,
+}
+
+impl PrimalDual {
+    /// # 错误
+    /// 接受准则的参数不合法时返回 `AcceptanceError`
+    pub fn new(
+        alm: AlmConfig,
+        penalties: TierPenalties,
+        axioms: Vec<Axiom>,
+        acceptance: AcceptanceRule,
+        seed: u64,
+    ) -> Result<Self, AcceptanceError> {
+        acceptance.validate()?;
+        Ok(Self {
+            alm,
+            penalties,
+            axioms,
+            acceptance,
+            seed,
+            resolved: Vec::new(),
+            mu: Vec::new(),
+            rho: Vec::new(),
+            multipliers: Vec::new(),
+            slacks: Vec::new(),
+            history: Vec::new(),
+            acceptor: None,
+        })
+    }
+
+    /// 每个残差一条公理：先是声明的公理，其余记为软约束 `axiom[i]`
+    fn resolve_axioms(&self, num_constraints: usize) -> Vec<Axiom> {
+        let mut axioms: Vec<Axiom> = self.axioms.iter().take(num_constraints).cloned().collect();
+        for i in axioms.len()..num_constraints {
+            axioms.push(Axiom::unnamed(i));
+        }
+        axioms
+    }
+
+    fn initialize(&mut self, num_constraints: usize) {
+        self.resolved = self.resolve_axioms(num_constraints);
+        self.mu = self.resolved.iter().map(|a| self.penalties.mu(a.tier)).collect();
+        self.rho = vec![self.alm.rho_init.clamp(self.alm.rho_min, self.alm.rho_max); num_constraints];
+        self.multipliers = vec![0.0; num_constraints];
+        self.slacks = vec![0.0; num_constraints];
+        self.history.clear();
+        self.acceptor = Some(Acceptor::new(self.acceptance.clone(), self.seed).expect("validated in PrimalDual::new"));
+    }
+
+    fn hamiltonian(&self, state: &AlgebraicState, evaluator: &dyn Evaluator) -> HamiltonianState {
+        let ideal = ideal(state);
+        StpBridge::lagrangian(
+            evaluator.objective(&ideal),
+            evaluator.residuals(&ideal),
+            &self.multipliers,
+            &self.slacks,
+            &self.rho,
+            &self.mu,
+        )
+    }
+}
+
+impl SearchStrategy for PrimalDual {
+    fn name(&self) -> &'static str {
+        "paraconsistent"
+    }
+
+    fn step(&mut self, walker: &mut Walker, evaluator: &dyn Evaluator, rng: &mut EvolverRng) -> StepResult {
+        if self.acceptor.is_none() {
+            self.initialize(evaluator.residuals(&walker.ideal()).len());
+        }
+        let num_constraints = self.rho.len();
+        let epoch = walker.steps;
+
+        // --- Step 1: Primal Update (The Will) ---
+        // Minimize L(S, fixed_lambda, fixed_xi) for S
+        let mut moved = false;
+        let mut current_h = self.hamiltonian(&walker.state, evaluator);
+        let initial_energy = walker.effective_current(current_h.total_energy);
+        for _ in 0..self.alm.inner_steps {
+            let candidate = walker.state.random_walk(1, rng);
+            let candidate_h = self.hamiltonian(&candidate, evaluator);
+            // Both sides carry the same path integral: J_eff against J_eff
+            let current_energy = walker.effective_current(current_h.total_energy);
+            let candidate_energy = walker.effective(&candidate, candidate_h.total_energy);
+
+            // Metropolis / threshold acceptance or Greedy descent
+            let acceptor = self.acceptor.as_mut().expect("initialized above");
+            if acceptor.accept(current_energy, candidate_energy) {
+                walker.advance(candidate, candidate_h.geometric_energy);
+                moved = true;
+
+                // Early exit if perfect (all constraints hold)
+                let residuals = &candidate_h.raw_residuals;
+                if !residuals.is_empty() && residuals.iter().all(|&r| r < 1e-6) {
+                    walker.steps += 1;
+                    return StepResult::Converged(walker.trace.clone());
+                }
+                current_h = candidate_h;
+            }
+        }
+
+        // Get the state after Primal optimization
+        let h_star = self.hamiltonian(&walker.state, evaluator);
+        let previous_slacks = self.slacks.clone();
+
+        // --- Step 2: Slack Update (The Compromise) ---
+        // xi = relu( C(S) + lambda/rho - mu/rho ):
+        // if the violation pressure is higher than the compromise cost (mu), we yield.
+        for i in 0..num_constraints {
+            let input = h_star.raw_residuals[i] + self.multipliers[i] / self.rho[i];
+            let threshold = self.mu[i] / self.rho[i];
+            self.slacks[i] = (input - threshold).max(0.0);
+        }
+
+        // --- Step 3: Dual Update (The Judgment) ---
+        // lambda = lambda + rho * (C(S) - xi)
+        let mut primal = vec![0.0; num_constraints];
+        let mut dual = vec![0.0; num_constraints];
+        for i in 0..num_constraints {
+            let c_val = h_star.raw_residuals[i];
+            let xi_val = self.slacks[i];
+            self.multipliers[i] += self.rho[i] * (c_val - xi_val);
+
+            primal[i] = (c_val - xi_val).abs();
+            dual[i] = self.rho[i] * (xi_val - previous_slacks[i]).abs();
+        }
+
+        let primal_residual = primal.iter().cloned().fold(0.0, f64::max);
+        let dual_residual = dual.iter().cloned().fold(0.0, f64::max);
+        self.history.push(AlmEpoch {
+            epoch,
+            rho: self.rho.clone(),
+            multipliers: self.multipliers.clone(),
+            slacks: self.slacks.clone(),
+            primal_residual,
+            dual_residual,
+        });
+        walker.steps += 1;
+
+        // --- Step 4: Stopping Test (The Verdict) ---
+        // A saddle point: the primal step is stationary and both residuals are below tolerance
+        if !moved && primal_residual <= self.alm.primal_tolerance && dual_residual <= self.alm.dual_tolerance {
+            return StepResult::Converged(walker.trace.clone());
+        }
+
+        // --- Step 5: Residual Balancing (The Stiffness) ---
+        // Stiffen constraints that are ignored, soften those whose slack keeps oscillating.
+        for i in 0..num_constraints {
+            if primal[i] > self.alm.balance * dual[i] {
+                self.rho[i] *= self.alm.rho_factor;
+            } else if dual[i] > self.alm.balance * primal[i] {
+                self.rho[i] /= self.alm.rho_factor;
+            }
+            self.rho[i] = self.rho[i].clamp(self.alm.rho_min, self.alm.rho_max);
+        }
+
+        // Uphill moves accepted by Metropolis / Threshold are not improvements
+        if moved && walker.effective_current(current_h.total_energy) < initial_energy {
+            StepResult::Improved(walker.energy)
+        } else if moved {
+            StepResult::Accepted(walker.energy)
+        } else {
+            StepResult::Stagnated
+        }
+    }
+
+    fn contradictions(&self) -> Option<ContradictionReport> {
+        Some(ContradictionReport::new(&self.resolved, &self.slacks, &self.multipliers))
+    }
+
+    fn history(&self) -> &[AlmEpoch] {
+        &self.history
+    }
+
+    fn acceptance_stats(&self) -> Option<&AcceptanceStats> {
+        self.acceptor.as_ref().map(|a| a.stats())
+    }
+}
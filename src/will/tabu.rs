@@ -0,0 +1,149 @@
+// Copyright (c) 2025 M-Patek
+// Part of the Evolver Project
+//
+// "Those who cannot remember the past are condemned to walk it again."
+
+use std::collections::{BTreeSet, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+use crate::soul::hecke::HeckeMove;
+
+/// 候选者的禁忌属性
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TabuKey {
+    /// 规范状态哈希 (见 `AlgebraicState::canonical_hash`)
+    pub state: u64,
+    /// 产生该候选者的移动 (路径为空时为 None)
+    pub mv: Option<HeckeMove>,
+}
+
+/// 禁忌记忆 (Tabu Memory)
+///
+/// 两个有界的 LRU 记忆：
+/// - 最近访问的规范状态：防止行者在同几个状态之间来回震荡。
+/// - 最近使用的生成元：防止沿同一个方向反复螺旋。
+///
+/// 特赦准则 (Aspiration)：若禁忌候选者的能量严格低于历史最低能量，则无视禁忌。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TabuMemory {
+    state_tenure: usize,
+    move_tenure: usize,
+    states: VecDeque<u64>,
+    /// 有序集合：序列化结果与插入历史无关 (检查点逐位一致)
+    state_set: BTreeSet<u64>,
+    moves: VecDeque<HeckeMove>,
+    best_energy: f64,
+}
+
+impl TabuMemory {
+    pub fn new(state_tenure: usize, move_tenure: usize) -> Self {
+        Self {
+            state_tenure,
+            move_tenure,
+            states: VecDeque::with_capacity(state_tenure),
+            state_set: BTreeSet::new(),
+            moves: VecDeque::with_capacity(move_tenure),
+            best_energy: f64::INFINITY,
+        }
+    }
+
+    /// 历史最低能量 (特赦阈值)
+    pub fn best_energy(&self) -> f64 {
+        self.best_energy
+    }
+
+    pub fn is_tabu(&self, key: &TabuKey) -> bool {
+        self.state_set.contains(&key.state)
+            || key.mv.is_some_and(|mv| self.moves.contains(&mv))
+    }
+
+    /// 候选者是否可以被选择：不在禁忌中，或满足特赦准则
+    pub fn admits(&self, key: &TabuKey, energy: f64) -> bool {
+        !self.is_tabu(key) || energy < self.best_energy
+    }
+
+    /// 记录一次访问 (刷新 LRU 位置)
+    pub fn visit(&mut self, key: TabuKey, energy: f64) {
+        self.best_energy = self.best_energy.min(energy);
+
+        if self.state_tenure > 0 {
+            if !self.state_set.insert(key.state) {
+                self.states.retain(|&h| h != key.state);
+            }
+            self.states.push_back(key.state);
+            while self.states.len() > self.state_tenure {
+                if let Some(old) = self.states.pop_front() {
+                    self.state_set.remove(&old);
+                }
+            }
+        }
+
+        if let Some(mv) = key.mv {
+            if self.move_tenure > 0 {
+                self.moves.retain(|&m| m != mv);
+                self.moves.push_back(mv);
+                while self.moves.len() > self.move_tenure {
+                    self.moves.pop_front();
+                }
+            }
+        }
+    }
+
+    /// 遗忘全部禁忌 (例如迁移到新宇宙之后)，保留历史最低能量
+    pub fn clear(&mut self) {
+        self.states.clear();
+        self.state_set.clear();
+        self.moves.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(state: u64, mv: Option<HeckeMove>) -> TabuKey {
+        TabuKey { state, mv }
+    }
+
+    #[test]
+    fn test_revisit_rejected_within_tenure() {
+        let mut tabu = TabuMemory::new(2, 0);
+        tabu.visit(key(1, None), 5.0);
+        tabu.visit(key(2, None), 4.0);
+
+        // 状态 1 仍在任期内：不优于历史最低能量时被拒绝
+        assert!(!tabu.admits(&key(1, None), 4.5));
+
+        // 第三次访问把状态 1 挤出 LRU
+        tabu.visit(key(3, None), 4.0);
+        assert!(tabu.admits(&key(1, None), 4.5));
+        assert!(!tabu.admits(&key(2, None), 4.5));
+    }
+
+    #[test]
+    fn test_recent_moves_are_tabu() {
+        let mv = HeckeMove::new(3, 1);
+        let mut tabu = TabuMemory::new(0, 1);
+        tabu.visit(key(1, Some(mv)), 2.0);
+
+        assert!(tabu.is_tabu(&key(9, Some(mv))));
+        assert!(!tabu.is_tabu(&key(9, Some(HeckeMove::new(5, 0)))));
+
+        tabu.visit(key(2, Some(HeckeMove::new(5, 0))), 2.0);
+        assert!(!tabu.is_tabu(&key(9, Some(mv))));
+    }
+
+    #[test]
+    fn test_aspiration_overrides_tabu() {
+        let mut tabu = TabuMemory::new(4, 0);
+        tabu.visit(key(1, None), 3.0);
+
+        assert!(!tabu.admits(&key(1, None), 3.0));
+        assert!(tabu.admits(&key(1, None), 2.9));
+
+        tabu.clear();
+        assert!(!tabu.is_tabu(&key(1, None)));
+        assert_eq!(tabu.best_energy(), 3.0);
+    }
+}
@@ -1,39 +1,192 @@
-use crate::soul::algebra::Quaternion;
+use serde::{Deserialize, Serialize};
+
+use crate::soul::algebra::{AlgebraicState, Quaternion};
+use crate::soul::hecke::HeckeMove;
+use crate::soul::lifter::{LiftConfig, StateLifter};
+use crate::will::restart::RestartReason;
+
+/// 状态转移 (Transition)
+///
+/// 行者的每一次位置变化。同一宇宙内的状态共享原点，由 (p, origin, path_history) 唯一确定，
+/// 因此宇宙内的转移都可以写成 "沿路径回退 rewind 步，再依次施加若干生成元"。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Transition {
+    /// 沿一个 Hecke 生成元前进一步
+    HeckeStep { generator: HeckeMove },
+    /// 波前内的换位 (波束模式下另一条路径的状态接替行者)：回退 rewind 步后施加 generators
+    Jump { rewind: usize, generators: Vec<HeckeMove> },
+    /// 宇宙迁移：以 lift_params 重新执行 `StateLifter::lift_and_requantize(state, p_new)`
+    Migration { p_old: u64, p_new: u64, lift_params: LiftConfig },
+    /// 重启：回退 rewind 步、沿 approach 到达游走起点，再走记录的随机游走 walk
+    Restart { reason: RestartReason, rewind: usize, approach: Vec<HeckeMove>, walk: Vec<HeckeMove> },
+}
+
+impl Transition {
+    /// 同一宇宙内从 `from` 到 `to` 的转移：单步前进记为 `HeckeStep`，其余记为 `Jump`。
+    /// 两者相同时返回 None。
+    pub fn between(from: &AlgebraicState, to: &AlgebraicState) -> Option<Self> {
+        debug_assert!(from.p == to.p && from.origin == to.origin, "transition must stay inside one universe");
+        let (rewind, generators) = divergence(from, to);
+        match (rewind, generators.as_slice()) {
+            (0, []) => None,
+            (0, [generator]) => Some(Transition::HeckeStep { generator: *generator }),
+            _ => Some(Transition::Jump { rewind, generators }),
+        }
+    }
+
+    /// 从 `origin` 出发的重启游走，表示为相对当前状态 `from` 的转移
+    pub fn restart(reason: RestartReason, from: &AlgebraicState, origin: &AlgebraicState, walk: Vec<HeckeMove>) -> Self {
+        let (rewind, approach) = divergence(from, origin);
+        Transition::Restart { reason, rewind, approach, walk }
+    }
+
+    /// 在 `state` 上重放该转移。生成元在当前宇宙中不可用时返回 None。
+    pub fn apply(&self, state: &AlgebraicState) -> Option<AlgebraicState> {
+        match self {
+            Transition::HeckeStep { generator } => state.apply_move(generator),
+            Transition::Jump { rewind, generators } => advance(&rewound(state, *rewind)?, generators),
+            Transition::Migration { p_new, lift_params, .. } => {
+                let (lifted, _) = StateLifter::with_config(lift_params.clone()).lift_and_requantize(state, *p_new);
+                Some(lifted)
+            }
+            Transition::Restart { rewind, approach, walk, .. } => {
+                advance(&advance(&rewound(state, *rewind)?, approach)?, walk)
+            }
+        }
+    }
+}
+
+/// 两条路径的分叉：(`from` 需要回退的步数, `to` 在公共前缀之后的生成元)
+fn divergence(from: &AlgebraicState, to: &AlgebraicState) -> (usize, Vec<HeckeMove>) {
+    let common = from
+        .path_history
+        .iter()
+        .zip(&to.path_history)
+        .take_while(|(a, b)| a == b)
+        .count();
+    (from.path_history.len() - common, to.path_history[common..].to_vec())
+}
+
+/// 沿路径回退：以截断后的路径从原点重建状态 (回退后的状态仍是图上的顶点)
+fn rewound(state: &AlgebraicState, rewind: usize) -> Option<AlgebraicState> {
+    let keep = state.path_history.len().checked_sub(rewind)?;
+    Some(state.algebra().replay_from(state.origin, &state.path_history[..keep]))
+}
+
+fn advance(state: &AlgebraicState, generators: &[HeckeMove]) -> Option<AlgebraicState> {
+    generators.iter().try_fold(state.clone(), |s, mv| s.apply_move(mv))
+}
+
+/// 追踪记录中的一条
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TraceEntry {
+    pub epoch: usize,
+    pub transition: Transition,
+    /// 转移之后行者的几何能量
+    pub energy: f64,
+}
 
 /// 追踪器 (Tracer)
 /// 负责记录“意志”的决策路径。
-/// 在本体论重构后，Trace 不再是状态的快照，而是 **因果算子 (Operators)** 的序列。
-/// 
-/// 验证逻辑： FinalState = InitialState * q1 * q2 * ... * qn
-#[derive(Debug, Clone)]
+/// Trace 不是状态的快照，而是 **因果转移 (Transitions)** 的类型化序列：
+/// Hecke 步、波前换位、重启游走与宇宙迁移都被记录在内。
+///
+/// 验证逻辑： FinalState = replay(Anchored(p_0, origin), t_1, t_2, ..., t_n)
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Trace {
-    /// 记录每一步施加的 Hecke 算子
-    pub path: Vec<Quaternion>,
-    /// 记录路径的总能量变化（用于分析收敛性）
-    pub energy_log: Vec<f64>,
+    /// 起始宇宙
+    pub initial_p: u64,
+    /// 重放的起点：宇宙的根 (单位元)，或由上下文哈希得到的种子格点
+    pub origin: Quaternion,
+    /// 产生该记录的运行种子
+    pub seed: u64,
+    pub entries: Vec<TraceEntry>,
 }
 
 impl Trace {
-    pub fn new() -> Self {
+    /// 从宇宙 p 的根出发的记录
+    pub fn new(initial_p: u64, seed: u64) -> Self {
+        Self::anchored(initial_p, Quaternion::identity(), seed)
+    }
+
+    /// 从任意格点出发的记录 (见 `AlgebraicState::anchored`)
+    pub fn anchored(initial_p: u64, origin: Quaternion, seed: u64) -> Self {
         Self {
-            path: Vec::new(),
-            energy_log: Vec::new(),
+            initial_p,
+            origin,
+            seed,
+            entries: Vec::new(),
         }
     }
 
-    /// 记录一步移动
-    pub fn record(&mut self, operator: Quaternion, energy: f64) {
-        self.path.push(operator);
-        self.energy_log.push(energy);
+    /// 记录一次转移
+    pub fn record(&mut self, epoch: usize, transition: Transition, energy: f64) {
+        self.entries.push(TraceEntry { epoch, transition, energy });
     }
 
-    /// 获取路径长度（即因果链的深度）
+    /// 获取记录长度（即因果链的深度）
     pub fn len(&self) -> usize {
-        self.path.len()
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 每次转移之后的能量序列（用于分析收敛性）
+    pub fn energy_log(&self) -> Vec<f64> {
+        self.entries.iter().map(|e| e.energy).collect()
+    }
+
+    /// 从起点出发重放全部转移，得到最终状态。
+    /// 重放不需要随机数：重启游走与迁移目标都已记录在案。
+    pub fn replay(&self) -> Option<AlgebraicState> {
+        self.entries
+            .iter()
+            .try_fold(AlgebraicState::anchored(self.initial_p, self.origin), |state, entry| entry.transition.apply(&state))
     }
 
-    /// 导出为简单的算子列表，用于 Proof Bundle
-    pub fn to_proof_sequence(&self) -> Vec<Quaternion> {
-        self.path.clone()
+    /// 验证记录是否精确复现了给定的最终状态
+    pub fn verify(&self, state: &AlgebraicState) -> bool {
+        self.replay().is_some_and(|replayed| &replayed == state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replay_reproduces_a_hand_built_migration() {
+        let lift_params = LiftConfig { beam_width: 4, max_steps: 5, probe_radius: 3, ..LiftConfig::default() };
+        let step = HeckeMove::new(3, 0);
+        let before = AlgebraicState::new_root(37).apply_move(&step).unwrap();
+        let (lifted, _) = StateLifter::with_config(lift_params.clone()).lift_and_requantize(&before, 41);
+        let after = lifted.apply_move(&HeckeMove::new(5, 1)).unwrap();
+
+        let mut trace = Trace::new(37, 0);
+        trace.record(1, Transition::HeckeStep { generator: step }, 1.0);
+        trace.record(2, Transition::Migration { p_old: 37, p_new: 41, lift_params }, 0.5);
+        trace.record(3, Transition::between(&lifted, &after).unwrap(), 0.25);
+
+        assert_eq!(trace.energy_log(), vec![1.0, 0.5, 0.25]);
+        assert!(trace.verify(&after));
+        assert!(!trace.verify(&lifted));
+    }
+
+    #[test]
+    fn test_transition_between_states() {
+        let root = AlgebraicState::new_root(37);
+        let a = root.apply_move(&HeckeMove::new(3, 0)).unwrap();
+        let b = root.apply_move(&HeckeMove::new(5, 1)).unwrap();
+
+        assert_eq!(Transition::between(&root, &root), None);
+        assert_eq!(
+            Transition::between(&root, &a),
+            Some(Transition::HeckeStep { generator: HeckeMove::new(3, 0) })
+        );
+        let jump = Transition::between(&a, &b).unwrap();
+        assert_eq!(jump, Transition::Jump { rewind: 1, generators: vec![HeckeMove::new(5, 1)] });
+        assert_eq!(jump.apply(&a), Some(b));
     }
 }